@@ -0,0 +1,50 @@
+use crate::api::range_state::get_shared_date_range;
+use crate::model::aggregation::{AggregateBucket, ExpenseKind, Mode};
+use crate::model::response::Response;
+use crate::service::aggregation_service::aggregate;
+use crate::store::app_store::ExpenseStore;
+use tauri::State;
+
+/// Groups the expenses of the shared date range into day, week, month or year buckets,
+/// optionally split by tag, with the total, count, average and median of each bucket.
+///
+/// `kind` selects expenses (default), income, savings or all, transfers always excluded.
+#[tauri::command]
+pub fn aggregate_expenses(
+    expense_store_state: State<'_, ExpenseStore>,
+    mode: Mode,
+    by_tag: Option<bool>,
+    kind: Option<ExpenseKind>,
+) -> Response {
+    let kind = kind.unwrap_or_default();
+
+    let expenses = match get_shared_date_range() {
+        Some((start, end)) => expense_store_state.get_expenses_between(start, end),
+        None => expense_store_state.get_all_expenses(),
+    };
+
+    let expenses = match expenses {
+        Ok(expenses) => expenses,
+        Err(e) => {
+            return Response::err(
+                format!("Failed to load expenses: {}", e),
+                Option::<Vec<AggregateBucket>>::None,
+            )
+        }
+    };
+
+    match aggregate(
+        expenses.iter().filter(|expense| kind.includes(expense)),
+        mode,
+        by_tag.unwrap_or(false),
+    ) {
+        Ok(buckets) => Response::ok(
+            format!("Aggregated into {} buckets", buckets.len()),
+            buckets,
+        ),
+        Err(e) => Response::err(
+            format!("Failed to aggregate expenses: {}", e),
+            Option::<Vec<AggregateBucket>>::None,
+        ),
+    }
+}
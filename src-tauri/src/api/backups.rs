@@ -0,0 +1,146 @@
+use crate::api::events::notify_store_changed;
+use crate::model::backup::{BackupReason, RetentionPolicy};
+use crate::model::response::{Response, Status};
+use crate::store::app_store::ExpenseStore;
+use crate::store::backup::BackupManager;
+use crate::store::settings::{StoreSettings, STORE_SETTINGS_FILE};
+use serde_json::Value;
+use std::error::Error as StdError;
+use tauri::{AppHandle, Manager, State};
+
+/// Snapshots the current store before an operation that overwrites it
+pub fn snapshot_store(
+    expense_store: &ExpenseStore,
+    backups: &BackupManager,
+    reason: BackupReason,
+) -> Result<(), Box<dyn StdError>> {
+    let store_data = expense_store.get_all_store_data()?;
+    backups
+        .create_snapshot(&store_data, reason)
+        .map_err(|err| format!("Failed to back up the store: {}", err))?;
+    Ok(())
+}
+
+/// Lists the automatic snapshots of the store, newest first
+#[tauri::command]
+pub fn list_backups(backups: State<'_, BackupManager>) -> Response {
+    match backups.inner().list() {
+        Ok(list) => Response::ok("Backups retrieved".to_string(), Some(list)),
+        Err(e) => Response::err(
+            format!("Failed to list backups: {}", e),
+            Option::<Value>::None,
+        ),
+    }
+}
+
+/// Summary of a snapshot (keys, expense count and date range) without restoring it
+#[tauri::command]
+pub fn inspect_backup(backups: State<'_, BackupManager>, id: String) -> Response {
+    match backups.inner().inspect(&id) {
+        Ok(details) => Response::ok("Backup inspected".to_string(), Some(details)),
+        Err(e) => Response::new(
+            Status::NotFound,
+            format!("Failed to inspect backup: {}", e),
+            Option::<Value>::None,
+        ),
+    }
+}
+
+/// Replaces the whole store with a snapshot.
+/// The current store is snapshotted first, so a restore can itself be restored away.
+#[tauri::command]
+pub fn restore_backup(
+    app_handle: AppHandle,
+    expense_store_state: State<'_, ExpenseStore>,
+    backups: State<'_, BackupManager>,
+    id: String,
+) -> Response {
+    let payload = match backups.inner().load(&id) {
+        Ok(payload) => payload,
+        Err(e) => {
+            return Response::new(
+                Status::NotFound,
+                format!("Failed to read backup: {}", e),
+                Option::<Value>::None,
+            )
+        }
+    };
+
+    let expense_store = expense_store_state.inner();
+    let previous_keys: Vec<String> = match expense_store.get_all_store_data() {
+        Ok(Value::Object(obj)) => obj.keys().cloned().collect(),
+        Ok(_) => Vec::new(),
+        Err(e) => {
+            return Response::err(
+                format!("Failed to read the store: {}", e),
+                Option::<Value>::None,
+            )
+        }
+    };
+
+    if let Err(e) = snapshot_store(expense_store, backups.inner(), BackupReason::Restore) {
+        return Response::err(e.to_string(), Option::<Value>::None);
+    }
+
+    let restored_keys: Vec<String> = payload
+        .data
+        .as_object()
+        .map(|obj| obj.keys().cloned().collect())
+        .unwrap_or_default();
+
+    if let Err(e) = expense_store.replace_all_data(payload.data) {
+        return Response::err(
+            format!("Failed to restore backup: {}", e),
+            Option::<Value>::None,
+        );
+    }
+
+    for key in previous_keys.iter().chain(restored_keys.iter()) {
+        notify_store_changed(&app_handle, key);
+    }
+
+    Response::ok("Backup restored".to_string(), Some(id))
+}
+
+#[tauri::command]
+pub fn get_backup_retention(backups: State<'_, BackupManager>) -> Response {
+    match backups.inner().retention() {
+        Ok(retention) => Response::ok("Backup retention retrieved".to_string(), Some(retention)),
+        Err(e) => Response::err(
+            format!("Failed to get backup retention: {}", e),
+            Option::<Value>::None,
+        ),
+    }
+}
+
+/// Saves the retention policy in the store settings and prunes the snapshots right away
+#[tauri::command]
+pub fn set_backup_retention(
+    app_handle: AppHandle,
+    backups: State<'_, BackupManager>,
+    retention: RetentionPolicy,
+) -> Response {
+    let settings_path = match app_handle.path().app_data_dir() {
+        Ok(dir) => dir.join(STORE_SETTINGS_FILE),
+        Err(e) => {
+            return Response::err(
+                format!("Failed to resolve app data directory: {}", e),
+                Option::<Value>::None,
+            )
+        }
+    };
+
+    let result = StoreSettings::load(&settings_path).and_then(|mut settings| {
+        settings.backup_retention = retention;
+        settings.save(&settings_path)?;
+        backups.inner().set_retention(retention)
+    });
+
+    match result {
+        Ok(_) => Response::ok("Backup retention saved".to_string(), Some(retention)),
+        Err(e) => Response::err(
+            format!("Failed to save backup retention: {}", e),
+            Option::<Value>::None,
+        ),
+    }
+}
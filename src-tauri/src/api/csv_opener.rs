@@ -1,14 +1,18 @@
 use std::fs;
 
+use crate::api::backups::snapshot_store;
 use crate::api::events::notify_store_changed;
 use crate::definition::csv_definition::{
     CsvDefinition, CsvDefinitionKey, CsvParser, CSV_DEFINITIONS,
 };
 use crate::definition::dynamic_csv_definition::DynamicCsvDefinition;
+use crate::model::backup::BackupReason;
 use crate::model::expense::Expense;
 use crate::model::response::{Response, Status};
 use crate::service::csv_file_service::{open_csv_file_and_find_definitions, open_file_from_path};
 use crate::store::app_store::ExpenseStore;
+use crate::store::backup::BackupManager;
+use crate::store::transaction::ConflictError;
 use chrono::Utc;
 use serde::Serialize;
 use serde_json::{json, Value};
@@ -78,6 +82,7 @@ pub fn open_csv_from_path(file: String, custom_definitions_json: Option<String>)
 pub fn parse_csv_from_path(
     app_handle: AppHandle,
     expense_store_state: State<'_, ExpenseStore>,
+    backups: State<'_, BackupManager>,
     path: String,
     csv_definition_key: String,
     custom_definitions_json: Option<String>,
@@ -122,10 +127,19 @@ pub fn parse_csv_from_path(
         );
     };
 
+    if let Err(e) = snapshot_store(
+        expense_store_state.inner(),
+        backups.inner(),
+        BackupReason::Import,
+    ) {
+        return Response::err(e.to_string(), Option::<String>::None);
+    }
+
     match crate::service::csv_file_service::parse_csv_file_with_definition(
         expense_store_state.inner(),
         path,
         &csv_definition,
+        &csv_definition_key,
     ) {
         Ok((added_count, duplicate_count, _min_date, _max_date)) => {
             notify_store_changed(&app_handle, "expenses");
@@ -282,6 +296,13 @@ pub fn update_bulk_expenses(
             }
         }
         Err(e) => {
+            if let Some(conflict) = e.downcast_ref::<ConflictError>() {
+                return Response::new(
+                    Status::Conflict,
+                    format!("Failed to update expenses: {}", conflict),
+                    Some(conflict.ids.clone()),
+                );
+            }
             return Response::err(
                 format!("Failed to update expenses: {}", e),
                 Option::<String>::None,
@@ -314,6 +335,13 @@ pub fn update_expense(
             }
         }
         Err(e) => {
+            if let Some(conflict) = e.downcast_ref::<ConflictError>() {
+                return Response::new(
+                    Status::Conflict,
+                    format!("Failed to update expense: {}", conflict),
+                    Some(conflict.ids.clone()),
+                );
+            }
             return Response::err(
                 format!("Failed to update expense: {}", e),
                 Option::<String>::None,
@@ -0,0 +1,62 @@
+use crate::api::events::notify_store_changed;
+use crate::config::{DEFAULT_DUPLICATE_THRESHOLD, DEFAULT_DUPLICATE_WINDOW_DAYS};
+use crate::model::expense::Expense;
+use crate::model::response::Response;
+use crate::service::duplicate_service::{
+    find_probable_duplicates as find_clusters, DuplicateCluster,
+};
+use crate::store::app_store::ExpenseStore;
+use tauri::AppHandle;
+use tauri::State;
+
+/// Finds clusters of expenses from different sources that probably describe the same
+/// transaction, scored on amount equality, date proximity and description similarity.
+///
+/// `window_days` defaults to `DEFAULT_DUPLICATE_WINDOW_DAYS` and `threshold` (0 to 1)
+/// to `DEFAULT_DUPLICATE_THRESHOLD`.
+#[tauri::command]
+pub fn find_probable_duplicates(
+    expense_store_state: State<'_, ExpenseStore>,
+    window_days: Option<i64>,
+    threshold: Option<f64>,
+) -> Response {
+    let window_days = window_days.unwrap_or(DEFAULT_DUPLICATE_WINDOW_DAYS);
+    let threshold = threshold.unwrap_or(DEFAULT_DUPLICATE_THRESHOLD);
+
+    let expenses = match expense_store_state.get_all_expenses() {
+        Ok(expenses) => expenses,
+        Err(e) => {
+            return Response::err(
+                format!("Failed to load expenses: {}", e),
+                Option::<Vec<DuplicateCluster>>::None,
+            )
+        }
+    };
+
+    let clusters = find_clusters(&expenses, window_days.max(0), threshold);
+
+    Response::ok(
+        format!("Found {} groups of probable duplicates", clusters.len()),
+        clusters,
+    )
+}
+
+/// Keeps one expense of a duplicate cluster, combining the tags of the merged ones into it
+#[tauri::command]
+pub fn merge_duplicates(
+    app_handle: AppHandle,
+    expense_store_state: State<'_, ExpenseStore>,
+    keep_id: String,
+    merge_ids: Vec<String>,
+) -> Response {
+    match expense_store_state.merge_expenses(&keep_id, &merge_ids) {
+        Ok(kept) => {
+            notify_store_changed(&app_handle, "expenses");
+            Response::ok(String::from("Expenses merged successfully"), kept)
+        }
+        Err(e) => Response::err(
+            format!("Failed to merge expenses: {}", e),
+            Option::<Expense>::None,
+        ),
+    }
+}
@@ -0,0 +1,169 @@
+use crate::api::backups::snapshot_store;
+use crate::api::events::notify_store_changed;
+use crate::model::backup::BackupReason;
+use crate::model::response::{Response, Status};
+use crate::store::app_store::ExpenseStore;
+use crate::store::backup::BackupManager;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, State};
+
+/// Commands still answered while the store is locked, every other one gets `Status::Locked`
+pub const LOCK_EXEMPT_COMMANDS: &[&str] = &["get_encryption_status", "unlock_store", "lock_store"];
+
+/// Answer of any other command while the store is locked
+pub fn locked_response() -> Response {
+    Response::new(
+        Status::Locked,
+        "The store is locked, unlock it with its passphrase first".to_string(),
+        Option::<Value>::None,
+    )
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptionStatus {
+    /// False when the storage backend cannot be encrypted (SQLite)
+    pub supported: bool,
+    pub enabled: bool,
+    pub locked: bool,
+}
+
+#[tauri::command]
+pub fn get_encryption_status(expense_store_state: State<'_, ExpenseStore>) -> Response {
+    let status = match expense_store_state.inner().backend().encryption() {
+        Some(encryption) => {
+            let vault = encryption.vault();
+            EncryptionStatus {
+                supported: true,
+                enabled: vault.is_enabled(),
+                locked: vault.is_locked(),
+            }
+        }
+        None => EncryptionStatus {
+            supported: false,
+            enabled: false,
+            locked: false,
+        },
+    };
+
+    Response::ok("Encryption status retrieved".to_string(), Some(status))
+}
+
+/// Unlocks the encrypted store and takes the snapshot that could not be taken at startup
+#[tauri::command]
+pub fn unlock_store(
+    app_handle: AppHandle,
+    expense_store_state: State<'_, ExpenseStore>,
+    backups: State<'_, BackupManager>,
+    passphrase: String,
+) -> Response {
+    let expense_store = expense_store_state.inner();
+
+    if let Err(e) = expense_store.with_encryption(|encryption| encryption.unlock(&passphrase)) {
+        return Response::err(
+            format!("Failed to unlock the store: {}", e),
+            Option::<Value>::None,
+        );
+    }
+
+    if let Err(e) = snapshot_store(expense_store, backups.inner(), BackupReason::Unlock) {
+        println!("{}", e);
+    }
+
+    if let Err(e) = expense_store.migrate_schema(false) {
+        return Response::err(
+            format!("Failed to migrate the store: {}", e),
+            Option::<Value>::None,
+        );
+    }
+
+    if let Ok(Value::Object(obj)) = expense_store.get_all_store_data() {
+        for key in obj.keys() {
+            notify_store_changed(&app_handle, key);
+        }
+    }
+
+    Response::ok("Store unlocked".to_string(), Option::<Value>::None)
+}
+
+#[tauri::command]
+pub fn lock_store(expense_store_state: State<'_, ExpenseStore>) -> Response {
+    match expense_store_state
+        .inner()
+        .with_encryption(|encryption| encryption.lock())
+    {
+        Ok(_) => Response::ok("Store locked".to_string(), Option::<Value>::None),
+        Err(e) => Response::err(
+            format!("Failed to lock the store: {}", e),
+            Option::<Value>::None,
+        ),
+    }
+}
+
+/// Encrypts the store and every backup with a key derived from `passphrase`
+#[tauri::command]
+pub fn enable_encryption(
+    expense_store_state: State<'_, ExpenseStore>,
+    backups: State<'_, BackupManager>,
+    passphrase: String,
+) -> Response {
+    let result = backups.inner().reseal_all(|| {
+        expense_store_state
+            .inner()
+            .with_encryption(|encryption| encryption.enable_encryption(&passphrase))
+    });
+
+    match result {
+        Ok(_) => Response::ok("Encryption enabled".to_string(), Option::<Value>::None),
+        Err(e) => Response::err(
+            format!("Failed to enable encryption: {}", e),
+            Option::<Value>::None,
+        ),
+    }
+}
+
+/// Re-encrypts the store and every backup with a key derived from `new_passphrase`
+#[tauri::command]
+pub fn change_passphrase(
+    expense_store_state: State<'_, ExpenseStore>,
+    backups: State<'_, BackupManager>,
+    current_passphrase: String,
+    new_passphrase: String,
+) -> Response {
+    let result = backups.inner().reseal_all(|| {
+        expense_store_state.inner().with_encryption(|encryption| {
+            encryption.change_passphrase(&current_passphrase, &new_passphrase)
+        })
+    });
+
+    match result {
+        Ok(_) => Response::ok("Passphrase changed".to_string(), Option::<Value>::None),
+        Err(e) => Response::err(
+            format!("Failed to change the passphrase: {}", e),
+            Option::<Value>::None,
+        ),
+    }
+}
+
+/// Writes the store and every backup back in plain text
+#[tauri::command]
+pub fn disable_encryption(
+    expense_store_state: State<'_, ExpenseStore>,
+    backups: State<'_, BackupManager>,
+    passphrase: String,
+) -> Response {
+    let result = backups.inner().reseal_all(|| {
+        expense_store_state
+            .inner()
+            .with_encryption(|encryption| encryption.disable_encryption(&passphrase))
+    });
+
+    match result {
+        Ok(_) => Response::ok("Encryption disabled".to_string(), Option::<Value>::None),
+        Err(e) => Response::err(
+            format!("Failed to disable encryption: {}", e),
+            Option::<Value>::None,
+        ),
+    }
+}
@@ -0,0 +1,157 @@
+use crate::api::events::notify_store_changed;
+use crate::model::export::{ExportColumn, ExportFormat, ExportSummary, DEFAULT_EXPORT_COLUMNS};
+use crate::model::ledger::{JournalFormat, LedgerConfig};
+use crate::model::query::ExpenseFilter;
+use crate::model::response::Response;
+use crate::service::export_service::export_to_path;
+use crate::service::ledger_service::{
+    export_journal_to_path, parse_ledger_config, validate_ledger_config, LEDGER_CONFIG_SUB_KEY,
+};
+use crate::store::app_store::ExpenseStore;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+/// Writes the expenses matching `filter` (all of them when omitted) to `path`, oldest first.
+///
+/// Parameters:
+/// - `columns`: Columns of the plain CSV format, date, description, amount and tags by default.
+#[tauri::command]
+pub fn export_expenses(
+    expense_store_state: State<'_, ExpenseStore>,
+    path: String,
+    format: ExportFormat,
+    filter: Option<ExpenseFilter>,
+    columns: Option<Vec<ExportColumn>>,
+) -> Response {
+    let filter = filter.unwrap_or_default();
+    let expenses = match expense_store_state.inner().get_matching_expenses(&filter) {
+        Ok(expenses) => expenses,
+        Err(e) => {
+            return Response::err(
+                format!("Failed to load expenses: {}", e),
+                Option::<Value>::None,
+            )
+        }
+    };
+
+    let columns = columns.unwrap_or_else(|| DEFAULT_EXPORT_COLUMNS.to_vec());
+    match export_to_path(Path::new(&path), &expenses, format, &columns) {
+        Ok(_) => Response::ok(
+            format!("Exported {} expenses", expenses.len()),
+            Some(ExportSummary {
+                path,
+                format,
+                count: expenses.len(),
+            }),
+        ),
+        Err(e) => Response::err(
+            format!("Failed to export expenses: {}", e),
+            Option::<Value>::None,
+        ),
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalSummary {
+    pub path: String,
+    pub format: JournalFormat,
+    /// Number of exported transactions
+    pub count: usize,
+}
+
+/// Returns the account mapping of the journal export, the defaults when never saved
+#[tauri::command]
+pub fn get_ledger_config(expense_store_state: State<'_, ExpenseStore>) -> Response {
+    let config = expense_store_state
+        .inner()
+        .get_json_value(LEDGER_CONFIG_SUB_KEY)
+        .and_then(parse_ledger_config);
+
+    match config {
+        Ok(config) => Response::ok("Ledger config retrieved".to_string(), Some(config)),
+        Err(e) => Response::err(
+            format!("Failed to get ledger config: {}", e),
+            Option::<Value>::None,
+        ),
+    }
+}
+
+#[tauri::command]
+pub fn set_ledger_config(
+    app_handle: AppHandle,
+    expense_store_state: State<'_, ExpenseStore>,
+    config: LedgerConfig,
+) -> Response {
+    if let Err(e) = validate_ledger_config(&config) {
+        return Response::err(
+            format!("Invalid ledger config: {}", e),
+            Option::<Value>::None,
+        );
+    }
+
+    let result = serde_json::to_value(&config)
+        .map_err(|err| err.into())
+        .and_then(|value| {
+            expense_store_state
+                .inner()
+                .set_json_value(LEDGER_CONFIG_SUB_KEY, value)
+        });
+
+    match result {
+        Ok(_) => {
+            notify_store_changed(&app_handle, LEDGER_CONFIG_SUB_KEY);
+            Response::ok("Ledger config saved".to_string(), Some(config))
+        }
+        Err(e) => Response::err(
+            format!("Failed to save ledger config: {}", e),
+            Option::<Value>::None,
+        ),
+    }
+}
+
+/// Writes the expenses matching `filter` as a ledger, hledger or beancount journal,
+/// with the accounts of the saved ledger config.
+#[tauri::command]
+pub fn export_journal(
+    expense_store_state: State<'_, ExpenseStore>,
+    path: String,
+    format: JournalFormat,
+    filter: Option<ExpenseFilter>,
+) -> Response {
+    let expense_store = expense_store_state.inner();
+    let loaded = expense_store
+        .get_json_value(LEDGER_CONFIG_SUB_KEY)
+        .and_then(parse_ledger_config)
+        .and_then(|config| {
+            let expenses = expense_store.get_matching_expenses(&filter.unwrap_or_default())?;
+            Ok((config, expenses))
+        });
+
+    let (config, expenses) = match loaded {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            return Response::err(
+                format!("Failed to load expenses: {}", e),
+                Option::<Value>::None,
+            )
+        }
+    };
+
+    match export_journal_to_path(Path::new(&path), &expenses, format, &config) {
+        Ok(_) => Response::ok(
+            format!("Exported {} transactions", expenses.len()),
+            Some(JournalSummary {
+                path,
+                format,
+                count: expenses.len(),
+            }),
+        ),
+        Err(e) => Response::err(
+            format!("Failed to export journal: {}", e),
+            Option::<Value>::None,
+        ),
+    }
+}
@@ -0,0 +1,38 @@
+use crate::api::events::notify_store_changed;
+use crate::model::response::{Response, Status};
+use crate::store::app_store::ExpenseStore;
+use serde_json::Value;
+use tauri::{AppHandle, State};
+
+/// Recorded changes of one expense, newest first
+#[tauri::command]
+pub fn get_expense_history(expense_store_state: State<'_, ExpenseStore>, id: String) -> Response {
+    match expense_store_state.inner().get_expense_history(&id) {
+        Ok(history) => Response::ok("Expense history retrieved".to_string(), Some(history)),
+        Err(e) => Response::err(
+            format!("Failed to get expense history: {}", e),
+            Option::<Value>::None,
+        ),
+    }
+}
+
+/// Reverts an expense to its version right after the history entry `entry_id`
+#[tauri::command]
+pub fn revert_expense(
+    app_handle: AppHandle,
+    expense_store_state: State<'_, ExpenseStore>,
+    id: String,
+    entry_id: u64,
+) -> Response {
+    match expense_store_state.inner().revert_expense(&id, entry_id) {
+        Ok(expense) => {
+            notify_store_changed(&app_handle, "expenses");
+            Response::ok("Expense reverted".to_string(), expense)
+        }
+        Err(e) => Response::new(
+            Status::NotFound,
+            format!("Failed to revert expense: {}", e),
+            Option::<Value>::None,
+        ),
+    }
+}
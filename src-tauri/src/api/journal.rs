@@ -0,0 +1,91 @@
+use crate::api::backups::snapshot_store;
+use crate::api::events::notify_store_changed;
+use crate::model::backup::BackupReason;
+use crate::model::ledger::JournalImportSummary;
+use crate::model::response::{Response, Status};
+use crate::service::journal_parser::parse_journal;
+use crate::service::ledger_service::{parse_ledger_config, LEDGER_CONFIG_SUB_KEY};
+use crate::store::app_store::ExpenseStore;
+use crate::store::backup::BackupManager;
+use chrono::Utc;
+use serde_json::{json, Value};
+use std::fs;
+use tauri::{AppHandle, State};
+
+/// Imports the expense and income postings of a beancount, ledger or hledger journal.
+///
+/// Accounts are mapped back to tags and import definitions with the saved ledger config.
+/// Directives that are not supported are listed as warnings in the response, duplicates of
+/// stored expenses are ignored like on a CSV import.
+#[tauri::command]
+pub fn import_journal(
+    app_handle: AppHandle,
+    expense_store_state: State<'_, ExpenseStore>,
+    backups: State<'_, BackupManager>,
+    path: String,
+) -> Response {
+    let expense_store = expense_store_state.inner();
+
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) => {
+            return Response::err(
+                format!("Failed to read journal: {}", e),
+                Option::<Value>::None,
+            )
+        }
+    };
+    let config = match expense_store
+        .get_json_value(LEDGER_CONFIG_SUB_KEY)
+        .and_then(parse_ledger_config)
+    {
+        Ok(config) => config,
+        Err(e) => {
+            return Response::err(
+                format!("Failed to get ledger config: {}", e),
+                Option::<Value>::None,
+            )
+        }
+    };
+
+    let parsed = parse_journal(&text, &config);
+
+    if let Err(e) = snapshot_store(expense_store, backups.inner(), BackupReason::Import) {
+        return Response::err(e.to_string(), Option::<Value>::None);
+    }
+
+    let result = match expense_store.add_expense_as_batch(parsed.expenses, false) {
+        Ok(result) => result,
+        Err(e) => {
+            return Response::err(
+                format!("Failed to import journal: {}", e),
+                Option::<Value>::None,
+            )
+        }
+    };
+    notify_store_changed(&app_handle, "expenses");
+
+    let today = Utc::now().format("%Y-%m-%d").to_string();
+    let mut history: Vec<Value> = expense_store
+        .get_json_value("import_history")
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_array().cloned())
+        .unwrap_or_default();
+    history.push(json!(today));
+    let _ = expense_store.set_json_value("import_history", Value::Array(history));
+
+    Response::new(
+        Status::Created,
+        format!(
+            "Added {} entries, ignored {} duplicate entries",
+            result.added_count, result.duplicate_count
+        ),
+        JournalImportSummary {
+            transactions: parsed.transactions,
+            added_count: result.added_count,
+            duplicate_count: result.duplicate_count,
+            warnings: parsed.warnings,
+        },
+    )
+}
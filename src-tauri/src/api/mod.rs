@@ -1,5 +1,17 @@
+pub mod aggregation;
+pub mod backups;
 pub mod csv_opener;
+pub mod duplicates;
+pub mod encryption;
 pub mod events;
+pub mod export;
+pub mod history;
+pub mod journal;
+pub mod pivot;
+pub mod query;
 pub mod range_state;
 pub mod store;
+pub mod tags;
+pub mod transfers;
+pub mod undo;
 pub mod window_manager;
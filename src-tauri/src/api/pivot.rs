@@ -0,0 +1,98 @@
+use crate::api::range_state::get_shared_date_range;
+use crate::config::DEFAULT_AMOUNT_BAND_EDGES;
+use crate::model::aggregation::ExpenseKind;
+use crate::model::pivot::{PivotDimension, PivotMeasure, PivotTable};
+use crate::model::response::Response;
+use crate::service::pivot_service::{pivot, pivot_to_csv};
+use crate::store::app_store::ExpenseStore;
+use std::error::Error as StdError;
+use tauri::State;
+
+/// Builds the pivot table of the expenses of the shared date range.
+/// `band_edges` defaults to `DEFAULT_AMOUNT_BAND_EDGES`, `kind` to expenses only.
+fn build_pivot(
+    expense_store: &ExpenseStore,
+    rows: PivotDimension,
+    columns: PivotDimension,
+    measure: PivotMeasure,
+    kind: Option<ExpenseKind>,
+    band_edges: Option<Vec<f64>>,
+) -> Result<PivotTable, Box<dyn StdError>> {
+    let kind = kind.unwrap_or_default();
+    let band_edges = band_edges.unwrap_or_else(|| DEFAULT_AMOUNT_BAND_EDGES.to_vec());
+
+    let expenses = match get_shared_date_range() {
+        Some((start, end)) => expense_store.get_expenses_between(start, end)?,
+        None => expense_store.get_all_expenses()?,
+    };
+
+    pivot(
+        expenses.iter().filter(|expense| kind.includes(expense)),
+        rows,
+        columns,
+        measure,
+        &band_edges,
+    )
+}
+
+/// Ad-hoc pivot of the expenses, e.g. tag × month or weekday × tag
+#[tauri::command]
+pub fn pivot_expenses(
+    expense_store_state: State<'_, ExpenseStore>,
+    rows: PivotDimension,
+    columns: PivotDimension,
+    measure: PivotMeasure,
+    kind: Option<ExpenseKind>,
+    band_edges: Option<Vec<f64>>,
+) -> Response {
+    match build_pivot(
+        expense_store_state.inner(),
+        rows,
+        columns,
+        measure,
+        kind,
+        band_edges,
+    ) {
+        Ok(table) => Response::ok(
+            format!(
+                "Pivot of {} rows and {} columns",
+                table.row_labels.len(),
+                table.column_labels.len()
+            ),
+            table,
+        ),
+        Err(e) => Response::err(
+            format!("Failed to build pivot: {}", e),
+            Option::<PivotTable>::None,
+        ),
+    }
+}
+
+/// Same pivot as `pivot_expenses`, as CSV text ready to be saved
+#[tauri::command]
+pub fn pivot_expenses_csv(
+    expense_store_state: State<'_, ExpenseStore>,
+    rows: PivotDimension,
+    columns: PivotDimension,
+    measure: PivotMeasure,
+    kind: Option<ExpenseKind>,
+    band_edges: Option<Vec<f64>>,
+) -> Response {
+    let csv = build_pivot(
+        expense_store_state.inner(),
+        rows,
+        columns,
+        measure,
+        kind,
+        band_edges,
+    )
+    .and_then(|table| pivot_to_csv(&table));
+
+    match csv {
+        Ok(csv) => Response::ok("Pivot exported".to_string(), csv),
+        Err(e) => Response::err(
+            format!("Failed to export pivot: {}", e),
+            Option::<String>::None,
+        ),
+    }
+}
@@ -0,0 +1,60 @@
+use crate::config::DEFAULT_SEARCH_LIMIT;
+use crate::model::query::{DescriptionMatch, ExpensePage, ExpenseQuery};
+use crate::model::response::Response;
+use crate::service::search_parser::parse_search_query as parse_query;
+use crate::store::app_store::ExpenseStore;
+use tauri::State;
+
+/// Returns one page of the expenses matching the query's filter, in the requested order,
+/// along with the total count and sum of all matches.
+#[tauri::command]
+pub fn query_expenses(
+    expense_store_state: State<'_, ExpenseStore>,
+    query: ExpenseQuery,
+) -> Response {
+    match expense_store_state.query_expenses(&query) {
+        Ok(page) => Response::ok(
+            format!("Found {} matching expenses", page.total_count),
+            page,
+        ),
+        Err(e) => Response::err(
+            format!("Failed to query expenses: {}", e),
+            Option::<ExpensePage>::None,
+        ),
+    }
+}
+
+/// Checks a query in the search syntax, returning the position of the first syntax error
+/// so the search box can point at it.
+#[tauri::command]
+pub fn parse_search_query(query: String) -> Response {
+    match parse_query(&query) {
+        Ok(parsed) => Response::ok(
+            format!("Search query has {} terms", parsed.terms.len()),
+            Option::<String>::None,
+        ),
+        Err(e) => Response::err(format!("Invalid search query: {}", e), e),
+    }
+}
+
+/// Full-text search over the expense descriptions (Hebrew and English), matching words
+/// exactly, by prefix or with small typos, ranked best first.
+#[tauri::command]
+pub fn search_descriptions(
+    expense_store_state: State<'_, ExpenseStore>,
+    query: String,
+    limit: Option<usize>,
+) -> Response {
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+
+    match expense_store_state.search_descriptions(&query, limit) {
+        Ok(matches) => Response::ok(
+            format!("Found {} matching expenses", matches.len()),
+            matches,
+        ),
+        Err(e) => Response::err(
+            format!("Failed to search expenses: {}", e),
+            Option::<Vec<DescriptionMatch>>::None,
+        ),
+    }
+}
@@ -1,4 +1,5 @@
 use crate::model::response::Response;
+use chrono::{Local, NaiveDateTime, TimeZone};
 use lazy_static::lazy_static;
 use std::sync::RwLock;
 
@@ -38,3 +39,14 @@ pub fn get_date_range() -> Response {
         ),
     }
 }
+
+/// Returns the date range shared between windows as local date times, None when unset.
+/// The UI sends the range as millisecond timestamps of local dates.
+pub fn get_shared_date_range() -> Option<(NaiveDateTime, NaiveDateTime)> {
+    let (start, end) = (*SHARED_DATE_RANGE.read().ok()?)?;
+
+    let start = Local.timestamp_millis_opt(start).single()?.naive_local();
+    let end = Local.timestamp_millis_opt(end).single()?.naive_local();
+
+    Some((start, end))
+}
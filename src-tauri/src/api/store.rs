@@ -1,19 +1,21 @@
+use crate::api::backups::snapshot_store;
 use crate::api::events::notify_store_changed;
+use crate::model::backup::BackupReason;
+use crate::model::export::{ExportPayload, EXPORT_VERSION};
+use crate::model::import::ImportMode;
 use crate::model::response::{Response, Status};
+use crate::service::import_service::validate_import;
 use crate::store::app_store::ExpenseStore;
-use serde::{Deserialize, Serialize};
+use crate::store::backend::StorageBackend;
+use crate::store::backup::BackupManager;
+use crate::store::schema::migrate;
+use crate::store::settings::{BackendKind, StoreSettings, STORE_SETTINGS_FILE};
+use serde::Serialize;
 use serde_json::Value;
 use tauri::AppHandle;
+use tauri::Manager;
 use tauri::State;
 
-#[derive(Serialize, Deserialize)]
-pub struct ExportPayload {
-    pub version: u32,
-    pub data: Value,
-}
-
-const EXPORT_VERSION: u32 = 1;
-
 #[tauri::command]
 pub fn store_set_json_value(
     app_handle: AppHandle,
@@ -48,7 +50,15 @@ pub fn store_get_json_value(expense_store_state: State<'_, ExpenseStore>, key: S
 
 #[tauri::command]
 pub fn export_all_data(expense_store_state: State<'_, ExpenseStore>) -> Response {
-    let data = expense_store_state.inner().get_all_store_data();
+    let data = match expense_store_state.inner().get_all_store_data() {
+        Ok(data) => data,
+        Err(e) => {
+            return Response::err(
+                format!("Failed to export data: {}", e),
+                Option::<Value>::None,
+            )
+        }
+    };
     let payload = ExportPayload {
         version: EXPORT_VERSION,
         data,
@@ -56,11 +66,15 @@ pub fn export_all_data(expense_store_state: State<'_, ExpenseStore>) -> Response
     Response::ok("Data exported".to_string(), Some(payload))
 }
 
+/// Imports an `export_all_data` file. Known keys are validated against their schema and
+/// combined with the stored data according to `mode` (replace when omitted).
 #[tauri::command]
 pub fn import_all_data(
     app_handle: AppHandle,
     expense_store_state: State<'_, ExpenseStore>,
+    backups: State<'_, BackupManager>,
     data: Value,
+    mode: Option<ImportMode>,
 ) -> Response {
     let imported: ExportPayload = match serde_json::from_value(data) {
         Ok(v) => v,
@@ -82,23 +96,218 @@ pub fn import_all_data(
         );
     }
 
-    if let Some(obj) = imported.data.as_object() {
-        let keys: Vec<String> = obj.keys().cloned().collect();
-        for key in &keys {
-            if let Some(value) = obj.get(key) {
-                if let Err(e) = expense_store_state.inner().set_json_value(key, value.clone()) {
-                    return Response::err(
-                        format!("Failed to import key '{}': {}", key, e),
-                        Option::<Value>::None,
-                    );
-                }
+    let mut values = match imported.data {
+        Value::Object(obj) => obj,
+        _ => {
+            return Response::err(
+                "Invalid import file: data is not an object".to_string(),
+                Option::<Value>::None,
+            )
+        }
+    };
+
+    // Files exported by older versions are brought to the current layout first
+    let migration = match migrate(&mut values, false) {
+        Ok(report) => report,
+        Err(e) => {
+            return Response::err(
+                format!("Failed to migrate import file: {}", e),
+                Option::<Value>::None,
+            )
+        }
+    };
+
+    // Checked before the snapshot, an invalid file does not touch anything
+    if let Err(invalid) = validate_import(&values) {
+        return Response::err(
+            format!("Failed to import data: {}", invalid),
+            Some(invalid.errors),
+        );
+    }
+
+    if let Err(e) = snapshot_store(
+        expense_store_state.inner(),
+        backups.inner(),
+        BackupReason::ImportAll,
+    ) {
+        return Response::err(e.to_string(), Option::<Value>::None);
+    }
+
+    // All keys are written in one commit, a failure leaves the store untouched
+    match expense_store_state
+        .inner()
+        .import_data(values, mode.unwrap_or_default())
+    {
+        Ok(report) => {
+            let keys = report.changed_keys();
+            for key in &keys {
                 notify_store_changed(&app_handle, key);
             }
+            Response::ok(
+                "Data imported successfully".to_string(),
+                Some(serde_json::json!({
+                    "imported_keys": keys,
+                    "report": report,
+                    "migration": migration
+                })),
+            )
+        }
+        Err(e) => Response::err(
+            format!("Failed to import data: {}", e),
+            Option::<Value>::None,
+        ),
+    }
+}
+
+/// Migrates the store to the current schema version.
+/// With `dry_run` nothing is written and the report tells what each migration would change.
+#[tauri::command]
+pub fn migrate_store(
+    app_handle: AppHandle,
+    expense_store_state: State<'_, ExpenseStore>,
+    dry_run: bool,
+) -> Response {
+    match expense_store_state.inner().migrate_schema(dry_run) {
+        Ok(report) => {
+            if !dry_run {
+                for key in &report.keys {
+                    notify_store_changed(&app_handle, key);
+                }
+            }
+            Response::ok("Store migration report".to_string(), Some(report))
+        }
+        Err(e) => Response::err(
+            format!("Failed to migrate the store: {}", e),
+            Option::<Value>::None,
+        ),
+    }
+}
+
+/// Reports the migrations an import file would go through, without importing it
+#[tauri::command]
+pub fn check_import_migrations(data: Value) -> Response {
+    let imported: ExportPayload = match serde_json::from_value(data) {
+        Ok(v) => v,
+        Err(e) => {
+            return Response::err(
+                format!("Invalid import file: {}", e),
+                Option::<Value>::None,
+            )
+        }
+    };
+
+    let mut values = match imported.data {
+        Value::Object(obj) => obj,
+        _ => {
+            return Response::err(
+                "Invalid import file: data is not an object".to_string(),
+                Option::<Value>::None,
+            )
+        }
+    };
+
+    match migrate(&mut values, true) {
+        Ok(report) => Response::ok("Import migration report".to_string(), Some(report)),
+        Err(e) => Response::err(
+            format!("Failed to migrate import file: {}", e),
+            Option::<Value>::None,
+        ),
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageBackendInfo {
+    pub active: String,
+    pub selected: BackendKind,
+}
+
+/// Returns the backend in use and the one selected for the next start
+#[tauri::command]
+pub fn get_storage_backend(
+    app_handle: AppHandle,
+    expense_store_state: State<'_, ExpenseStore>,
+) -> Response {
+    let settings_path = match app_handle.path().app_data_dir() {
+        Ok(dir) => dir.join(STORE_SETTINGS_FILE),
+        Err(e) => {
+            return Response::err(
+                format!("Failed to resolve app data directory: {}", e),
+                Option::<Value>::None,
+            )
         }
+    };
+
+    match StoreSettings::load(&settings_path) {
+        Ok(settings) => Response::ok(
+            "Storage backend retrieved".to_string(),
+            Some(StorageBackendInfo {
+                active: expense_store_state.inner().backend().name().to_string(),
+                selected: settings.backend,
+            }),
+        ),
+        Err(e) => Response::err(
+            format!("Failed to read store settings: {}", e),
+            Option::<Value>::None,
+        ),
+    }
+}
+
+/// Selects the storage backend used from the next start of the app.
+/// On that start the current data is copied into the selected backend, in either direction.
+/// SQLite stores data unencrypted, so it is refused while the JSON store is encrypted.
+#[tauri::command]
+pub fn set_storage_backend(
+    app_handle: AppHandle,
+    expense_store_state: State<'_, ExpenseStore>,
+    backend: BackendKind,
+) -> Response {
+    let encrypted = expense_store_state
+        .inner()
+        .backend()
+        .encryption()
+        .is_some_and(|encryption| encryption.vault().is_enabled());
+    if backend == BackendKind::Sqlite && encrypted {
+        return Response::err(
+            "The SQLite backend stores data unencrypted, disable encryption before switching to it"
+                .to_string(),
+            Option::<Value>::None,
+        );
     }
 
-    Response::ok(
-        "Data imported successfully".to_string(),
-        Some(serde_json::json!({ "imported_keys": imported.data.as_object().map(|o| o.keys().cloned().collect::<Vec<_>>()).unwrap_or_default() })),
-    )
+    let settings_path = match app_handle.path().app_data_dir() {
+        Ok(dir) => dir.join(STORE_SETTINGS_FILE),
+        Err(e) => {
+            return Response::err(
+                format!("Failed to resolve app data directory: {}", e),
+                Option::<Value>::None,
+            )
+        }
+    };
+
+    let mut settings = match StoreSettings::load(&settings_path) {
+        Ok(settings) => settings,
+        Err(e) => {
+            return Response::err(
+                format!("Failed to read store settings: {}", e),
+                Option::<Value>::None,
+            )
+        }
+    };
+    settings.backend = backend;
+
+    let message = match backend {
+        BackendKind::Json => "Storage backend saved, restart the app to apply it",
+        BackendKind::Sqlite => {
+            "Storage backend saved, restart the app to apply it. The SQLite backend stores data unencrypted"
+        }
+    };
+
+    match settings.save(&settings_path) {
+        Ok(_) => Response::ok(message.to_string(), Some(backend)),
+        Err(e) => Response::err(
+            format!("Failed to save store settings: {}", e),
+            Option::<Value>::None,
+        ),
+    }
 }
@@ -0,0 +1,87 @@
+use crate::api::events::notify_store_changed;
+use crate::model::response::{Response, Status};
+use crate::model::tag::TagSummary;
+use crate::store::app_store::ExpenseStore;
+use std::error::Error as StdError;
+use tauri::AppHandle;
+use tauri::State;
+
+/// Every tag in use with its number of expenses and their total amount
+#[tauri::command]
+pub fn list_tags(expense_store_state: State<'_, ExpenseStore>) -> Response {
+    match expense_store_state.list_tags() {
+        Ok(tags) => Response::ok(String::from("Tags retrieved"), tags),
+        Err(e) => Response::err(
+            format!("Failed to get tags: {}", e),
+            Option::<Vec<TagSummary>>::None,
+        ),
+    }
+}
+
+#[tauri::command]
+pub fn rename_tag(
+    app_handle: AppHandle,
+    expense_store_state: State<'_, ExpenseStore>,
+    from: String,
+    to: String,
+) -> Response {
+    tag_change_response(
+        &app_handle,
+        expense_store_state.rename_tag(&from, &to),
+        "rename tag",
+    )
+}
+
+/// Replaces every tag of `sources` by `target`, on all expenses at once
+#[tauri::command]
+pub fn merge_tags(
+    app_handle: AppHandle,
+    expense_store_state: State<'_, ExpenseStore>,
+    sources: Vec<String>,
+    target: String,
+) -> Response {
+    tag_change_response(
+        &app_handle,
+        expense_store_state.merge_tags(&sources, &target),
+        "merge tags",
+    )
+}
+
+/// Removes a tag from all expenses, putting `replacement` in its place when given
+#[tauri::command]
+pub fn delete_tag(
+    app_handle: AppHandle,
+    expense_store_state: State<'_, ExpenseStore>,
+    tag: String,
+    replacement: Option<String>,
+) -> Response {
+    tag_change_response(
+        &app_handle,
+        expense_store_state.delete_tag(&tag, replacement.as_deref()),
+        "delete tag",
+    )
+}
+
+/// Number of changed expenses, NotFound when no expense had the tag
+fn tag_change_response(
+    app_handle: &AppHandle,
+    result: Result<usize, Box<dyn StdError>>,
+    action: &str,
+) -> Response {
+    match result {
+        Ok(0) => Response::new(
+            Status::NotFound,
+            String::from("No expense has this tag"),
+            Option::<usize>::None,
+        ),
+        Ok(count) => {
+            notify_store_changed(app_handle, "expenses");
+            notify_store_changed(app_handle, "ledger_config");
+            Response::ok(format!("Updated the tags of {} expenses", count), count)
+        }
+        Err(e) => Response::err(
+            format!("Failed to {}: {}", action, e),
+            Option::<usize>::None,
+        ),
+    }
+}
@@ -0,0 +1,122 @@
+use crate::api::events::notify_store_changed;
+use crate::config::DEFAULT_TRANSFER_WINDOW_DAYS;
+use crate::model::response::{Response, Status};
+use crate::model::transfer::TransferPair;
+use crate::store::app_store::ExpenseStore;
+use tauri::AppHandle;
+use tauri::State;
+
+#[tauri::command]
+pub fn list_transfers(expense_store_state: State<'_, ExpenseStore>) -> Response {
+    match expense_store_state.get_transfers() {
+        Ok(transfers) => Response::ok(String::from("Transfers retrieved"), transfers),
+        Err(e) => Response::err(
+            format!("Failed to get transfers: {}", e),
+            Option::<Vec<TransferPair>>::None,
+        ),
+    }
+}
+
+/// Finds opposite-signed, equal-magnitude expenses from different sources and stores them
+/// as suggested transfer pairs. Expenses without a recorded source, like everything stored
+/// before sources existed, are treated as an unknown source that may pair with any other.
+///
+/// `window_days` is the maximum number of days between both legs, defaulting to
+/// `DEFAULT_TRANSFER_WINDOW_DAYS`.
+#[tauri::command]
+pub fn detect_transfers(
+    app_handle: AppHandle,
+    expense_store_state: State<'_, ExpenseStore>,
+    window_days: Option<i64>,
+) -> Response {
+    let window_days = window_days.unwrap_or(DEFAULT_TRANSFER_WINDOW_DAYS);
+
+    if window_days < 0 {
+        return Response::err(
+            String::from("Transfer window must not be negative"),
+            Option::<Vec<TransferPair>>::None,
+        );
+    }
+
+    match expense_store_state.detect_transfers(window_days) {
+        Ok(suggestions) => {
+            notify_store_changed(&app_handle, "transfers");
+            Response::ok(
+                format!("Found {} possible transfers", suggestions.len()),
+                suggestions,
+            )
+        }
+        Err(e) => Response::err(
+            format!("Failed to detect transfers: {}", e),
+            Option::<Vec<TransferPair>>::None,
+        ),
+    }
+}
+
+#[tauri::command]
+pub fn confirm_transfer(
+    app_handle: AppHandle,
+    expense_store_state: State<'_, ExpenseStore>,
+    id: String,
+) -> Response {
+    match expense_store_state.confirm_transfer(&id) {
+        Ok(true) => {
+            notify_store_changed(&app_handle, "transfers");
+            notify_store_changed(&app_handle, "expenses");
+            Response::ok(String::from("Transfer confirmed"), Option::<String>::None)
+        }
+        Ok(false) => Response::new(
+            Status::NotFound,
+            String::from("Transfer not found"),
+            Option::<String>::None,
+        ),
+        Err(e) => Response::err(
+            format!("Failed to confirm transfer: {}", e),
+            Option::<String>::None,
+        ),
+    }
+}
+
+#[tauri::command]
+pub fn reject_transfer(
+    app_handle: AppHandle,
+    expense_store_state: State<'_, ExpenseStore>,
+    id: String,
+) -> Response {
+    match expense_store_state.reject_transfer(&id) {
+        Ok(true) => {
+            notify_store_changed(&app_handle, "transfers");
+            notify_store_changed(&app_handle, "expenses");
+            Response::ok(String::from("Transfer rejected"), Option::<String>::None)
+        }
+        Ok(false) => Response::new(
+            Status::NotFound,
+            String::from("Transfer not found"),
+            Option::<String>::None,
+        ),
+        Err(e) => Response::err(
+            format!("Failed to reject transfer: {}", e),
+            Option::<String>::None,
+        ),
+    }
+}
+
+#[tauri::command]
+pub fn link_transfer(
+    app_handle: AppHandle,
+    expense_store_state: State<'_, ExpenseStore>,
+    first_id: String,
+    second_id: String,
+) -> Response {
+    match expense_store_state.link_transfer(&first_id, &second_id) {
+        Ok(pair) => {
+            notify_store_changed(&app_handle, "transfers");
+            notify_store_changed(&app_handle, "expenses");
+            Response::new(Status::Created, String::from("Transfer linked"), pair)
+        }
+        Err(e) => Response::err(
+            format!("Failed to link transfer: {}", e),
+            Option::<TransferPair>::None,
+        ),
+    }
+}
@@ -0,0 +1,60 @@
+use crate::api::events::notify_store_changed;
+use crate::model::response::{Response, Status};
+use crate::model::undo::UndoResult;
+use crate::store::app_store::ExpenseStore;
+use crate::store::transaction::ConflictError;
+use serde_json::Value;
+use std::error::Error as StdError;
+use tauri::{AppHandle, State};
+
+fn undo_response(
+    app_handle: &AppHandle,
+    action: &str,
+    result: Result<Option<UndoResult>, Box<dyn StdError>>,
+) -> Response {
+    match result {
+        Ok(Some(result)) => {
+            for key in result.keys.iter() {
+                notify_store_changed(app_handle, key);
+            }
+            Response::ok(format!("{} done", action), Some(result))
+        }
+        Ok(None) => Response::new(
+            Status::NotFound,
+            format!("Nothing to {}", action.to_lowercase()),
+            Option::<Value>::None,
+        ),
+        Err(e) => match e.downcast_ref::<ConflictError>() {
+            Some(conflict) => Response::new(
+                Status::Conflict,
+                format!("{} failed: {}", action, e),
+                Some(conflict.ids.clone()),
+            ),
+            None => Response::err(format!("{} failed: {}", action, e), Option::<Value>::None),
+        },
+    }
+}
+
+/// Reverts every change of the last undoable command
+#[tauri::command]
+pub fn undo(app_handle: AppHandle, expense_store_state: State<'_, ExpenseStore>) -> Response {
+    undo_response(&app_handle, "Undo", expense_store_state.inner().undo())
+}
+
+/// Applies again the changes of the last undone command
+#[tauri::command]
+pub fn redo(app_handle: AppHandle, expense_store_state: State<'_, ExpenseStore>) -> Response {
+    undo_response(&app_handle, "Redo", expense_store_state.inner().redo())
+}
+
+/// Commands the next undo and redo would apply to
+#[tauri::command]
+pub fn get_undo_state(expense_store_state: State<'_, ExpenseStore>) -> Response {
+    match expense_store_state.inner().undo_state() {
+        Ok(state) => Response::ok("Undo state retrieved".to_string(), Some(state)),
+        Err(e) => Response::err(
+            format!("Failed to get undo state: {}", e),
+            Option::<Value>::None,
+        ),
+    }
+}
@@ -1 +1,35 @@
 pub const WINDOW_TITLE: &str = "Expense Tracker";
+
+/// Default number of days between the two legs of a transfer for them to be paired
+pub const DEFAULT_TRANSFER_WINDOW_DAYS: i64 = 5;
+
+/// Default number of days between two expenses for them to be probable duplicates
+pub const DEFAULT_DUPLICATE_WINDOW_DAYS: i64 = 3;
+
+/// Default minimum score (0 to 1) for two expenses to be reported as probable duplicates
+pub const DEFAULT_DUPLICATE_THRESHOLD: f64 = 0.75;
+
+/// Default maximum number of results of the description search
+pub const DEFAULT_SEARCH_LIMIT: usize = 50;
+
+/// Default edges of the amount bands of the pivot table
+pub const DEFAULT_AMOUNT_BAND_EDGES: [f64; 5] = [10.0, 50.0, 100.0, 500.0, 1000.0];
+
+/// Default number of daily, weekly and monthly store snapshots kept in the backups directory
+pub const DEFAULT_BACKUP_DAILY: usize = 7;
+pub const DEFAULT_BACKUP_WEEKLY: usize = 4;
+pub const DEFAULT_BACKUP_MONTHLY: usize = 12;
+
+/// Minimum length of the passphrase of an encrypted store
+pub const MIN_PASSPHRASE_LENGTH: usize = 8;
+
+/// Bounds of the expense audit log, the oldest entries beyond either one are dropped
+pub const DEFAULT_AUDIT_MAX_ENTRIES: usize = 10_000;
+pub const DEFAULT_AUDIT_MAX_AGE_DAYS: i64 = 365;
+/// Commits changing more expenses than this (imports) are logged as bulk entries, bounded
+/// on their own so they never evict the history of manual edits
+pub const DEFAULT_AUDIT_BULK_COMMIT_SIZE: usize = 50;
+pub const DEFAULT_AUDIT_MAX_BULK_ENTRIES: usize = 10_000;
+
+/// Maximum number of command invocations kept on the undo stack
+pub const DEFAULT_UNDO_LIMIT: usize = 100;
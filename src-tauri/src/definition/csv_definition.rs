@@ -1,4 +1,5 @@
 use crate::model::expense::Expense;
+use crate::model::money::{self, Money};
 use chrono::{NaiveDate, NaiveDateTime};
 use csv::StringRecord;
 use mockall::automock;
@@ -96,15 +97,15 @@ impl CsvColumnRole {
             (CsvColumnRole::Description, ParsedValue::String(description)) => {
                 expense.set_description(&description);
             }
-            (CsvColumnRole::Amount, ParsedValue::Float(mut total_amount)) => {
+            (CsvColumnRole::Amount, ParsedValue::Amount(mut total_amount)) => {
                 // Special handling for optional amount in conjunction with credit column
                 if !current_column_info.is_required {
                     if let Some(credit_column_info) = meta_data_columns.get(&CsvColumnRole::CreditAmount) {
                         credit_column_info.clone().with_temporary_required_state(|credit_info| {
                             if let Ok(Some(credit_str)) = Self::get_and_normalize(CsvColumnRole::CreditAmount, &string_record, &credit_info) {
-                                if let Ok(ParsedValue::Float(credit)) = cast_raw_value(&credit_str, &credit_info) {
+                                if let Ok(ParsedValue::Amount(Some(credit))) = cast_raw_value(&credit_str, &credit_info) {
                                     // Credit is treated as a negative amount (no negative sign here because credit amount is inversed)
-                                    total_amount = credit;
+                                    total_amount = Some(credit);
                                 }
                             }
                         });
@@ -119,12 +120,12 @@ impl CsvColumnRole {
                             if let Some(query) = credit_column_info.args_to_check.get(&Arg::CreditDebitQuery) {
                                     if let ArgValue::String(query_str) = query {
                                         if &credit_str == query_str{
-                                            total_amount = -total_amount;
+                                            total_amount = total_amount.map(|amount| -amount);
                                         }
                                     }
                                     else if let ArgValue::StringVA(query_strs) = query {
                                         if query_strs.contains(&credit_str) {
-                                            total_amount = -total_amount;
+                                            total_amount = total_amount.map(|amount| -amount);
                                         }
                                     }
                                 }
@@ -133,16 +134,21 @@ impl CsvColumnRole {
                     }
                 }
 
-                // Guard amount cannot be zero after processing
-                if !current_column_info.is_required && total_amount.is_nan() {
-                    return Err("Amount encountered as NaN, something went horribly wrong".into());
-                }
+                // Guard an optional amount was replaced by the credit amount
+                let mut total_amount = match total_amount {
+                    Some(amount) => amount,
+                    None => return Err("Amount is empty and no credit amount replaced it".into()),
+                };
 
                 // If the amount is in Shekel, divide it by the exchange rate.
                 // This will be overwritten by Currency column by priority order iteration.
                 if let Some(ArgValue::Currency(currency)) = current_column_info.args_to_check.get(&Arg::AmountDefaultCurrency) {
                     if currency == &Currency::Shekel {
-                        total_amount = total_amount / SHEKEL_TO_DOLLAR_DIVISION;
+                        // The shekels were read as plain units, converted in agorot and rounded once to cents
+                        let shekels = Money::from_minor(total_amount.minor_units(), money::Currency::Ils);
+                        total_amount = Money::from_major(SHEKEL_TO_DOLLAR_DIVISION, money::Currency::Ils)
+                            .and_then(|dollar_price| shekels.convert(money::Currency::STORE, dollar_price))
+                            .ok_or("Shekel amount is too large to convert")?;
                     }
                 }
 
@@ -171,7 +177,7 @@ impl CsvColumnRole {
                                 second_amount_column_definition,
                             )? {
                                 // Parse the second amount value
-                                if let Ok(ParsedValue::Float(second_amount)) = cast_raw_value(
+                                if let Ok(ParsedValue::Amount(Some(second_amount))) = cast_raw_value(
                                     &second_amount_str,
                                     &second_amount_column_definition,
                                 ) {
@@ -736,7 +742,8 @@ pub fn build_definitions() -> HashMap<CsvDefinitionKey, CsvDefinition> {
 #[derive(Debug, PartialEq)]
 pub enum ParsedValue {
     String(String),
-    Float(f64),
+    // None when an optional amount is empty or not a number
+    Amount(Option<Money>),
     Date(chrono::NaiveDateTime),
 }
 
@@ -758,18 +765,15 @@ pub fn cast_raw_value(
             let mut parsed;
 
             if col_info.is_required {
-                parsed = value.parse::<f64>()?;
+                parsed = Some(Money::parse(value, money::Currency::STORE)?);
             } else {
-                parsed = value.parse::<f64>().unwrap_or(f64::NAN);
+                parsed = Money::parse(value, money::Currency::STORE).ok();
             }
 
-            if parsed.is_infinite() {
-                return Err("Overflow: value is too large to be represented as f64".into());
-            }
             if !*is_standard {
-                parsed = -parsed;
+                parsed = parsed.map(|amount| -amount);
             }
-            Ok(ParsedValue::Float(parsed))
+            Ok(ParsedValue::Amount(parsed))
         }
         CsvColumnDataType::DateObject(format) => {
             let date = NaiveDate::parse_from_str(value, format)?
@@ -1,15 +1,46 @@
+use model::backup::BackupReason;
 use store::app_store::ExpenseStore;
-use tauri::Manager;
-use tauri_plugin_store::StoreExt;
+use store::command_context::run_as_command;
+use store::backup::{BackupManager, BACKUPS_DIR};
+use store::settings::{StoreSettings, STORE_SETTINGS_FILE};
+use tauri::ipc::Invoke;
+use tauri::{Manager, Wry};
 
 mod api;
 mod config;
 pub mod definition;
-mod model;
+pub mod model;
 pub mod service;
-mod store;
+pub mod store;
 mod utils;
 
+/// Wraps the command handler so that, while the encrypted store is locked, every command
+/// but the encryption ones is answered with `Status::Locked` without running.
+/// Otherwise the command runs under its name, recorded in the audit log of its changes.
+fn guard_locked_store(
+    handler: impl Fn(Invoke<Wry>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<Wry>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let command = invoke.message.command().to_string();
+        if !api::encryption::LOCK_EXEMPT_COMMANDS.contains(&command.as_str()) {
+            let locked = invoke
+                .message
+                .webview_ref()
+                .try_state::<ExpenseStore>()
+                .is_some_and(|store| store.is_locked());
+
+            if locked {
+                invoke
+                    .resolver
+                    .resolve(api::encryption::locked_response());
+                return true;
+            }
+        }
+
+        run_as_command(&command, || handler(invoke))
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -17,13 +48,41 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::default().build())
         .setup(|app| {
-            // Load the store
-            let store = app
-                .store("store.json")
-                .map_err(|e| format!("Failed to load store: {}", e))?;
+            // Initialize the store for backend, with the storage backend selected in the settings
+            let app_data_dir = app
+                .path()
+                .app_data_dir()
+                .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+            let expense_store = ExpenseStore::open_selected_backend(&app_data_dir)
+                .map_err(|e| format!("Failed to open expense store: {}", e))?;
+
+            // Snapshot the store on every start, a failed backup must not prevent starting
+            let settings = StoreSettings::load(&app_data_dir.join(STORE_SETTINGS_FILE))
+                .unwrap_or_default();
+            let mut backups =
+                BackupManager::new(app_data_dir.join(BACKUPS_DIR), settings.backup_retention);
+            if let Some(encryption) = expense_store.backend().encryption() {
+                backups = backups.with_vault(encryption.vault());
+            }
+            // An encrypted store is snapshotted and migrated once unlocked instead
+            if !expense_store.is_locked() {
+                if let Err(e) =
+                    api::backups::snapshot_store(&expense_store, &backups, BackupReason::Startup)
+                {
+                    println!("{}", e);
+                }
 
-            // Initialize the store for backend
-            let expense_store = ExpenseStore::new(store);
+                // Bring the data of older versions to the current layout, after the snapshot
+                let report = expense_store
+                    .migrate_schema(false)
+                    .map_err(|e| format!("Failed to migrate the store: {}", e))?;
+                for step in report.steps {
+                    println!(
+                        "Migrated store to schema version {}: {}",
+                        step.version, step.description
+                    );
+                }
+            }
 
             // Get main window via AppHandle hello world
             let app_handle = app.handle();
@@ -38,14 +97,24 @@ pub fn run() {
 
             // Make the store available to commands
             app.manage(expense_store);
+            app.manage(backups);
 
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(guard_locked_store(tauri::generate_handler![
             api::store::store_set_json_value,
             api::store::store_get_json_value,
             api::store::export_all_data,
             api::store::import_all_data,
+            api::store::migrate_store,
+            api::store::check_import_migrations,
+            api::export::export_expenses,
+            api::export::get_ledger_config,
+            api::export::set_ledger_config,
+            api::export::export_journal,
+            api::journal::import_journal,
+            api::store::get_storage_backend,
+            api::store::set_storage_backend,
             api::window_manager::new_window,
             api::range_state::set_date_range,
             api::range_state::get_date_range,
@@ -60,7 +129,40 @@ pub fn run() {
             api::csv_opener::read_text_file,
             api::csv_opener::read_csv_preview,
             api::csv_opener::preview_csv_parse,
-        ])
+            api::transfers::list_transfers,
+            api::transfers::detect_transfers,
+            api::transfers::confirm_transfer,
+            api::transfers::reject_transfer,
+            api::transfers::link_transfer,
+            api::duplicates::find_probable_duplicates,
+            api::duplicates::merge_duplicates,
+            api::tags::list_tags,
+            api::tags::rename_tag,
+            api::tags::merge_tags,
+            api::tags::delete_tag,
+            api::query::query_expenses,
+            api::query::parse_search_query,
+            api::query::search_descriptions,
+            api::aggregation::aggregate_expenses,
+            api::pivot::pivot_expenses,
+            api::pivot::pivot_expenses_csv,
+            api::backups::list_backups,
+            api::backups::inspect_backup,
+            api::backups::restore_backup,
+            api::backups::get_backup_retention,
+            api::backups::set_backup_retention,
+            api::encryption::get_encryption_status,
+            api::encryption::unlock_store,
+            api::encryption::lock_store,
+            api::encryption::enable_encryption,
+            api::encryption::change_passphrase,
+            api::encryption::disable_encryption,
+            api::history::get_expense_history,
+            api::history::revert_expense,
+            api::undo::undo,
+            api::undo::redo,
+            api::undo::get_undo_state,
+        ]))
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
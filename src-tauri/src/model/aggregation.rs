@@ -0,0 +1,63 @@
+use crate::model::expense::Expense;
+use crate::model::money::Money;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Tags the UI treats as non-spending (NonExpenseTags)
+pub const INCOME_TAG: &str = "Income";
+pub const SAVINGS_TAG: &str = "Savings";
+
+/// Bucket name of expenses without any tag, as in the UI's byTag
+pub const UNTAGGED: &str = "Untagged";
+
+/// Time bucket size, named like the UI's `Mode` enum
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Mode {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// Which expenses are aggregated, following the useExpenses / useIncome / useSavings hooks
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExpenseKind {
+    // Neither income, savings nor a transfer
+    #[default]
+    Expenses,
+    Income,
+    Savings,
+    // Everything except transfers
+    All,
+}
+
+impl ExpenseKind {
+    pub fn includes(&self, expense: &Expense) -> bool {
+        let tags = expense.get_tags();
+        let is_income = tags.iter().any(|tag| tag == INCOME_TAG);
+        let is_savings = tags.iter().any(|tag| tag == SAVINGS_TAG);
+
+        match self {
+            ExpenseKind::Expenses => !is_income && !is_savings && !expense.is_transfer(),
+            ExpenseKind::Income => is_income && !expense.is_transfer(),
+            ExpenseKind::Savings => is_savings && !expense.is_transfer(),
+            ExpenseKind::All => !expense.is_transfer(),
+        }
+    }
+}
+
+/// Totals of one time bucket, optionally of a single tag
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregateBucket {
+    // "2025-03-14", "2025-W11", "2025-03" or "2025"
+    pub key: String,
+    pub start: NaiveDate,
+    pub tag: Option<String>,
+    pub total: Money,
+    pub count: usize,
+    pub average: Money,
+    pub median: Money,
+}
@@ -0,0 +1,33 @@
+use crate::model::expense::Expense;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+}
+
+/// One change of one expense, as recorded in the audit log
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    /// Increasing sequence number of the entry
+    pub id: u64,
+    pub expense_id: String,
+    /// UTC time of the commit
+    pub timestamp: NaiveDateTime,
+    /// Command that made the change
+    pub command: String,
+    /// True when the commit changed many expenses at once (e.g. an import),
+    /// such entries are bounded apart from the others
+    #[serde(default)]
+    pub bulk: bool,
+    pub action: AuditAction,
+    /// Expense before the change, None when it was created
+    pub before: Option<Expense>,
+    /// Expense after the change, None when it was deleted
+    pub after: Option<Expense>,
+}
@@ -0,0 +1,92 @@
+use crate::config::{DEFAULT_BACKUP_DAILY, DEFAULT_BACKUP_MONTHLY, DEFAULT_BACKUP_WEEKLY};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// What triggered a snapshot, also part of its file name
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupReason {
+    Startup,
+    /// Before a CSV import
+    Import,
+    /// Before `import_all_data`
+    ImportAll,
+    /// Before a snapshot is restored, so the restore itself can be undone
+    Restore,
+    /// After the encrypted store was unlocked, it cannot be read at startup
+    Unlock,
+}
+
+impl BackupReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BackupReason::Startup => "startup",
+            BackupReason::Import => "import",
+            BackupReason::ImportAll => "import-all",
+            BackupReason::Restore => "restore",
+            BackupReason::Unlock => "unlock",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "startup" => Some(BackupReason::Startup),
+            "import" => Some(BackupReason::Import),
+            "import-all" => Some(BackupReason::ImportAll),
+            "restore" => Some(BackupReason::Restore),
+            "unlock" => Some(BackupReason::Unlock),
+            _ => None,
+        }
+    }
+}
+
+/// How many snapshots are kept: the latest one of each of the last `daily` days,
+/// `weekly` ISO weeks and `monthly` months that have a snapshot. The newest snapshot is
+/// always kept.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPolicy {
+    pub daily: usize,
+    pub weekly: usize,
+    pub monthly: usize,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        RetentionPolicy {
+            daily: DEFAULT_BACKUP_DAILY,
+            weekly: DEFAULT_BACKUP_WEEKLY,
+            monthly: DEFAULT_BACKUP_MONTHLY,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    /// File name of the snapshot, used to inspect or restore it
+    pub id: String,
+    /// UTC time the snapshot was taken
+    pub created_at: NaiveDateTime,
+    pub reason: BackupReason,
+    pub size_bytes: u64,
+}
+
+/// Number of entries of one store_data sub-key in a snapshot
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupKeySummary {
+    pub key: String,
+    pub entries: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupDetails {
+    pub info: BackupInfo,
+    pub version: u32,
+    pub keys: Vec<BackupKeySummary>,
+    pub expense_count: usize,
+    pub first_expense_date: Option<NaiveDateTime>,
+    pub last_expense_date: Option<NaiveDateTime>,
+}
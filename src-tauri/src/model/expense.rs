@@ -1,35 +1,59 @@
+use crate::model::money::Money;
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Expense {
+    // Opaque id given once by the store, kept across every edit
     id: String,
+    // Hash of the imported content (description, date, amount and occurrence), only used to
+    // recognize the same row on a later import. Empty for manual entries.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    fingerprint: String,
     description: String,
-    amount: f64,
+    // Positive for spending, negative for income, in the store currency
+    amount: Money,
     tags: Vec<String>,
     date: NaiveDateTime,
+    // Key of the definition the expense was imported with ("NavyFederal", custom id, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    // Id of the confirmed transfer pair this expense belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    transfer_id: Option<String>,
+    // Bumped by the store on every write, used to reject edits made on a stale copy
+    #[serde(default)]
+    revision: u64,
 }
 
 impl Default for Expense {
     fn default() -> Self {
         return Expense {
             id: String::default(),
+            fingerprint: String::default(),
             description: String::default(),
-            amount: f64::default(),
+            amount: Money::default(),
             tags: Vec::default(),
             date: NaiveDateTime::default(),
+            source: None,
+            transfer_id: None,
+            revision: 0,
         };
     }
 }
 
 impl Expense {
-    pub fn new(description: String, amount: f64, date: NaiveDateTime) -> Self {
+    pub fn new(description: String, amount: impl Into<Money>, date: NaiveDateTime) -> Self {
         return Expense {
             id: String::from("0"),
+            fingerprint: String::new(),
             description: description,
-            amount: amount,
+            amount: amount.into(),
             tags: Vec::default(),
             date: date,
+            source: None,
+            transfer_id: None,
+            revision: 0,
         };
     }
 
@@ -37,10 +61,18 @@ impl Expense {
         self.tags.push(tag.to_string());
     }
 
+    pub fn set_tags(&mut self, tags: Vec<String>) {
+        self.tags = tags;
+    }
+
     pub fn set_id(&mut self, id: &str) {
         self.id = id.to_string();
     }
 
+    pub fn set_fingerprint(&mut self, fingerprint: &str) {
+        self.fingerprint = fingerprint.to_string();
+    }
+
     pub fn set_date(&mut self, date: NaiveDateTime) {
         self.date = date;
     }
@@ -49,14 +81,39 @@ impl Expense {
         self.description = description.to_string();
     }
 
-    pub fn set_amount(&mut self, amount: f64) {
-        self.amount = amount;
+    pub fn set_amount(&mut self, amount: impl Into<Money>) {
+        self.amount = amount.into();
+    }
+
+    pub fn set_source(&mut self, source: &str) {
+        self.source = Some(source.to_string());
+    }
+
+    pub fn set_transfer_id(&mut self, transfer_id: Option<String>) {
+        self.transfer_id = transfer_id;
+    }
+
+    pub fn set_revision(&mut self, revision: u64) {
+        self.revision = revision;
+    }
+
+    /// Copies the fields owned by the backend (id, fingerprint, source, transfer link) from the
+    /// stored version, so that edits coming from the UI never drop or change them.
+    pub fn keep_backend_fields_from(&mut self, stored: &Expense) {
+        self.id = stored.id.clone();
+        self.fingerprint = stored.fingerprint.clone();
+        self.source = stored.source.clone();
+        self.transfer_id = stored.transfer_id.clone();
     }
 
     pub fn get_id(&self) -> &str {
         &self.id
     }
 
+    pub fn get_fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
     pub fn get_description(&self) -> &str {
         &self.description
     }
@@ -65,11 +122,34 @@ impl Expense {
         &self.date
     }
 
-    pub fn get_amount(&self) -> f64 {
+    pub fn get_amount(&self) -> Money {
         self.amount
     }
 
     pub fn get_tags(&self) -> &Vec<String> {
         &self.tags
     }
+
+    pub fn get_source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// True only when both sources are known and equal. An expense without a source (entered
+    /// manually, or stored before sources were recorded) may come from any account.
+    pub fn has_same_source_as(&self, other: &Expense) -> bool {
+        matches!((self.get_source(), other.get_source()), (Some(a), Some(b)) if a == b)
+    }
+
+    pub fn get_transfer_id(&self) -> Option<&str> {
+        self.transfer_id.as_deref()
+    }
+
+    pub fn get_revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Confirmed transfers move money between accounts and are neither spending nor income
+    pub fn is_transfer(&self) -> bool {
+        self.transfer_id.is_some()
+    }
 }
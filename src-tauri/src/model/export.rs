@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Current version of the export file format
+pub const EXPORT_VERSION: u32 = 1;
+
+/// Full store export, as written by `export_all_data` and the automatic backups
+#[derive(Serialize, Deserialize)]
+pub struct ExportPayload {
+    pub version: u32,
+    pub data: Value,
+}
+
+/// File formats of `export_expenses`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    /// CSV read back by the built-in "Expense Tracker Backup" definition
+    ExpenseTrackerBackup,
+    /// CSV with the chosen columns
+    Csv,
+    /// List of the expenses as stored
+    Json,
+    /// Bank statement for finance software (OFX 1.0.2)
+    Ofx,
+    /// Quicken interchange format, bank account
+    Qif,
+}
+
+/// Columns of the plain CSV export
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportColumn {
+    Id,
+    Date,
+    Description,
+    Amount,
+    Tags,
+    Source,
+    TransferId,
+}
+
+/// Columns of the plain CSV export when none are chosen
+pub const DEFAULT_EXPORT_COLUMNS: &[ExportColumn] = &[
+    ExportColumn::Date,
+    ExportColumn::Description,
+    ExportColumn::Amount,
+    ExportColumn::Tags,
+];
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSummary {
+    pub path: String,
+    pub format: ExportFormat,
+    /// Number of exported expenses
+    pub count: usize,
+}
@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Typed layout of the store_data sub-keys written by the frontend, used to validate imports.
+// Dates are kept as the strings the frontend writes.
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Stock {
+    pub id: String,
+    pub ticker: String,
+    pub current_price: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VestingFrequency {
+    Monthly,
+    Quarterly,
+    SemiAnnual,
+    Annual,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VestingSchedule {
+    pub start_date: String,
+    pub total_years: u32,
+    pub frequency: VestingFrequency,
+    /// Share of the grant vesting each year
+    pub distribution: Vec<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Grant {
+    pub id: String,
+    pub name: String,
+    pub stock_id: String,
+    pub grant_price: f64,
+    pub total_shares: f64,
+    pub vesting_schedule: Option<VestingSchedule>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RsuVest {
+    pub id: String,
+    pub grant_id: String,
+    pub vest_date: String,
+    pub shares: f64,
+    pub basis_price: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Sale {
+    pub id: String,
+    pub stock_id: String,
+    pub date: String,
+    pub shares: f64,
+    pub sale_price: f64,
+    pub basis_price: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BalanceKind {
+    Asset,
+    Debt,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceSnapshot {
+    pub id: String,
+    pub account_name: String,
+    pub date: String,
+    pub balance: f64,
+    pub notes: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: Option<BalanceKind>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomeStream {
+    pub name: Option<String>,
+    pub amount: f64,
+    pub pay_period: String,
+    pub first_paycheck_date: String,
+    pub semimonthly_payday1: u32,
+    pub semimonthly_payday2: u32,
+    pub end_date: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringExpense {
+    pub name: Option<String>,
+    /// Day of the month
+    pub day: u32,
+    pub amount: f64,
+    pub period: Option<String>,
+    pub first_date: Option<String>,
+    pub end_date: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForecastConfig {
+    pub start_balance: f64,
+    pub reserve: f64,
+    pub start_date: String,
+    pub end_date: String,
+    pub income_streams: Vec<IncomeStream>,
+    pub expenses: Vec<RecurringExpense>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SsdiPayPeriod {
+    pub id: String,
+    pub begin_date: String,
+    pub end_date: String,
+    pub deposit_expense_id: String,
+    pub gross_earnings: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SsdiConfig {
+    pub year: i32,
+    /// Substantial gainful activity limit per year
+    pub sga_by_year: BTreeMap<i32, f64>,
+}
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+
+/// How imported store_data sub-keys are combined with the stored ones
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportMode {
+    /// Every imported key replaces the stored one as a whole
+    #[default]
+    Replace,
+    /// Imported records replace the stored records with the same id, the others are kept
+    Merge,
+    /// Only records (or whole keys) missing from the store are added
+    OnlyMissing,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyImportStatus {
+    Imported,
+    /// Valid, but the store already held the same data
+    Unchanged,
+    /// Not imported, see the message
+    Skipped,
+}
+
+/// What the import did to one store_data sub-key. Keys that are not a collection of
+/// records count as a single record.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyImportReport {
+    pub key: String,
+    pub status: KeyImportStatus,
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+    /// Records left out because the store already had them (only-missing mode)
+    pub skipped: usize,
+    pub message: Option<String>,
+}
+
+impl KeyImportReport {
+    pub fn new(key: &str) -> Self {
+        KeyImportReport {
+            key: key.to_string(),
+            status: KeyImportStatus::Unchanged,
+            added: 0,
+            updated: 0,
+            removed: 0,
+            unchanged: 0,
+            skipped: 0,
+            message: None,
+        }
+    }
+
+    pub fn skipped(key: &str, message: &str) -> Self {
+        KeyImportReport {
+            status: KeyImportStatus::Skipped,
+            message: Some(message.to_string()),
+            ..Self::new(key)
+        }
+    }
+
+    pub fn has_changes(&self) -> bool {
+        self.added + self.updated + self.removed > 0
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub mode: ImportMode,
+    pub keys: Vec<KeyImportReport>,
+}
+
+impl ImportReport {
+    /// Keys whose stored value changed, to notify
+    pub fn changed_keys(&self) -> Vec<String> {
+        self.keys
+            .iter()
+            .filter(|report| report.status == KeyImportStatus::Imported)
+            .map(|report| report.key.clone())
+            .collect()
+    }
+}
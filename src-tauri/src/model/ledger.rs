@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Plain-text accounting formats of `export_journal`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JournalFormat {
+    Ledger,
+    Hledger,
+    Beancount,
+}
+
+/// How expenses are turned into postings, saved under the "ledger_config" sub-key
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LedgerConfig {
+    /// Commodity of the bare amounts of imported journals. The store holds, and exports,
+    /// converted dollars: other commodities are only read through a price.
+    pub currency: String,
+    /// Account of each tag, e.g. "Food" -> "Expenses:Food:Groceries"
+    pub tag_accounts: BTreeMap<String, String>,
+    /// Account of each import definition key, e.g. "NavyFederal" -> "Assets:Bank:Checking"
+    pub source_accounts: BTreeMap<String, String>,
+    /// Spending without a tag
+    pub default_expense_account: String,
+    /// Income (negative amounts) without a tag
+    pub default_income_account: String,
+    /// Manually added expenses, which have no import definition
+    pub default_source_account: String,
+    /// Clearing account of the transfer legs, both legs of a pair cancel out in it
+    pub transfer_account: String,
+}
+
+impl Default for LedgerConfig {
+    fn default() -> Self {
+        LedgerConfig {
+            currency: "USD".to_string(),
+            tag_accounts: BTreeMap::new(),
+            source_accounts: BTreeMap::new(),
+            default_expense_account: "Expenses:Uncategorized".to_string(),
+            default_income_account: "Income:Uncategorized".to_string(),
+            default_source_account: "Assets:Cash".to_string(),
+            transfer_account: "Assets:Transfers".to_string(),
+        }
+    }
+}
+
+/// Something of a journal that was skipped on import, at a 1-based line number
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalWarning {
+    pub line: usize,
+    pub message: String,
+}
+
+/// Result of `import_journal`
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalImportSummary {
+    /// Number of transactions read from the journal
+    pub transactions: usize,
+    pub added_count: u16,
+    pub duplicate_count: u16,
+    pub warnings: Vec<JournalWarning>,
+}
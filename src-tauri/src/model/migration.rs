@@ -0,0 +1,23 @@
+use serde::Serialize;
+
+/// What one schema migration changed, or would change in a dry run
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationStepReport {
+    /// Schema version reached by the migration
+    pub version: u32,
+    pub description: String,
+    /// Human readable changes, empty when the data already had the new layout
+    pub changes: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    pub from_version: u32,
+    pub to_version: u32,
+    pub dry_run: bool,
+    pub steps: Vec<MigrationStepReport>,
+    /// store_data sub-keys changed or removed by the migrations
+    pub keys: Vec<String>,
+}
@@ -1,2 +1,16 @@
+pub mod aggregation;
+pub mod audit;
+pub mod backup;
 pub mod expense;
+pub mod export;
+pub mod finance;
+pub mod import;
+pub mod ledger;
+pub mod migration;
+pub mod money;
+pub mod pivot;
+pub mod query;
 pub mod response;
+pub mod tag;
+pub mod transfer;
+pub mod undo;
@@ -0,0 +1,342 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::error::Error as StdError;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::Neg;
+
+/// Minor units in one major unit, the same for every supported currency
+const MINOR_PER_MAJOR: i64 = 100;
+
+/// Currencies amounts are read in. The store holds every amount in `Currency::STORE`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Currency {
+    #[default]
+    Usd,
+    Ils,
+}
+
+impl Currency {
+    /// Currency of the stored amounts, imports convert to it
+    pub const STORE: Currency = Currency::Usd;
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::Usd => "USD",
+            Currency::Ils => "ILS",
+        }
+    }
+
+    /// The currency of an ISO 4217 code ("USD", "ils"), None when not supported
+    pub fn from_code(code: &str) -> Option<Self> {
+        [Currency::Usd, Currency::Ils]
+            .into_iter()
+            .find(|currency| currency.code().eq_ignore_ascii_case(code))
+    }
+}
+
+/// Returned when adding or subtracting amounts of different currencies, which must be
+/// converted first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurrencyMismatchError {
+    pub left: Currency,
+    pub right: Currency,
+}
+
+impl fmt::Display for CurrencyMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Cannot combine amounts in {} and {}",
+            self.left.code(),
+            self.right.code()
+        )
+    }
+}
+
+impl StdError for CurrencyMismatchError {}
+
+/// Exact amount of money, in integer minor units (cents, agorot) of its currency.
+///
+/// Amounts of different currencies are never added together, `checked_add` and
+/// `checked_sub` refuse it. Ordering compares the minor units, then the currency.
+///
+/// Serialized as a plain number of major units, like the `f64` amounts stored before, and
+/// read back from any JSON number rounded to the nearest minor unit. The currency is not
+/// part of that format: stored amounts are always in `Currency::STORE`, serializing an
+/// amount in another currency fails.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Money {
+    minor_units: i64,
+    currency: Currency,
+}
+
+impl Money {
+    pub const ZERO: Money = Money {
+        minor_units: 0,
+        currency: Currency::STORE,
+    };
+
+    pub fn from_minor(minor_units: i64, currency: Currency) -> Self {
+        Money {
+            minor_units,
+            currency,
+        }
+    }
+
+    /// Rounds a number of major units half away from zero, None when it is not finite or
+    /// does not fit
+    pub fn from_major(value: f64, currency: Currency) -> Option<Self> {
+        let minor = (value * MINOR_PER_MAJOR as f64).round();
+        if !minor.is_finite() || minor.abs() >= i64::MAX as f64 {
+            return None;
+        }
+        Some(Money::from_minor(minor as i64, currency))
+    }
+
+    /// Reads a decimal number of major units ("-1234.5", "0.99") without going through a
+    /// float, digits past the minor units are rounded half away from zero.
+    /// Other notations (e.g. "1.5e3") are read as a float.
+    pub fn parse(text: &str, currency: Currency) -> Result<Self, Box<dyn StdError>> {
+        let text = text.trim();
+        if let Some((mantissa, scale)) = parse_decimal(text) {
+            return mantissa
+                .checked_mul(MINOR_PER_MAJOR as i128)
+                .and_then(|scaled| Self::from_scaled(scaled, scale, currency))
+                .ok_or_else(|| format!("Overflow: {} is too large to be an amount", text).into());
+        }
+
+        let value: f64 = text.parse()?;
+        Self::from_major(value, currency)
+            .ok_or_else(|| format!("Overflow: {} is too large to be an amount", text).into())
+    }
+
+    /// `value / 10^scale` minor units, rounded half away from zero, None when it does not fit
+    fn from_scaled(value: i128, scale: u32, currency: Currency) -> Option<Self> {
+        let minor = divide_rounded(value, 10i128.checked_pow(scale)?)?;
+        Some(Money::from_minor(i64::try_from(minor).ok()?, currency))
+    }
+
+    pub fn minor_units(&self) -> i64 {
+        self.minor_units
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// Value in major units, for ratios and charts only
+    pub fn to_f64(&self) -> f64 {
+        self.minor_units as f64 / MINOR_PER_MAJOR as f64
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.minor_units > 0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.minor_units < 0
+    }
+
+    pub fn abs(&self) -> Self {
+        Money::from_minor(self.minor_units.abs(), self.currency)
+    }
+
+    pub fn checked_add(self, other: Money) -> Result<Money, CurrencyMismatchError> {
+        if self.currency != other.currency {
+            return Err(CurrencyMismatchError {
+                left: self.currency,
+                right: other.currency,
+            });
+        }
+        Ok(Money::from_minor(
+            self.minor_units.saturating_add(other.minor_units),
+            self.currency,
+        ))
+    }
+
+    pub fn checked_sub(self, other: Money) -> Result<Money, CurrencyMismatchError> {
+        self.checked_add(-other)
+    }
+
+    /// Converts to `currency`, where one unit of it costs `unit_price` (in the currency of
+    /// this amount), in integer arithmetic rounded once to the nearest minor unit.
+    /// None when the price is zero, in another currency, or the result does not fit.
+    pub fn convert(&self, currency: Currency, unit_price: Money) -> Option<Self> {
+        if unit_price.currency != self.currency {
+            return None;
+        }
+        let minor = divide_rounded(
+            self.minor_units as i128 * MINOR_PER_MAJOR as i128,
+            unit_price.minor_units as i128,
+        )?;
+        Some(Money::from_minor(i64::try_from(minor).ok()?, currency))
+    }
+
+    /// Reads the product of two plain decimal numbers ("0.333", "-3"), e.g. a unit price
+    /// times a number of units, exactly and rounded once to the nearest minor unit
+    pub fn parse_product(
+        unit_price: &str,
+        quantity: &str,
+        currency: Currency,
+    ) -> Result<Self, Box<dyn StdError>> {
+        let (price, price_scale) = parse_decimal(unit_price.trim())
+            .ok_or_else(|| format!("Invalid amount: {}", unit_price))?;
+        let (units, units_scale) = parse_decimal(quantity.trim())
+            .ok_or_else(|| format!("Invalid quantity: {}", quantity))?;
+        price
+            .checked_mul(units)
+            .and_then(|product| product.checked_mul(MINOR_PER_MAJOR as i128))
+            .and_then(|scaled| Self::from_scaled(scaled, price_scale + units_scale, currency))
+            .ok_or_else(|| {
+                format!(
+                    "Overflow: {} times {} is too large to be an amount",
+                    unit_price, quantity
+                )
+                .into()
+            })
+    }
+
+    /// Share of `count` equal parts, rounded to the nearest minor unit (e.g. an average)
+    pub fn divide(&self, count: usize) -> Self {
+        if count == 0 {
+            return Money::from_minor(0, self.currency);
+        }
+        let count = count as i64;
+        let half = count / 2;
+        let minor = if self.minor_units < 0 {
+            (self.minor_units - half) / count
+        } else {
+            (self.minor_units + half) / count
+        };
+        Money::from_minor(minor, self.currency)
+    }
+
+    /// Major units with exactly two decimals ("12.50", "-0.05"), as journals and OFX expect
+    pub fn to_fixed_string(&self) -> String {
+        let sign = if self.minor_units < 0 { "-" } else { "" };
+        let minor = self.minor_units.unsigned_abs();
+        let per_major = MINOR_PER_MAJOR as u64;
+        format!("{}{}.{:02}", sign, minor / per_major, minor % per_major)
+    }
+}
+
+/// Digits of a plain decimal number ("-12.345" -> (-12345, 3)), None for any other notation
+fn parse_decimal(text: &str) -> Option<(i128, u32)> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    if (whole.is_empty() && fraction.is_empty())
+        || !whole
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let mut mantissa: i128 = 0;
+    for digit in whole.bytes().chain(fraction.bytes()) {
+        mantissa = mantissa
+            .checked_mul(10)?
+            .checked_add((digit - b'0') as i128)?;
+    }
+    let scale = u32::try_from(fraction.len()).ok()?;
+
+    Some((if negative { -mantissa } else { mantissa }, scale))
+}
+
+/// `value / divisor` rounded half away from zero, None when dividing by zero
+fn divide_rounded(value: i128, divisor: i128) -> Option<i128> {
+    if divisor == 0 {
+        return None;
+    }
+    let quotient = value / divisor;
+    let remainder = value % divisor;
+    if remainder.unsigned_abs() * 2 >= divisor.unsigned_abs() {
+        let away = if (value < 0) != (divisor < 0) { -1 } else { 1 };
+        return quotient.checked_add(away);
+    }
+    Some(quotient)
+}
+
+impl fmt::Display for Money {
+    /// Major units without trailing zeros ("12.5", "100", "-0.05"), the way the `f64`
+    /// amounts were printed. Amounts that had more decimals (converted shekels) print
+    /// rounded, schema migration 3 hashes their fingerprints again for that.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fixed = self.to_fixed_string();
+        let trimmed = fixed.trim_end_matches('0').trim_end_matches('.');
+        f.write_str(trimmed)
+    }
+}
+
+impl From<f64> for Money {
+    /// Major units in the store currency, out of range values saturate
+    fn from(value: f64) -> Self {
+        let minor = (value * MINOR_PER_MAJOR as f64).round() as i64;
+        Money::from_minor(minor, Currency::STORE)
+    }
+}
+
+impl PartialOrd for Money {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Money {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.minor_units
+            .cmp(&other.minor_units)
+            .then(self.currency.cmp(&other.currency))
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money::from_minor(-self.minor_units, self.currency)
+    }
+}
+
+impl Sum<Money> for Result<Money, CurrencyMismatchError> {
+    /// Zero in the store currency when empty, else in the currency of the amounts
+    fn sum<I: Iterator<Item = Money>>(mut iter: I) -> Self {
+        match iter.next() {
+            Some(first) => iter.try_fold(first, Money::checked_add),
+            None => Ok(Money::ZERO),
+        }
+    }
+}
+
+impl<'a> Sum<&'a Money> for Result<Money, CurrencyMismatchError> {
+    fn sum<I: Iterator<Item = &'a Money>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.currency != Currency::STORE {
+            return Err(serde::ser::Error::custom(format!(
+                "Amount {} {} must be converted to {} before it is stored",
+                self,
+                self.currency.code(),
+                Currency::STORE.code()
+            )));
+        }
+        serializer.serialize_f64(self.to_f64())
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = f64::deserialize(deserializer)?;
+        Money::from_major(value, Currency::STORE)
+            .ok_or_else(|| serde::de::Error::custom(format!("Invalid amount: {}", value)))
+    }
+}
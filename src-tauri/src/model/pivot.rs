@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PivotDimension {
+    Tag,
+    Month,
+    Year,
+    Weekday,
+    // Definition the expense was imported with
+    Source,
+    AmountBand,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PivotMeasure {
+    Sum,
+    Count,
+    Avg,
+    Min,
+    Max,
+}
+
+/// A pivot matrix, `cells[row][column]` being None where no expense falls.
+///
+/// Totals apply the measure to all the expenses of the row, column or table, so they
+/// are averages/minimums/maximums too for those measures. With a tag dimension an
+/// expense counts in each of its tags, while the grand total counts it once.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PivotTable {
+    pub rows: PivotDimension,
+    pub columns: PivotDimension,
+    pub measure: PivotMeasure,
+    pub row_labels: Vec<String>,
+    pub column_labels: Vec<String>,
+    pub cells: Vec<Vec<Option<f64>>>,
+    pub row_totals: Vec<f64>,
+    pub column_totals: Vec<f64>,
+    pub grand_total: f64,
+}
@@ -0,0 +1,87 @@
+use crate::model::expense::Expense;
+use crate::model::money::Money;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Page size used when the query does not give one
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AmountSign {
+    // Spending, money leaving the account
+    Positive,
+    // Income or credits
+    Negative,
+}
+
+/// Criteria an expense must all match. Empty fields do not filter anything.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExpenseFilter {
+    // Both dates are inclusive
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    // At least one of these tags
+    pub tags_any: Vec<String>,
+    // Every one of these tags
+    pub tags_all: Vec<String>,
+    // None of these tags
+    pub tags_none: Vec<String>,
+    pub min_amount: Option<Money>,
+    pub max_amount: Option<Money>,
+    // Case-insensitive substring of the description
+    pub text: Option<String>,
+    // Regular expression matched against the description
+    pub regex: Option<String>,
+    pub sign: Option<AmountSign>,
+    // Query in the search syntax, e.g. `tag:Food -tag:Gifts amount>50`
+    pub search: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortField {
+    #[default]
+    Date,
+    Amount,
+    Description,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExpenseQuery {
+    pub filter: ExpenseFilter,
+    pub sort_by: SortField,
+    pub sort_order: SortOrder,
+    // Zero-based
+    pub page: usize,
+    pub page_size: Option<usize>,
+}
+
+/// One page of the matching expenses, with the count and sum of all matches
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpensePage {
+    pub expenses: Vec<Expense>,
+    pub total_count: usize,
+    pub total_amount: Money,
+    pub page: usize,
+    pub page_size: usize,
+}
+
+/// An expense found by the description search, with its relevance score
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DescriptionMatch {
+    pub expense: Expense,
+    pub score: f64,
+}
@@ -9,6 +9,7 @@ pub enum Status {
     Found = 302,
     NotFound = 404,
     Conflict = 409,
+    Locked = 423,
     Error = 500,
 }
 
@@ -0,0 +1,12 @@
+use crate::model::money::Money;
+use serde::{Deserialize, Serialize};
+
+/// How much one tag is used across all expenses
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagSummary {
+    pub tag: String,
+    pub count: usize,
+    // Sum of the tagged amounts, income counts as negative
+    pub total: Money,
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransferStatus {
+    Suggested,
+    Confirmed,
+    Rejected,
+}
+
+/// A pair of opposite-signed expenses that represent money moving between two accounts,
+/// e.g. a card payment debited from checking and credited on the card.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferPair {
+    pub id: String,
+    // Positive amount, money leaving the account
+    pub outflow_id: String,
+    // Negative amount, money arriving in the account
+    pub inflow_id: String,
+    pub status: TransferStatus,
+}
+
+impl TransferPair {
+    pub fn new(outflow_id: &str, inflow_id: &str, status: TransferStatus) -> Self {
+        TransferPair {
+            id: transfer_pair_id(outflow_id, inflow_id),
+            outflow_id: outflow_id.to_string(),
+            inflow_id: inflow_id.to_string(),
+            status,
+        }
+    }
+
+    pub fn contains(&self, expense_id: &str) -> bool {
+        self.outflow_id == expense_id || self.inflow_id == expense_id
+    }
+}
+
+/// Deterministic pair id so that re-running detection never re-suggests a rejected pair
+pub fn transfer_pair_id(outflow_id: &str, inflow_id: &str) -> String {
+    let input = format!("transfer:{}:{}", outflow_id, inflow_id);
+    blake3::hash(input.as_bytes()).to_hex().to_string()
+}
@@ -0,0 +1,20 @@
+use serde::Serialize;
+
+/// Result of an undo or redo
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoResult {
+    /// Command whose changes were undone or redone
+    pub command: String,
+    /// store_data sub-keys that changed, to notify
+    pub keys: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoState {
+    /// Command the next undo would revert, if any
+    pub undo_command: Option<String>,
+    /// Command the next redo would apply again, if any
+    pub redo_command: Option<String>,
+}
@@ -0,0 +1,114 @@
+use crate::model::aggregation::{AggregateBucket, Mode, UNTAGGED};
+use crate::model::expense::Expense;
+use crate::model::money::{CurrencyMismatchError, Money};
+use chrono::{Datelike, NaiveDate};
+use std::collections::BTreeMap;
+
+/// First day of the bucket a date falls in (weeks start on Monday, as ISO weeks do)
+pub fn bucket_start(date: NaiveDate, mode: Mode) -> NaiveDate {
+    match mode {
+        Mode::Daily => date,
+        Mode::Weekly => date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64),
+        Mode::Monthly => date.with_day(1).unwrap_or(date),
+        Mode::Yearly => NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap_or(date),
+    }
+}
+
+/// Sortable label of the bucket a date falls in
+pub fn bucket_key(date: NaiveDate, mode: Mode) -> String {
+    match mode {
+        Mode::Daily => date.format("%Y-%m-%d").to_string(),
+        Mode::Weekly => {
+            let week = date.iso_week();
+            format!("{}-W{:02}", week.year(), week.week())
+        }
+        Mode::Monthly => date.format("%Y-%m").to_string(),
+        Mode::Yearly => date.format("%Y").to_string(),
+    }
+}
+
+/// Median of a list of amounts, 0 when empty. The mean of the two middle amounts of an
+/// even list is rounded to the nearest cent.
+pub fn median(amounts: &mut [Money]) -> Result<Money, CurrencyMismatchError> {
+    if amounts.is_empty() {
+        return Ok(Money::ZERO);
+    }
+
+    amounts.sort();
+    let middle = amounts.len() / 2;
+    match amounts.len() % 2 {
+        0 => Ok(amounts[middle - 1].checked_add(amounts[middle])?.divide(2)),
+        _ => Ok(amounts[middle]),
+    }
+}
+
+/// Groups expenses into time buckets (and tags) and computes their totals.
+///
+/// With `by_tag`, an expense counts once under each of its tags, untagged ones under
+/// "Untagged", like the tag charts do.
+///
+/// Parameters:
+/// - `expenses`: The expenses to aggregate, already filtered.
+/// - `mode`: The size of the time buckets.
+/// - `by_tag`: Whether to split every bucket by tag.
+///
+/// Returns:
+/// - `Vec<AggregateBucket>`: The buckets ordered by date, then tag.
+pub fn aggregate<'a, I>(
+    expenses: I,
+    mode: Mode,
+    by_tag: bool,
+) -> Result<Vec<AggregateBucket>, CurrencyMismatchError>
+where
+    I: IntoIterator<Item = &'a Expense>,
+{
+    let mut groups: BTreeMap<(NaiveDate, Option<String>), Vec<Money>> = BTreeMap::new();
+
+    for expense in expenses {
+        let start = bucket_start(expense.get_date().date(), mode);
+
+        if !by_tag {
+            groups
+                .entry((start, None))
+                .or_default()
+                .push(expense.get_amount());
+            continue;
+        }
+
+        let tags: Vec<&str> = expense
+            .get_tags()
+            .iter()
+            .map(|tag| tag.as_str())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        let tags = if tags.is_empty() {
+            vec![UNTAGGED]
+        } else {
+            tags
+        };
+
+        for tag in tags {
+            groups
+                .entry((start, Some(tag.to_string())))
+                .or_default()
+                .push(expense.get_amount());
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|((start, tag), mut amounts)| {
+            let total: Money = amounts.iter().sum::<Result<Money, _>>()?;
+            let count = amounts.len();
+            Ok(AggregateBucket {
+                key: bucket_key(start, mode),
+                start,
+                tag,
+                total,
+                count,
+                average: total.divide(count),
+                median: median(&mut amounts)?,
+            })
+        })
+        .collect()
+}
@@ -3,6 +3,7 @@ use crate::definition::csv_definition::{
 };
 use crate::model::expense::Expense;
 use crate::store::app_store::ExpenseStore;
+use crate::store::backend::StorageBackend;
 use chrono::NaiveDate;
 use csv::{ReaderBuilder, StringRecord};
 use std::collections::HashMap;
@@ -109,8 +110,8 @@ pub fn open_csv_file_and_find_definitions(
 pub type ParseResult = (u16, u16, Option<NaiveDate>, Option<NaiveDate>);
 
 /// Parse a CSV file with a given definition and update the store
-pub fn parse_csv_file_with_selected_definition(
-    expense_store: &ExpenseStore,
+pub fn parse_csv_file_with_selected_definition<B: StorageBackend>(
+    expense_store: &ExpenseStore<B>,
     path: String,
     csv_definition_key: CsvDefinitionKey,
 ) -> Result<ParseResult, Box<dyn StdError>> {
@@ -118,14 +119,17 @@ pub fn parse_csv_file_with_selected_definition(
         .get(&csv_definition_key)
         .ok_or("CSV definition not found")?;
 
-    parse_csv_file_with_definition(expense_store, path, csv_definition)
+    let source = format!("{:?}", csv_definition_key);
+    parse_csv_file_with_definition(expense_store, path, csv_definition, &source)
 }
 
-/// Parse a CSV file with a given CsvDefinition reference and update the store
-pub fn parse_csv_file_with_definition(
-    expense_store: &ExpenseStore,
+/// Parse a CSV file with a given CsvDefinition reference and update the store.
+/// Every parsed expense is tagged with `source`, the key of the definition it came from.
+pub fn parse_csv_file_with_definition<B: StorageBackend>(
+    expense_store: &ExpenseStore<B>,
     path: String,
     csv_definition: &CsvDefinition,
+    source: &str,
 ) -> Result<ParseResult, Box<dyn StdError>> {
     let file =
         open_file_from_path(&path).map_err(|_| format!("Failed to open file at path: {}", path))?;
@@ -181,6 +185,10 @@ pub fn parse_csv_file_with_definition(
         expenses_batch.extend(thread_results.iter().cloned());
     }
 
+    for expense in expenses_batch.iter_mut() {
+        expense.set_source(source);
+    }
+
     let min_date = expenses_batch.iter().map(|e| e.get_date().date()).min();
     let max_date = expenses_batch.iter().map(|e| e.get_date().date()).max();
 
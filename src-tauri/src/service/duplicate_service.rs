@@ -0,0 +1,218 @@
+use crate::model::expense::Expense;
+use crate::model::money::Money;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+// Weights of each signal in the final pair score, summing to 1
+const AMOUNT_WEIGHT: f64 = 0.2;
+const DATE_WEIGHT: f64 = 0.3;
+const DESCRIPTION_WEIGHT: f64 = 0.5;
+
+/// A scored pair of expenses that probably describe the same transaction
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicatePair {
+    pub first_id: String,
+    pub second_id: String,
+    pub score: f64,
+    pub description_similarity: f64,
+    pub day_gap: i64,
+}
+
+/// A group of expenses connected by probable-duplicate pairs, returned for review
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateCluster {
+    pub expense_ids: Vec<String>,
+    pub pairs: Vec<DuplicatePair>,
+    pub score: f64,
+}
+
+/// Computes the Levenshtein edit distance between two strings, by characters
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    if a.is_empty() {
+        return b.len();
+    }
+    if b.is_empty() {
+        return a.len();
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current: Vec<usize> = vec![0; b.len() + 1];
+
+    for (i, a_char) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// Splits a description into lowercase alphanumeric tokens
+pub fn tokenize_description(description: &str) -> Vec<String> {
+    description
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(|token| token.to_lowercase())
+        .collect()
+}
+
+/// Scores how similar two descriptions are, from 0 (unrelated) to 1 (same text).
+///
+/// Takes the best of the token overlap (Jaccard) and the normalized Levenshtein similarity,
+/// so both reordered words and small typos score high.
+pub fn description_similarity(a: &str, b: &str) -> f64 {
+    let a_tokens = tokenize_description(a);
+    let b_tokens = tokenize_description(b);
+
+    if a_tokens.is_empty() && b_tokens.is_empty() {
+        return 1.0;
+    }
+
+    let a_set: HashSet<&String> = a_tokens.iter().collect();
+    let b_set: HashSet<&String> = b_tokens.iter().collect();
+    let intersection = a_set.intersection(&b_set).count() as f64;
+    let union = a_set.union(&b_set).count() as f64;
+    let jaccard = if union > 0.0 {
+        intersection / union
+    } else {
+        0.0
+    };
+
+    let a_joined = a_tokens.join(" ");
+    let b_joined = b_tokens.join(" ");
+    let longest = a_joined.chars().count().max(b_joined.chars().count());
+    let edit_similarity = if longest > 0 {
+        1.0 - levenshtein(&a_joined, &b_joined) as f64 / longest as f64
+    } else {
+        0.0
+    };
+
+    jaccard.max(edit_similarity)
+}
+
+/// Scores a pair of expenses, or returns None when they cannot be duplicates
+/// (different amount, same known source, or dates further apart than `window_days`).
+/// Expenses without a source, manual or stored before sources were recorded, are compared
+/// with everything.
+pub fn score_pair(first: &Expense, second: &Expense, window_days: i64) -> Option<DuplicatePair> {
+    if first.has_same_source_as(second) {
+        return None;
+    }
+
+    if first.get_amount() != second.get_amount() {
+        return None;
+    }
+
+    let day_gap = (first.get_date().date() - second.get_date().date())
+        .num_days()
+        .abs();
+    if day_gap > window_days {
+        return None;
+    }
+
+    let date_score = 1.0 - day_gap as f64 / (window_days + 1) as f64;
+    let description_score =
+        description_similarity(first.get_description(), second.get_description());
+
+    Some(DuplicatePair {
+        first_id: first.get_id().to_string(),
+        second_id: second.get_id().to_string(),
+        score: AMOUNT_WEIGHT + DATE_WEIGHT * date_score + DESCRIPTION_WEIGHT * description_score,
+        description_similarity: description_score,
+        day_gap,
+    })
+}
+
+fn find_root(parents: &mut HashMap<String, String>, id: &str) -> String {
+    let parent = parents.get(id).cloned().unwrap_or_else(|| id.to_string());
+    if parent == id {
+        return parent;
+    }
+
+    let root = find_root(parents, &parent);
+    parents.insert(id.to_string(), root.clone());
+    root
+}
+
+/// Finds probable duplicates across sources that exact-hash dedup misses, e.g. the same
+/// purchase imported from an Amex CSV and entered manually with slightly different text.
+///
+/// Pairs scoring at least `threshold` are grouped into clusters (transitively), ordered by
+/// their best pair score.
+///
+/// Parameters:
+/// - `expenses`: The expenses to search through.
+/// - `window_days`: The maximum number of days between two duplicates.
+/// - `threshold`: The minimum pair score, between 0 and 1.
+///
+/// Returns:
+/// - `Vec<DuplicateCluster>`: The clusters to review.
+pub fn find_probable_duplicates(
+    expenses: &[Expense],
+    window_days: i64,
+    threshold: f64,
+) -> Vec<DuplicateCluster> {
+    // Only equal amounts can be duplicates, so compare within amount buckets
+    let mut by_amount: HashMap<Money, Vec<&Expense>> = HashMap::new();
+    for expense in expenses {
+        by_amount
+            .entry(expense.get_amount())
+            .or_default()
+            .push(expense);
+    }
+
+    let mut pairs: Vec<DuplicatePair> = Vec::new();
+    for group in by_amount.values() {
+        for (i, first) in group.iter().enumerate() {
+            for second in group.iter().skip(i + 1) {
+                if let Some(pair) = score_pair(first, second, window_days) {
+                    if pair.score >= threshold {
+                        pairs.push(pair);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut parents: HashMap<String, String> = HashMap::new();
+    for pair in pairs.iter() {
+        let first_root = find_root(&mut parents, &pair.first_id);
+        let second_root = find_root(&mut parents, &pair.second_id);
+        if first_root != second_root {
+            parents.insert(first_root, second_root);
+        }
+    }
+
+    let mut clusters: BTreeMap<String, DuplicateCluster> = BTreeMap::new();
+    for pair in pairs {
+        let root = find_root(&mut parents, &pair.first_id);
+        let cluster = clusters.entry(root).or_insert_with(|| DuplicateCluster {
+            expense_ids: Vec::new(),
+            pairs: Vec::new(),
+            score: 0.0,
+        });
+
+        for id in [&pair.first_id, &pair.second_id] {
+            if !cluster.expense_ids.contains(id) {
+                cluster.expense_ids.push(id.clone());
+            }
+        }
+        cluster.score = cluster.score.max(pair.score);
+        cluster.pairs.push(pair);
+    }
+
+    let mut result: Vec<DuplicateCluster> = clusters.into_values().collect();
+    for cluster in result.iter_mut() {
+        cluster.expense_ids.sort();
+    }
+    result.sort_by(|a, b| b.score.total_cmp(&a.score));
+    result
+}
@@ -0,0 +1,241 @@
+use crate::model::expense::Expense;
+use crate::model::export::{ExportColumn, ExportFormat};
+use crate::model::money::Currency;
+use crate::store::json_file_backend::write_atomically_with;
+use chrono::{NaiveDateTime, Utc};
+use std::error::Error as StdError;
+use std::io::Write;
+use std::path::Path;
+
+/// Date layout of the "Expense Tracker Backup" CSV definition
+const BACKUP_DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+/// OFX 1.x limits the payee name to 32 characters
+const OFX_NAME_LENGTH: usize = 32;
+
+/// Writes `expenses` to `path` in `format`, streamed to a temporary file that replaces
+/// the target once complete.
+///
+/// Parameters:
+/// - `columns`: Columns of the plain CSV format, ignored by the others.
+pub fn export_to_path(
+    path: &Path,
+    expenses: &[Expense],
+    format: ExportFormat,
+    columns: &[ExportColumn],
+) -> Result<(), Box<dyn StdError>> {
+    write_atomically_with(path, |writer| {
+        write_expenses(writer, expenses, format, columns)
+    })
+}
+
+pub fn write_expenses(
+    writer: &mut dyn Write,
+    expenses: &[Expense],
+    format: ExportFormat,
+    columns: &[ExportColumn],
+) -> Result<(), Box<dyn StdError>> {
+    match format {
+        ExportFormat::ExpenseTrackerBackup => write_backup_csv(writer, expenses),
+        ExportFormat::Csv => write_csv(writer, expenses, columns),
+        ExportFormat::Json => write_json(writer, expenses),
+        ExportFormat::Ofx => write_ofx(writer, expenses, Utc::now().naive_utc()),
+        ExportFormat::Qif => write_qif(writer, expenses),
+    }
+}
+
+/// Same layout as the CSV the frontend used to build: only the first tag is kept
+fn write_backup_csv(writer: &mut dyn Write, expenses: &[Expense]) -> Result<(), Box<dyn StdError>> {
+    let mut csv = csv::Writer::from_writer(writer);
+    csv.write_record(["Tags", "Date", "Description", "Amount"])?;
+
+    for expense in expenses {
+        csv.write_record([
+            expense.get_tags().first().map(String::as_str).unwrap_or(""),
+            &expense.get_date().format(BACKUP_DATE_FORMAT).to_string(),
+            expense.get_description(),
+            &expense.get_amount().to_string(),
+        ])?;
+    }
+
+    csv.flush()?;
+    Ok(())
+}
+
+fn column_header(column: ExportColumn) -> &'static str {
+    match column {
+        ExportColumn::Id => "Id",
+        ExportColumn::Date => "Date",
+        ExportColumn::Description => "Description",
+        ExportColumn::Amount => "Amount",
+        ExportColumn::Tags => "Tags",
+        ExportColumn::Source => "Source",
+        ExportColumn::TransferId => "Transfer Id",
+    }
+}
+
+fn column_value(expense: &Expense, column: ExportColumn) -> String {
+    match column {
+        ExportColumn::Id => expense.get_id().to_string(),
+        ExportColumn::Date => expense.get_date().format("%Y-%m-%d").to_string(),
+        ExportColumn::Description => expense.get_description().to_string(),
+        ExportColumn::Amount => expense.get_amount().to_string(),
+        ExportColumn::Tags => expense.get_tags().join("; "),
+        ExportColumn::Source => expense.get_source().unwrap_or("").to_string(),
+        ExportColumn::TransferId => expense.get_transfer_id().unwrap_or("").to_string(),
+    }
+}
+
+fn write_csv(
+    writer: &mut dyn Write,
+    expenses: &[Expense],
+    columns: &[ExportColumn],
+) -> Result<(), Box<dyn StdError>> {
+    if columns.is_empty() {
+        return Err("At least one column is needed for a CSV export".into());
+    }
+
+    let mut csv = csv::Writer::from_writer(writer);
+    csv.write_record(columns.iter().map(|column| column_header(*column)))?;
+
+    for expense in expenses {
+        csv.write_record(columns.iter().map(|column| column_value(expense, *column)))?;
+    }
+
+    csv.flush()?;
+    Ok(())
+}
+
+fn write_json(writer: &mut dyn Write, expenses: &[Expense]) -> Result<(), Box<dyn StdError>> {
+    writer.write_all(b"[")?;
+    for (index, expense) in expenses.iter().enumerate() {
+        if index > 0 {
+            writer.write_all(b",")?;
+        }
+        writer.write_all(b"\n  ")?;
+        serde_json::to_writer(&mut *writer, expense)
+            .map_err(|err| format!("Failed to serialize expense: {}", err))?;
+    }
+    writer.write_all(b"\n]\n")?;
+    Ok(())
+}
+
+/// Amount as seen from the bank account: spending (positive here) is money going out
+fn account_amount(expense: &Expense) -> String {
+    (-expense.get_amount()).to_fixed_string()
+}
+
+fn escape_ofx(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace(['\r', '\n'], " ")
+}
+
+fn ofx_date(date: &NaiveDateTime) -> String {
+    date.format("%Y%m%d%H%M%S").to_string()
+}
+
+fn write_ofx(
+    writer: &mut dyn Write,
+    expenses: &[Expense],
+    now: NaiveDateTime,
+) -> Result<(), Box<dyn StdError>> {
+    let start = expenses
+        .iter()
+        .map(|expense| *expense.get_date())
+        .min()
+        .unwrap_or(now);
+    let end = expenses
+        .iter()
+        .map(|expense| *expense.get_date())
+        .max()
+        .unwrap_or(now);
+
+    writeln!(writer, "OFXHEADER:100")?;
+    writeln!(writer, "DATA:OFXSGML")?;
+    writeln!(writer, "VERSION:102")?;
+    writeln!(writer, "SECURITY:NONE")?;
+    writeln!(writer, "ENCODING:UTF-8")?;
+    writeln!(writer, "CHARSET:NONE")?;
+    writeln!(writer, "COMPRESSION:NONE")?;
+    writeln!(writer, "OLDFILEUID:NONE")?;
+    writeln!(writer, "NEWFILEUID:NONE")?;
+    writeln!(writer)?;
+    writeln!(writer, "<OFX>")?;
+    writeln!(writer, "<SIGNONMSGSRSV1><SONRS>")?;
+    writeln!(writer, "<STATUS><CODE>0<SEVERITY>INFO</STATUS>")?;
+    writeln!(writer, "<DTSERVER>{}<LANGUAGE>ENG", ofx_date(&now))?;
+    writeln!(writer, "</SONRS></SIGNONMSGSRSV1>")?;
+    writeln!(writer, "<BANKMSGSRSV1><STMTTRNRS>")?;
+    writeln!(writer, "<TRNUID>0")?;
+    writeln!(writer, "<STATUS><CODE>0<SEVERITY>INFO</STATUS>")?;
+    writeln!(writer, "<STMTRS>")?;
+    writeln!(writer, "<CURDEF>{}", Currency::STORE.code())?;
+    writeln!(
+        writer,
+        "<BANKACCTFROM><BANKID>0<ACCTID>expense-tracker<ACCTTYPE>CHECKING</BANKACCTFROM>"
+    )?;
+    writeln!(writer, "<BANKTRANLIST>")?;
+    writeln!(
+        writer,
+        "<DTSTART>{}<DTEND>{}",
+        ofx_date(&start),
+        ofx_date(&end)
+    )?;
+
+    for expense in expenses {
+        let name: String = expense
+            .get_description()
+            .chars()
+            .take(OFX_NAME_LENGTH)
+            .collect();
+        let kind = if expense.get_amount().is_positive() {
+            "DEBIT"
+        } else {
+            "CREDIT"
+        };
+
+        writeln!(writer, "<STMTTRN>")?;
+        writeln!(writer, "<TRNTYPE>{}", kind)?;
+        writeln!(writer, "<DTPOSTED>{}", ofx_date(expense.get_date()))?;
+        writeln!(writer, "<TRNAMT>{}", account_amount(expense))?;
+        writeln!(writer, "<FITID>{}", escape_ofx(expense.get_id()))?;
+        writeln!(writer, "<NAME>{}", escape_ofx(&name))?;
+        writeln!(writer, "<MEMO>{}", escape_ofx(expense.get_description()))?;
+        writeln!(writer, "</STMTTRN>")?;
+    }
+
+    writeln!(writer, "</BANKTRANLIST>")?;
+    writeln!(
+        writer,
+        "<LEDGERBAL><BALAMT>0.00<DTASOF>{}</LEDGERBAL>",
+        ofx_date(&end)
+    )?;
+    writeln!(writer, "</STMTRS>")?;
+    writeln!(writer, "</STMTTRNRS></BANKMSGSRSV1>")?;
+    writeln!(writer, "</OFX>")?;
+    Ok(())
+}
+
+/// QIF fields are one line each, a line break would start another field
+fn qif_text(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+fn write_qif(writer: &mut dyn Write, expenses: &[Expense]) -> Result<(), Box<dyn StdError>> {
+    writeln!(writer, "!Type:Bank")?;
+
+    for expense in expenses {
+        writeln!(writer, "D{}", expense.get_date().format("%m/%d/%Y"))?;
+        writeln!(writer, "T{}", account_amount(expense))?;
+        writeln!(writer, "P{}", qif_text(expense.get_description()))?;
+        // The category is the first tag
+        if let Some(tag) = expense.get_tags().first() {
+            writeln!(writer, "L{}", qif_text(tag))?;
+        }
+        writeln!(writer, "^")?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,345 @@
+use crate::definition::dynamic_csv_definition::DynamicCsvDefinition;
+use crate::model::expense::Expense;
+use crate::model::finance::{
+    BalanceSnapshot, ForecastConfig, Grant, RsuVest, Sale, SsdiConfig, SsdiPayPeriod, Stock,
+};
+use crate::model::import::{ImportMode, KeyImportReport, KeyImportStatus};
+use crate::model::ledger::LedgerConfig;
+use crate::model::transfer::TransferPair;
+use crate::store::audit::AUDIT_LOG_SUB_KEY;
+use crate::store::schema::SCHEMA_VERSION_SUB_KEY;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::error::Error as StdError;
+use std::fmt;
+
+/// How an importable store_data sub-key is laid out
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum KeyLayout {
+    /// Object of records keyed by their id
+    RecordMap,
+    /// Array of records holding their id
+    RecordList,
+    /// A value imported as a whole
+    Single,
+}
+
+struct ImportKey {
+    key: &'static str,
+    layout: KeyLayout,
+    /// Checks one record, or the whole value of a `Single` key
+    validate: fn(&Value) -> Result<(), String>,
+}
+
+const IMPORT_KEYS: &[ImportKey] = &[
+    ImportKey {
+        key: "expenses",
+        layout: KeyLayout::RecordMap,
+        validate: validate_as::<Expense>,
+    },
+    ImportKey {
+        key: "transfers",
+        layout: KeyLayout::RecordMap,
+        validate: validate_as::<TransferPair>,
+    },
+    ImportKey {
+        key: "stocks",
+        layout: KeyLayout::RecordMap,
+        validate: validate_as::<Stock>,
+    },
+    ImportKey {
+        key: "grants",
+        layout: KeyLayout::RecordMap,
+        validate: validate_as::<Grant>,
+    },
+    ImportKey {
+        key: "rsu_vests",
+        layout: KeyLayout::RecordMap,
+        validate: validate_as::<RsuVest>,
+    },
+    ImportKey {
+        key: "sales",
+        layout: KeyLayout::RecordMap,
+        validate: validate_as::<Sale>,
+    },
+    ImportKey {
+        key: "balance_snapshots",
+        layout: KeyLayout::RecordMap,
+        validate: validate_as::<BalanceSnapshot>,
+    },
+    ImportKey {
+        key: "ssdi_pay_periods",
+        layout: KeyLayout::RecordMap,
+        validate: validate_as::<SsdiPayPeriod>,
+    },
+    ImportKey {
+        key: "custom_csv_definitions",
+        layout: KeyLayout::RecordList,
+        validate: validate_as::<DynamicCsvDefinition>,
+    },
+    ImportKey {
+        key: "forecast_config",
+        layout: KeyLayout::Single,
+        validate: validate_as::<ForecastConfig>,
+    },
+    ImportKey {
+        key: "ssdi_config",
+        layout: KeyLayout::Single,
+        validate: validate_as::<SsdiConfig>,
+    },
+    ImportKey {
+        key: "ledger_config",
+        layout: KeyLayout::Single,
+        validate: validate_as::<LedgerConfig>,
+    },
+    ImportKey {
+        key: "import_history",
+        layout: KeyLayout::Single,
+        validate: validate_as::<Vec<String>>,
+    },
+];
+
+/// Returned when some imported keys do not match their schema; nothing is imported then
+#[derive(Debug, Clone)]
+pub struct ImportValidationError {
+    /// Error per invalid key
+    pub errors: BTreeMap<String, String>,
+}
+
+impl fmt::Display for ImportValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors: Vec<String> = self
+            .errors
+            .iter()
+            .map(|(key, error)| format!("{}: {}", key, error))
+            .collect();
+        write!(f, "Invalid import data, {}", errors.join("; "))
+    }
+}
+
+impl StdError for ImportValidationError {}
+
+fn validate_as<T: DeserializeOwned>(value: &Value) -> Result<(), String> {
+    T::deserialize(value)
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
+
+fn import_key(key: &str) -> Option<&'static ImportKey> {
+    IMPORT_KEYS.iter().find(|import_key| import_key.key == key)
+}
+
+/// Sub-keys maintained by the app itself, never taken from an import
+fn is_managed_key(key: &str) -> bool {
+    key == SCHEMA_VERSION_SUB_KEY || key == AUDIT_LOG_SUB_KEY
+}
+
+fn record_id(record: &Value) -> Option<&str> {
+    record.get("id").and_then(Value::as_str)
+}
+
+fn validate_key(import_key: &ImportKey, value: &Value) -> Result<(), String> {
+    match import_key.layout {
+        KeyLayout::Single => (import_key.validate)(value),
+        KeyLayout::RecordMap => {
+            let records = value.as_object().ok_or("expected an object keyed by id")?;
+            for (key, record) in records {
+                (import_key.validate)(record).map_err(|err| format!("record {}: {}", key, err))?;
+                if record_id(record) != Some(key.as_str()) {
+                    return Err(format!("record {} is stored under another id", key));
+                }
+            }
+            Ok(())
+        }
+        KeyLayout::RecordList => {
+            let records = value.as_array().ok_or("expected a list")?;
+            let mut ids = HashSet::new();
+            for (index, record) in records.iter().enumerate() {
+                (import_key.validate)(record)
+                    .map_err(|err| format!("record {}: {}", index, err))?;
+                let id = record_id(record).ok_or(format!("record {} has no id", index))?;
+                if !ids.insert(id) {
+                    return Err(format!("two records share the id {}", id));
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Checks every known sub-key of an import against its schema.
+/// Unknown and app-maintained keys are not checked, the import skips them.
+pub fn validate_import(values: &Map<String, Value>) -> Result<(), ImportValidationError> {
+    let errors: BTreeMap<String, String> = values
+        .iter()
+        .filter_map(|(key, value)| {
+            let import_key = import_key(key)?;
+            validate_key(import_key, value)
+                .err()
+                .map(|error| (key.clone(), error))
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ImportValidationError { errors })
+    }
+}
+
+/// Records are the same when they only differ by their revision, which is local to a store
+fn same_record(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            let without_revision = |record: &Map<String, Value>| {
+                let mut record = record.clone();
+                record.remove("revision");
+                record
+            };
+            without_revision(a) == without_revision(b)
+        }
+        _ => a == b,
+    }
+}
+
+fn records_of(layout: KeyLayout, value: Option<&Value>) -> Vec<(String, Value)> {
+    match (layout, value) {
+        (KeyLayout::RecordMap, Some(Value::Object(records))) => records
+            .iter()
+            .map(|(id, record)| (id.clone(), record.clone()))
+            .collect(),
+        (KeyLayout::RecordList, Some(Value::Array(records))) => records
+            .iter()
+            .filter_map(|record| Some((record_id(record)?.to_string(), record.clone())))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn merge_records(
+    current: Vec<(String, Value)>,
+    imported: Vec<(String, Value)>,
+    mode: ImportMode,
+    report: &mut KeyImportReport,
+) -> Vec<(String, Value)> {
+    let stored: HashMap<&str, &Value> = current
+        .iter()
+        .map(|(id, record)| (id.as_str(), record))
+        .collect();
+
+    let mut merged = match mode {
+        ImportMode::Replace => Vec::new(),
+        ImportMode::Merge | ImportMode::OnlyMissing => current.clone(),
+    };
+    let mut positions: HashMap<String, usize> = merged
+        .iter()
+        .enumerate()
+        .map(|(index, (id, _))| (id.clone(), index))
+        .collect();
+
+    for (id, record) in imported {
+        // Records left as they are keep the stored version, with its revision
+        let record = match stored.get(id.as_str()) {
+            None => {
+                report.added += 1;
+                record
+            }
+            Some(existing) if same_record(existing, &record) => {
+                report.unchanged += 1;
+                (*existing).clone()
+            }
+            Some(existing) if mode == ImportMode::OnlyMissing => {
+                report.skipped += 1;
+                (*existing).clone()
+            }
+            Some(_) => {
+                report.updated += 1;
+                record
+            }
+        };
+
+        match positions.get(&id) {
+            Some(&index) => merged[index].1 = record,
+            None => {
+                positions.insert(id.clone(), merged.len());
+                merged.push((id, record));
+            }
+        }
+    }
+
+    if mode == ImportMode::Replace {
+        report.removed = current
+            .iter()
+            .filter(|(id, _)| !positions.contains_key(id))
+            .count();
+    }
+
+    merged
+}
+
+/// Combines the imported value of `key` with the `current` one according to `mode`.
+/// The value must have passed `validate_import`.
+///
+/// Returns:
+/// - The new value of the key, None when it stays as it is
+/// - What changed for the key
+pub fn merge_key(
+    key: &str,
+    current: Option<&Value>,
+    imported: Value,
+    mode: ImportMode,
+) -> (Option<Value>, KeyImportReport) {
+    let import_key = match import_key(key) {
+        Some(import_key) => import_key,
+        None if is_managed_key(key) => {
+            return (None, KeyImportReport::skipped(key, "Maintained by the app"))
+        }
+        None => return (None, KeyImportReport::skipped(key, "Unknown key")),
+    };
+
+    let mut report = KeyImportReport::new(key);
+    let current = current.filter(|value| !value.is_null());
+
+    let merged = match import_key.layout {
+        KeyLayout::Single => match current {
+            Some(current) if same_record(current, &imported) => {
+                report.unchanged = 1;
+                None
+            }
+            Some(_) if mode == ImportMode::OnlyMissing => {
+                report.skipped = 1;
+                None
+            }
+            Some(_) => {
+                report.updated = 1;
+                Some(imported)
+            }
+            None => {
+                report.added = 1;
+                Some(imported)
+            }
+        },
+        layout => {
+            let records = merge_records(
+                records_of(layout, current),
+                records_of(layout, Some(&imported)),
+                mode,
+                &mut report,
+            );
+            Some(match layout {
+                KeyLayout::RecordList => {
+                    Value::Array(records.into_iter().map(|(_, record)| record).collect())
+                }
+                _ => Value::Object(records.into_iter().collect()),
+            })
+        }
+    };
+
+    if report.has_changes() {
+        report.status = KeyImportStatus::Imported;
+        (merged, report)
+    } else {
+        (None, report)
+    }
+}
@@ -0,0 +1,661 @@
+use crate::model::expense::Expense;
+use crate::model::ledger::{JournalWarning, LedgerConfig};
+use crate::model::money::{Currency, Money};
+use chrono::NaiveDate;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::BTreeMap;
+
+/// Source of imported expenses whose funding account is not in `source_accounts`
+pub static JOURNAL_SOURCE: &str = "Journal";
+
+/// Dated beancount directives without expenses, read without a warning
+static IGNORED_DATED_DIRECTIVES: [&str; 3] = ["open", "close", "commodity"];
+/// Dated beancount directives that are skipped with a warning
+static UNSUPPORTED_DATED_DIRECTIVES: [&str; 8] = [
+    "balance", "pad", "note", "document", "event", "price", "query", "custom",
+];
+/// Undated options and declarations without expenses, read without a warning
+static IGNORED_DIRECTIVES: [&str; 5] = ["option", "account", "commodity", "payee", "tag"];
+
+static DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%Y/%m/%d", "%Y.%m.%d"];
+
+// `12.50 USD`, `-12.50`, `$12.50`, `-$1,200.00`, `$-12.50`, `EUR 10`
+static AMOUNT_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^(-)?\s*([^\s\d.,+-]+)?\s*([-+]?(?:\d[\d,]*(?:\.\d*)?|\.\d+))\s*([^\s\d.,+-]\S*)?$",
+    )
+    .unwrap()
+});
+// Beancount metadata line of a transaction or posting: `key: value`
+static METADATA_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^([a-z][A-Za-z0-9_-]*):(?:\s+(.*))?$").unwrap());
+// Ledger metadata in a comment: `; Key: value`
+static COMMENT_METADATA_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^([A-Za-z][A-Za-z0-9_-]*):\s+(.*)$").unwrap());
+
+/// Expenses read from a journal, not yet added to the store
+#[derive(Clone, Debug, Default)]
+pub struct ParsedJournal {
+    /// Number of transactions read, including the skipped ones
+    pub transactions: usize,
+    pub expenses: Vec<Expense>,
+    pub warnings: Vec<JournalWarning>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum PostingValue {
+    // No amount, inferred from the other postings
+    Elided,
+    // In the store currency, directly or through a price
+    Known(Money),
+    // In another currency without a price
+    Foreign(String),
+}
+
+#[derive(Clone, Debug)]
+struct Posting {
+    line: usize,
+    account: String,
+    value: PostingValue,
+    tags: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+struct Transaction {
+    line: usize,
+    date: NaiveDate,
+    description: String,
+    tags: Vec<String>,
+    metadata: BTreeMap<String, String>,
+    postings: Vec<Posting>,
+    // A posting could not be read, the warning was already given
+    invalid: bool,
+}
+
+/// Reads the dated transactions of a beancount, ledger or hledger journal.
+///
+/// Every posting to an Expenses or Income account becomes an `Expense`, with the amount of
+/// the posting (spending is positive, income negative, as in the store) and tags derived
+/// from the account name. Directives and postings that cannot be turned into expenses are
+/// skipped with a warning instead of failing the import.
+pub fn parse_journal(text: &str, config: &LedgerConfig) -> ParsedJournal {
+    let mut parser = JournalParser {
+        config,
+        result: ParsedJournal::default(),
+        pending: None,
+        skipping: false,
+        block_comment: None,
+    };
+
+    for (index, line) in text.lines().enumerate() {
+        parser.read_line(index + 1, line);
+    }
+    parser.finish_transaction();
+
+    parser.result
+}
+
+struct JournalParser<'a> {
+    config: &'a LedgerConfig,
+    result: ParsedJournal,
+    pending: Option<Transaction>,
+    // Inside the indented block of a directive that is not a transaction
+    skipping: bool,
+    // Closing line of the ledger `comment` or `test` block being skipped
+    block_comment: Option<String>,
+}
+
+impl JournalParser<'_> {
+    fn warn(&mut self, line: usize, message: String) {
+        self.result.warnings.push(JournalWarning { line, message });
+    }
+
+    fn read_line(&mut self, number: usize, line: &str) {
+        let line = line.trim_end();
+
+        if let Some(end) = &self.block_comment {
+            if line.trim() == end {
+                self.block_comment = None;
+            }
+            return;
+        }
+
+        if line.trim().is_empty() {
+            self.finish_transaction();
+            self.skipping = false;
+            return;
+        }
+
+        if line.starts_with([' ', '\t']) {
+            self.read_indented(number, line.trim());
+            return;
+        }
+
+        self.finish_transaction();
+        self.skipping = false;
+
+        if line.starts_with([';', '#', '%', '|', '*']) {
+            return;
+        }
+
+        if line.starts_with(|c: char| c.is_ascii_digit()) {
+            self.read_dated(number, line);
+            return;
+        }
+
+        let (keyword, _) = split_first_word(line);
+        match keyword {
+            "comment" | "test" => self.block_comment = Some(format!("end {}", keyword)),
+            _ if IGNORED_DIRECTIVES.contains(&keyword) => self.skipping = true,
+            _ => {
+                self.warn(
+                    number,
+                    format!("Unsupported directive `{}` skipped", keyword),
+                );
+                self.skipping = true;
+            }
+        }
+    }
+
+    fn read_dated(&mut self, number: usize, line: &str) {
+        let (date_text, rest) = split_first_word(line);
+        let date = match parse_date(date_text) {
+            Some(date) => date,
+            None => {
+                self.warn(
+                    number,
+                    format!("Invalid date `{}`, entry skipped", date_text),
+                );
+                self.skipping = true;
+                return;
+            }
+        };
+
+        let (keyword, body) = split_first_word(rest);
+        if IGNORED_DATED_DIRECTIVES.contains(&keyword) {
+            self.skipping = true;
+            return;
+        }
+        if UNSUPPORTED_DATED_DIRECTIVES.contains(&keyword) {
+            self.warn(
+                number,
+                format!("Unsupported directive `{}` skipped", keyword),
+            );
+            self.skipping = true;
+            return;
+        }
+
+        // Ledger transactions may omit the flag
+        let body = match keyword {
+            "*" | "!" | "txn" => body,
+            _ => rest,
+        };
+        let (description, tags, metadata) = parse_header(body);
+
+        self.pending = Some(Transaction {
+            line: number,
+            date,
+            description,
+            tags,
+            metadata: metadata.into_iter().collect(),
+            postings: Vec::new(),
+            invalid: false,
+        });
+    }
+
+    fn read_indented(&mut self, number: usize, line: &str) {
+        if self.skipping {
+            return;
+        }
+        let config = self.config;
+        let transaction = match self.pending.as_mut() {
+            Some(transaction) => transaction,
+            None => {
+                self.warn(
+                    number,
+                    "Indented line outside of a transaction skipped".into(),
+                );
+                return;
+            }
+        };
+
+        if let Some(comment) = line.strip_prefix(';') {
+            let (tags, metadata) = parse_comment(comment);
+            match transaction.postings.last_mut() {
+                Some(posting) => posting.tags.extend(tags),
+                None => {
+                    transaction.tags.extend(tags);
+                    transaction.metadata.extend(metadata);
+                }
+            }
+            return;
+        }
+
+        if let Some(captures) = METADATA_PATTERN.captures(line) {
+            // Metadata of a posting has nothing to map to
+            if transaction.postings.is_empty() {
+                let value = captures.get(2).map_or("", |value| value.as_str());
+                transaction
+                    .metadata
+                    .insert(captures[1].to_string(), unquote(value));
+            }
+            return;
+        }
+
+        if line.starts_with(['(', '[']) {
+            self.warn(number, "Virtual posting skipped".into());
+            return;
+        }
+
+        match parse_posting(number, line, config) {
+            Ok(posting) => transaction.postings.push(posting),
+            Err(message) => {
+                transaction.invalid = true;
+                self.warn(number, format!("{}, transaction skipped", message));
+            }
+        }
+    }
+
+    fn finish_transaction(&mut self) {
+        let transaction = match self.pending.take() {
+            Some(transaction) => transaction,
+            None => return,
+        };
+        self.result.transactions += 1;
+        if transaction.invalid {
+            return;
+        }
+
+        let elided: Vec<usize> = (0..transaction.postings.len())
+            .filter(|&index| transaction.postings[index].value == PostingValue::Elided)
+            .collect();
+        if elided.len() > 1 {
+            self.warn(
+                transaction.line,
+                "More than one posting without an amount, transaction skipped".into(),
+            );
+            return;
+        }
+
+        let mut values: Vec<Option<Money>> = transaction
+            .postings
+            .iter()
+            .map(|posting| match posting.value {
+                PostingValue::Known(value) => Some(value),
+                _ => None,
+            })
+            .collect();
+        let balance: Option<Money> = transaction
+            .postings
+            .iter()
+            .map(|posting| match posting.value {
+                PostingValue::Known(value) => Some(value),
+                PostingValue::Elided => Some(Money::ZERO),
+                PostingValue::Foreign(_) => None,
+            })
+            .sum::<Option<Result<Money, _>>>()
+            .and_then(Result::ok);
+
+        match (elided.first(), balance) {
+            (Some(&index), Some(balance)) => values[index] = Some(-balance),
+            (Some(_), None) => {
+                self.warn(
+                    transaction.line,
+                    "Cannot infer the missing amount of a transaction in several currencies, transaction skipped"
+                        .into(),
+                );
+                return;
+            }
+            (None, Some(balance)) if balance != Money::ZERO => {
+                self.warn(
+                    transaction.line,
+                    format!(
+                        "Transaction does not balance by {} {}, transaction skipped",
+                        balance.to_fixed_string(),
+                        balance.currency().code()
+                    ),
+                );
+                return;
+            }
+            _ => {}
+        }
+
+        let source = self.transaction_source(&transaction);
+        for (posting, value) in transaction.postings.iter().zip(values) {
+            if !is_category_account(&posting.account) {
+                continue;
+            }
+            let amount = match (value, &posting.value) {
+                (Some(value), _) => value,
+                (None, PostingValue::Foreign(currency)) => {
+                    self.warn(
+                        posting.line,
+                        format!(
+                            "Amount in {} without a price in {}, posting skipped",
+                            currency,
+                            Currency::STORE.code()
+                        ),
+                    );
+                    continue;
+                }
+                (None, _) => continue,
+            };
+
+            let description = if transaction.description.is_empty() {
+                posting.account.clone()
+            } else {
+                transaction.description.clone()
+            };
+            let mut expense = Expense::new(
+                description,
+                amount,
+                transaction.date.and_hms_opt(0, 0, 0).unwrap(),
+            );
+            let tags = tag_for_account(&posting.account, self.config)
+                .into_iter()
+                .chain(transaction.tags.iter().cloned())
+                .chain(posting.tags.iter().cloned());
+            for tag in tags {
+                if !expense.get_tags().contains(&tag) {
+                    expense.add_tag(&tag);
+                }
+            }
+            expense.set_source(&source);
+            self.result.expenses.push(expense);
+        }
+    }
+
+    /// The `source` metadata, else the import definition mapped to the funding account
+    fn transaction_source(&self, transaction: &Transaction) -> String {
+        if let Some(source) = transaction.metadata.get("source") {
+            return source.clone();
+        }
+
+        transaction
+            .postings
+            .iter()
+            .filter(|posting| !is_category_account(&posting.account))
+            .find_map(|posting| {
+                self.config
+                    .source_accounts
+                    .iter()
+                    .find(|(_, account)| **account == posting.account)
+                    .map(|(source, _)| source.clone())
+            })
+            .unwrap_or_else(|| JOURNAL_SOURCE.to_string())
+    }
+}
+
+fn split_first_word(text: &str) -> (&str, &str) {
+    let text = text.trim_start();
+    match text.find(char::is_whitespace) {
+        Some(index) => (&text[..index], text[index..].trim_start()),
+        None => (text, ""),
+    }
+}
+
+/// Ledger allows an auxiliary date after `=`, only the primary one is kept
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    let primary = text.split('=').next().unwrap_or(text);
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(primary, format).ok())
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    match value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        Some(inner) => inner.replace("\\\"", "\"").replace("\\\\", "\\"),
+        None => value.to_string(),
+    }
+}
+
+/// Reads the quoted strings at the start of a beancount header, returns them and the rest
+fn quoted_strings(text: &str) -> (Vec<String>, &str) {
+    let mut strings = Vec::new();
+    let mut rest = text.trim_start();
+
+    while let Some(body) = rest.strip_prefix('"') {
+        let mut value = String::new();
+        let mut escaped = false;
+        let mut end = body.len();
+        for (index, c) in body.char_indices() {
+            match c {
+                _ if escaped => {
+                    value.push(c);
+                    escaped = false;
+                }
+                '\\' => escaped = true,
+                '"' => {
+                    end = index + 1;
+                    break;
+                }
+                _ => value.push(c),
+            }
+        }
+        strings.push(value);
+        rest = body[end..].trim_start();
+    }
+
+    (strings, rest)
+}
+
+/// Description, tags and metadata of the header line, after the date and the flag
+fn parse_header(body: &str) -> (String, Vec<String>, Vec<(String, String)>) {
+    if body.starts_with('"') {
+        // Beancount: ["payee"] "narration" #tag ^link
+        let (strings, rest) = quoted_strings(body);
+        let description = strings
+            .iter()
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+            .collect::<Vec<&str>>()
+            .join(" - ");
+        let tags = rest
+            .split_whitespace()
+            .filter_map(|token| token.strip_prefix('#'))
+            .filter(|tag| !tag.is_empty())
+            .map(String::from)
+            .collect();
+        return (description, tags, Vec::new());
+    }
+
+    // Ledger: [(code)] payee [| note] [; comment]
+    let mut body = body.trim();
+    if body.starts_with('(') {
+        if let Some(end) = body.find(')') {
+            body = body[end + 1..].trim_start();
+        }
+    }
+    let (payee, comment) = match body.split_once(';') {
+        Some((payee, comment)) => (payee, comment),
+        None => (body, ""),
+    };
+    let description = payee
+        .split('|')
+        .map(|part| part.trim())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" - ");
+    let (tags, metadata) = parse_comment(comment);
+
+    (description, tags, metadata)
+}
+
+/// Ledger tags (`:food:travel:`) and a `Key: value` pair of a comment
+fn parse_comment(comment: &str) -> (Vec<String>, Vec<(String, String)>) {
+    let comment = comment.trim();
+    let tags = comment
+        .split_whitespace()
+        .filter(|token| token.len() > 2 && token.starts_with(':') && token.ends_with(':'))
+        .flat_map(|token| token.split(':'))
+        .filter(|tag| !tag.is_empty())
+        .map(String::from)
+        .collect();
+    let metadata = COMMENT_METADATA_PATTERN
+        .captures(comment)
+        .map(|captures| (captures[1].to_string(), unquote(&captures[2])))
+        .into_iter()
+        .collect();
+
+    (tags, metadata)
+}
+
+fn parse_posting(number: usize, line: &str, config: &LedgerConfig) -> Result<Posting, String> {
+    let (body, comment) = match line.split_once(';') {
+        Some((body, comment)) => (body, comment),
+        None => (line, ""),
+    };
+    // Beancount posting flag
+    let body = body.trim();
+    let body = body
+        .strip_prefix("* ")
+        .or_else(|| body.strip_prefix("! "))
+        .unwrap_or(body)
+        .trim_start();
+
+    // Ledger accounts may contain single spaces, the amount follows two spaces or a tab
+    let separator = [body.find("  "), body.find('\t')]
+        .into_iter()
+        .flatten()
+        .min();
+    let (account, value) = match separator {
+        Some(index) => (body[..index].trim(), posting_value(&body[index..], config)?),
+        // Beancount only needs one space
+        None => match body.split_once(' ') {
+            Some((account, amount)) => match posting_value(amount, config) {
+                Ok(value) => (account, value),
+                Err(_) => (body, PostingValue::Elided),
+            },
+            None => (body, PostingValue::Elided),
+        },
+    };
+
+    Ok(Posting {
+        line: number,
+        account: account.to_string(),
+        value,
+        tags: parse_comment(comment).0,
+    })
+}
+
+/// Value of the amount of a posting in the store currency, converted with its price
+/// (`@ unit`, `@@ total`) or cost (`{unit}`, `{{total}}`) when in another currency.
+/// Bare numbers are in the configured currency.
+fn posting_value(text: &str, config: &LedgerConfig) -> Result<PostingValue, String> {
+    // Ledger balance assertion
+    let text = text.split('=').next().unwrap_or(text).trim();
+    if text.is_empty() {
+        return Ok(PostingValue::Elided);
+    }
+
+    let mut amount = text.to_string();
+    let mut price: Option<(String, bool)> = None;
+    if let (Some(start), Some(end)) = (text.find('{'), text.rfind('}')) {
+        let cost = text[start + 1..end].trim();
+        let total = cost.starts_with('{');
+        let cost = cost
+            .trim_matches(['{', '}'])
+            .split(", ")
+            .next()
+            .unwrap_or("");
+        price = Some((cost.to_string(), total));
+        amount = format!("{} {}", &text[..start], &text[end + 1..]);
+    }
+    if let Some((units, unit_price)) = amount.clone().split_once('@') {
+        price = match unit_price.strip_prefix('@') {
+            Some(total) => Some((total.to_string(), true)),
+            None => Some((unit_price.to_string(), false)),
+        };
+        amount = units.to_string();
+    }
+
+    let (quantity, currency) = parse_amount(&amount, &config.currency)
+        .ok_or_else(|| format!("Invalid amount `{}`", text))?;
+    if is_store_currency(&currency) {
+        return Money::parse(&quantity, Currency::STORE)
+            .map(PostingValue::Known)
+            .map_err(|e| e.to_string());
+    }
+
+    match price {
+        Some((price, total)) => {
+            let (price, price_currency) = parse_amount(&price, &config.currency)
+                .ok_or_else(|| format!("Invalid price `{}`", text))?;
+            if !is_store_currency(&price_currency) {
+                return Ok(PostingValue::Foreign(currency));
+            }
+            let value = if total {
+                let price = Money::parse(&price, Currency::STORE).map_err(|e| e.to_string())?;
+                match quantity.starts_with('-') {
+                    true => -price.abs(),
+                    false => price.abs(),
+                }
+            } else {
+                Money::parse_product(&price, &quantity, Currency::STORE)
+                    .map_err(|e| e.to_string())?
+            };
+            Ok(PostingValue::Known(value))
+        }
+        None => Ok(PostingValue::Foreign(currency)),
+    }
+}
+
+fn is_store_currency(code: &str) -> bool {
+    Currency::from_code(code) == Some(Currency::STORE)
+}
+
+/// Quantity as a plain decimal ("-1234.5") and commodity of an amount, bare numbers are in
+/// `default_currency`
+fn parse_amount(text: &str, default_currency: &str) -> Option<(String, String)> {
+    let captures = AMOUNT_PATTERN.captures(text.trim())?;
+    let digits = captures[3].replace(',', "");
+    let quantity = match (captures.get(1).is_some(), digits.strip_prefix('-')) {
+        (true, Some(positive)) => positive.to_string(),
+        (true, None) => format!("-{}", digits.trim_start_matches('+')),
+        (false, _) => digits,
+    };
+
+    let currency = match captures.get(2).or(captures.get(4)).map(|c| c.as_str()) {
+        Some("$") => "USD",
+        Some("€") => "EUR",
+        Some("£") => "GBP",
+        Some("₪") => "ILS",
+        Some(currency) => currency,
+        None => default_currency,
+    };
+
+    Some((quantity, currency.to_string()))
+}
+
+fn is_category_account(account: &str) -> bool {
+    let root = account.split(':').next().unwrap_or(account);
+    ["Expenses", "Income", "Revenue", "Revenues"]
+        .iter()
+        .any(|candidate| root.eq_ignore_ascii_case(candidate))
+}
+
+/// The tag mapped to the account in the config, else its first component below the root:
+/// "Expenses:Food:Groceries" -> "Food". The default accounts have no tag.
+pub fn tag_for_account(account: &str, config: &LedgerConfig) -> Option<String> {
+    if let Some((tag, _)) = config
+        .tag_accounts
+        .iter()
+        .find(|(_, mapped)| mapped.as_str() == account)
+    {
+        return Some(tag.clone());
+    }
+    if account == config.default_expense_account || account == config.default_income_account {
+        return None;
+    }
+
+    account
+        .split(':')
+        .nth(1)
+        .filter(|component| !component.is_empty())
+        .map(String::from)
+}
@@ -0,0 +1,218 @@
+use crate::model::expense::Expense;
+use crate::model::ledger::{JournalFormat, LedgerConfig};
+use crate::model::money::Currency;
+use crate::store::json_file_backend::write_atomically_with;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::error::Error as StdError;
+use std::io::Write;
+use std::path::Path;
+
+/// Sub-key of store_data holding the `LedgerConfig`
+pub static LEDGER_CONFIG_SUB_KEY: &str = "ledger_config";
+
+/// Date the beancount accounts are opened on. Fixed rather than the first exported date,
+/// so that exporting another range does not change the directives.
+static BEANCOUNT_OPEN_DATE: &str = "1970-01-01";
+
+static ACCOUNT_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(Assets|Liabilities|Equity|Income|Expenses)(:[A-Z0-9][A-Za-z0-9-]*)+$").unwrap()
+});
+static CURRENCY_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[A-Z][A-Z0-9]{1,23}$").unwrap());
+
+pub fn parse_ledger_config(value: Option<Value>) -> Result<LedgerConfig, Box<dyn StdError>> {
+    match value {
+        Some(value @ Value::Object(_)) => Ok(serde_json::from_value(value)
+            .map_err(|err| format!("Failed to deserialize ledger config: {}", err))?),
+        _ => Ok(LedgerConfig::default()),
+    }
+}
+
+/// Checks that every account and the currency are valid in all three formats
+pub fn validate_ledger_config(config: &LedgerConfig) -> Result<(), Box<dyn StdError>> {
+    if !CURRENCY_PATTERN.is_match(&config.currency) {
+        return Err(format!("Invalid currency: {}", config.currency).into());
+    }
+
+    let accounts = [
+        &config.default_expense_account,
+        &config.default_income_account,
+        &config.default_source_account,
+        &config.transfer_account,
+    ]
+    .into_iter()
+    .chain(config.tag_accounts.values())
+    .chain(config.source_accounts.values());
+
+    for account in accounts {
+        if !ACCOUNT_PATTERN.is_match(account) {
+            return Err(format!(
+                "Invalid account name: {}, it must start with Assets, Liabilities, Equity, Income or Expenses followed by capitalized components",
+                account
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// Turns a tag or definition key into an account component: "Rent/Mortgage" -> "Rent-Mortgage".
+/// Only ASCII is kept, names without any (e.g. Hebrew tags) should be mapped in the config.
+pub fn account_component(name: &str) -> String {
+    let words: Vec<String> = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect();
+
+    if words.is_empty() {
+        "Other".to_string()
+    } else {
+        words.join("-")
+    }
+}
+
+/// Account the expense is booked to: the first tag with a configured account, else one
+/// derived from the first tag
+pub fn category_account(expense: &Expense, config: &LedgerConfig) -> String {
+    if expense.is_transfer() {
+        return config.transfer_account.clone();
+    }
+
+    let tags = expense.get_tags();
+    if let Some(account) = tags.iter().find_map(|tag| config.tag_accounts.get(tag)) {
+        return account.clone();
+    }
+
+    let income = expense.get_amount().is_negative();
+    match tags.first() {
+        Some(tag) if income => format!("Income:{}", account_component(tag)),
+        Some(tag) => format!("Expenses:{}", account_component(tag)),
+        None if income => config.default_income_account.clone(),
+        None => config.default_expense_account.clone(),
+    }
+}
+
+/// Account the money comes from, derived from the import definition of the expense
+pub fn source_account(expense: &Expense, config: &LedgerConfig) -> String {
+    match expense.get_source() {
+        Some(source) => config
+            .source_accounts
+            .get(source)
+            .cloned()
+            .unwrap_or_else(|| format!("Assets:{}", account_component(source))),
+        None => config.default_source_account.clone(),
+    }
+}
+
+fn single_line(value: &str) -> String {
+    value.replace(['\r', '\n', '\t'], " ")
+}
+
+fn quoted(value: &str) -> String {
+    format!(
+        "\"{}\"",
+        single_line(value)
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+    )
+}
+
+/// Writes one balanced transaction per expense, oldest first, with the expense id as
+/// metadata so that the same expense keeps the same text across exports.
+pub fn write_journal(
+    writer: &mut dyn Write,
+    expenses: &[Expense],
+    format: JournalFormat,
+    config: &LedgerConfig,
+) -> Result<(), Box<dyn StdError>> {
+    validate_ledger_config(config)?;
+
+    let postings: Vec<(&Expense, String, String)> = expenses
+        .iter()
+        .map(|expense| {
+            (
+                expense,
+                category_account(expense, config),
+                source_account(expense, config),
+            )
+        })
+        .collect();
+
+    if format == JournalFormat::Beancount {
+        writeln!(
+            writer,
+            "option \"operating_currency\" \"{}\"",
+            Currency::STORE.code()
+        )?;
+        writeln!(writer)?;
+
+        let accounts: BTreeSet<&String> = postings
+            .iter()
+            .flat_map(|(_, category, source)| [category, source])
+            .collect();
+        for account in accounts {
+            writeln!(writer, "{} open {}", BEANCOUNT_OPEN_DATE, account)?;
+        }
+    }
+
+    for (expense, category, source) in postings.iter() {
+        // The postings are exact opposites, so the transaction always balances
+        let amount = expense.get_amount().to_fixed_string();
+        let negated = (-expense.get_amount()).to_fixed_string();
+        let currency = expense.get_amount().currency().code();
+        let width = category.len().max(source.len());
+
+        writeln!(writer)?;
+        match format {
+            JournalFormat::Beancount => {
+                writeln!(
+                    writer,
+                    "{} * {}",
+                    expense.get_date().format("%Y-%m-%d"),
+                    quoted(expense.get_description())
+                )?;
+                writeln!(writer, "  id: {}", quoted(expense.get_id()))?;
+                writeln!(writer, "  {:<width$}  {} {}", category, amount, currency)?;
+                writeln!(writer, "  {:<width$}  {} {}", source, negated, currency)?;
+            }
+            JournalFormat::Ledger | JournalFormat::Hledger => {
+                let date_format = match format {
+                    JournalFormat::Ledger => "%Y/%m/%d",
+                    _ => "%Y-%m-%d",
+                };
+                writeln!(
+                    writer,
+                    "{} * {}",
+                    expense.get_date().format(date_format),
+                    single_line(expense.get_description())
+                )?;
+                writeln!(writer, "    ; id: {}", expense.get_id())?;
+                writeln!(writer, "    {:<width$}  {} {}", category, amount, currency)?;
+                writeln!(writer, "    {:<width$}  {} {}", source, negated, currency)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the journal to `path`, streamed to a temporary file that replaces the target
+pub fn export_journal_to_path(
+    path: &Path,
+    expenses: &[Expense],
+    format: JournalFormat,
+    config: &LedgerConfig,
+) -> Result<(), Box<dyn StdError>> {
+    write_atomically_with(path, |writer| {
+        write_journal(writer, expenses, format, config)
+    })
+}
@@ -1 +1,12 @@
+pub mod aggregation_service;
 pub mod csv_file_service;
+pub mod duplicate_service;
+pub mod export_service;
+pub mod import_service;
+pub mod journal_parser;
+pub mod ledger_service;
+pub mod pivot_service;
+pub mod query_service;
+pub mod search_parser;
+pub mod tag_service;
+pub mod transfer_service;
@@ -0,0 +1,226 @@
+use crate::model::aggregation::UNTAGGED;
+use crate::model::expense::Expense;
+use crate::model::money::{CurrencyMismatchError, Money};
+use crate::model::pivot::{PivotDimension, PivotMeasure, PivotTable};
+use chrono::Datelike;
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error as StdError;
+
+/// Source label of expenses added manually or imported before sources were recorded
+pub const UNKNOWN_SOURCE: &str = "Unknown";
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// A dimension value: labels are ordered by `order` first (weekdays, amount bands),
+/// then alphabetically (tags, "2025-03" months, years)
+type DimensionKey = (usize, String);
+
+/// Running measures of the expenses falling in one cell, row, column or table
+#[derive(Clone, Copy, Debug, Default)]
+struct Accumulator {
+    sum: Money,
+    count: usize,
+    min: Option<Money>,
+    max: Option<Money>,
+}
+
+impl Accumulator {
+    fn add(&mut self, amount: Money) -> Result<(), CurrencyMismatchError> {
+        self.sum = match self.count {
+            0 => amount,
+            _ => self.sum.checked_add(amount)?,
+        };
+        self.count += 1;
+        self.min = Some(self.min.map_or(amount, |min| min.min(amount)));
+        self.max = Some(self.max.map_or(amount, |max| max.max(amount)));
+        Ok(())
+    }
+
+    fn value(&self, measure: PivotMeasure) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+
+        match measure {
+            PivotMeasure::Sum => self.sum.to_f64(),
+            PivotMeasure::Count => self.count as f64,
+            PivotMeasure::Avg => self.sum.divide(self.count).to_f64(),
+            PivotMeasure::Min => self.min.unwrap_or_default().to_f64(),
+            PivotMeasure::Max => self.max.unwrap_or_default().to_f64(),
+        }
+    }
+}
+
+/// Labels of the amount bands delimited by the ascending `edges`, e.g. "<10", "10-50", "50+"
+pub fn amount_band_labels(edges: &[f64]) -> Vec<String> {
+    let mut labels = Vec::with_capacity(edges.len() + 1);
+
+    if let Some(first) = edges.first() {
+        labels.push(format!("<{}", first));
+    }
+    for pair in edges.windows(2) {
+        labels.push(format!("{}-{}", pair[0], pair[1]));
+    }
+    if let Some(last) = edges.last() {
+        labels.push(format!("{}+", last));
+    }
+
+    labels
+}
+
+fn dimension_keys(
+    expense: &Expense,
+    dimension: PivotDimension,
+    band_labels: &[String],
+    band_edges: &[f64],
+) -> Vec<DimensionKey> {
+    let date = expense.get_date().date();
+
+    match dimension {
+        PivotDimension::Tag => {
+            let tags: Vec<DimensionKey> = expense
+                .get_tags()
+                .iter()
+                .filter(|tag| !tag.is_empty())
+                .map(|tag| (0, tag.clone()))
+                .collect();
+            if tags.is_empty() {
+                vec![(0, UNTAGGED.to_string())]
+            } else {
+                tags
+            }
+        }
+        PivotDimension::Month => vec![(0, date.format("%Y-%m").to_string())],
+        PivotDimension::Year => vec![(0, date.year().to_string())],
+        PivotDimension::Weekday => {
+            let index = date.weekday().num_days_from_monday() as usize;
+            vec![(index, WEEKDAYS[index].to_string())]
+        }
+        PivotDimension::Source => vec![(
+            0,
+            expense.get_source().unwrap_or(UNKNOWN_SOURCE).to_string(),
+        )],
+        PivotDimension::AmountBand => {
+            let index = band_edges
+                .iter()
+                .take_while(|edge| expense.get_amount().to_f64() >= **edge)
+                .count();
+            vec![(index, band_labels[index].clone())]
+        }
+    }
+}
+
+/// Builds a pivot matrix of the expenses over two dimensions.
+///
+/// Parameters:
+/// - `expenses`: The expenses to pivot, already filtered.
+/// - `rows`, `columns`: The dimensions of the rows and columns.
+/// - `measure`: What each cell holds.
+/// - `band_edges`: The ascending amounts delimiting the amount bands.
+///
+/// Returns:
+/// - `PivotTable`: The matrix with its labels and totals.
+pub fn pivot<'a, I>(
+    expenses: I,
+    rows: PivotDimension,
+    columns: PivotDimension,
+    measure: PivotMeasure,
+    band_edges: &[f64],
+) -> Result<PivotTable, Box<dyn StdError>>
+where
+    I: IntoIterator<Item = &'a Expense>,
+{
+    if band_edges.is_empty() || band_edges.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err("Amount band edges must be a non-empty ascending list".into());
+    }
+    let band_labels = amount_band_labels(band_edges);
+
+    let mut cells: BTreeMap<(DimensionKey, DimensionKey), Accumulator> = BTreeMap::new();
+    let mut row_totals: BTreeMap<DimensionKey, Accumulator> = BTreeMap::new();
+    let mut column_totals: BTreeMap<DimensionKey, Accumulator> = BTreeMap::new();
+    let mut grand_total = Accumulator::default();
+
+    for expense in expenses {
+        let amount = expense.get_amount();
+        let row_keys = dimension_keys(expense, rows, &band_labels, band_edges);
+        let column_keys = dimension_keys(expense, columns, &band_labels, band_edges);
+
+        for row in row_keys.iter() {
+            row_totals.entry(row.clone()).or_default().add(amount)?;
+            for column in column_keys.iter() {
+                cells
+                    .entry((row.clone(), column.clone()))
+                    .or_default()
+                    .add(amount)?;
+            }
+        }
+        for column in column_keys {
+            column_totals.entry(column).or_default().add(amount)?;
+        }
+        grand_total.add(amount)?;
+    }
+
+    let row_keys: BTreeSet<&DimensionKey> = row_totals.keys().collect();
+    let column_keys: BTreeSet<&DimensionKey> = column_totals.keys().collect();
+
+    let matrix = row_keys
+        .iter()
+        .map(|row| {
+            column_keys
+                .iter()
+                .map(|column| {
+                    cells
+                        .get(&((*row).clone(), (*column).clone()))
+                        .map(|cell| cell.value(measure))
+                })
+                .collect()
+        })
+        .collect();
+
+    Ok(PivotTable {
+        rows,
+        columns,
+        measure,
+        row_labels: row_keys.iter().map(|(_, label)| label.clone()).collect(),
+        column_labels: column_keys.iter().map(|(_, label)| label.clone()).collect(),
+        cells: matrix,
+        row_totals: row_totals.values().map(|acc| acc.value(measure)).collect(),
+        column_totals: column_totals
+            .values()
+            .map(|acc| acc.value(measure))
+            .collect(),
+        grand_total: grand_total.value(measure),
+    })
+}
+
+/// Writes a pivot table as CSV: a header of column labels, one line per row label,
+/// and the totals as last column and last line. Empty cells are left blank.
+pub fn pivot_to_csv(table: &PivotTable) -> Result<String, Box<dyn StdError>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    let mut header = vec![String::new()];
+    header.extend(table.column_labels.iter().cloned());
+    header.push(String::from("Total"));
+    writer.write_record(&header)?;
+
+    for (index, label) in table.row_labels.iter().enumerate() {
+        let mut record = vec![label.clone()];
+        record.extend(
+            table.cells[index]
+                .iter()
+                .map(|cell| cell.map(|value| value.to_string()).unwrap_or_default()),
+        );
+        record.push(table.row_totals[index].to_string());
+        writer.write_record(&record)?;
+    }
+
+    let mut totals = vec![String::from("Total")];
+    totals.extend(table.column_totals.iter().map(|value| value.to_string()));
+    totals.push(table.grand_total.to_string());
+    writer.write_record(&totals)?;
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|err| format!("Failed to write pivot CSV: {}", err))?;
+    Ok(String::from_utf8(bytes)?)
+}
@@ -0,0 +1,198 @@
+use crate::model::expense::Expense;
+use crate::model::money::Money;
+use crate::model::query::{
+    AmountSign, ExpenseFilter, ExpensePage, ExpenseQuery, SortField, SortOrder, DEFAULT_PAGE_SIZE,
+};
+use crate::service::search_parser::{parse_search_query, SearchQuery};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use regex::Regex;
+use std::cmp::Ordering;
+use std::error::Error as StdError;
+
+/// An `ExpenseFilter` ready to be matched, with its regex compiled and text lowercased once
+pub struct CompiledFilter<'a> {
+    filter: &'a ExpenseFilter,
+    text: Option<String>,
+    regex: Option<Regex>,
+    search: Option<SearchQuery>,
+}
+
+impl<'a> CompiledFilter<'a> {
+    pub fn new(filter: &'a ExpenseFilter) -> Result<Self, Box<dyn StdError>> {
+        let regex = match &filter.regex {
+            Some(pattern) => Some(
+                Regex::new(pattern).map_err(|err| format!("Invalid description regex: {}", err))?,
+            ),
+            None => None,
+        };
+
+        let search = match &filter.search {
+            Some(input) => {
+                Some(parse_search_query(input).map_err(|err| format!("Invalid search: {}", err))?)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            filter,
+            text: filter.text.as_ref().map(|text| text.to_lowercase()),
+            regex,
+            search,
+        })
+    }
+
+    pub fn matches(&self, expense: &Expense) -> bool {
+        let filter = self.filter;
+        let date = expense.get_date().date();
+        let amount = expense.get_amount();
+        let tags = expense.get_tags();
+
+        if filter.start_date.is_some_and(|start| date < start)
+            || filter.end_date.is_some_and(|end| date > end)
+        {
+            return false;
+        }
+
+        if filter.min_amount.is_some_and(|min| amount < min)
+            || filter.max_amount.is_some_and(|max| amount > max)
+        {
+            return false;
+        }
+
+        match filter.sign {
+            Some(AmountSign::Positive) if !amount.is_positive() => return false,
+            Some(AmountSign::Negative) if !amount.is_negative() => return false,
+            _ => {}
+        }
+
+        if !filter.tags_any.is_empty() && !filter.tags_any.iter().any(|tag| tags.contains(tag)) {
+            return false;
+        }
+        if !filter.tags_all.iter().all(|tag| tags.contains(tag)) {
+            return false;
+        }
+        if filter.tags_none.iter().any(|tag| tags.contains(tag)) {
+            return false;
+        }
+
+        if let Some(text) = &self.text {
+            if !expense.get_description().to_lowercase().contains(text) {
+                return false;
+            }
+        }
+
+        if let Some(regex) = &self.regex {
+            if !regex.is_match(expense.get_description()) {
+                return false;
+            }
+        }
+
+        if let Some(search) = &self.search {
+            if !search.matches(expense) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Inclusive datetime bounds of the filter's date range, used to narrow candidates by index
+pub fn datetime_bounds(filter: &ExpenseFilter) -> Option<(NaiveDateTime, NaiveDateTime)> {
+    if filter.start_date.is_none() && filter.end_date.is_none() {
+        return None;
+    }
+
+    let start = filter
+        .start_date
+        .unwrap_or(NaiveDate::MIN)
+        .and_time(NaiveTime::MIN);
+    let end = filter
+        .end_date
+        .unwrap_or(NaiveDate::MAX)
+        .and_hms_opt(23, 59, 59)?;
+
+    Some((start, end))
+}
+
+/// Every candidate matching `filter`, oldest first
+pub fn matching_expenses<'a, I>(
+    candidates: I,
+    filter: &ExpenseFilter,
+) -> Result<Vec<&'a Expense>, Box<dyn StdError>>
+where
+    I: IntoIterator<Item = &'a Expense>,
+{
+    let filter = CompiledFilter::new(filter)?;
+
+    let mut matched: Vec<&Expense> = candidates
+        .into_iter()
+        .filter(|expense| filter.matches(expense))
+        .collect();
+    matched.sort_by(|a, b| compare(a, b, SortField::Date));
+
+    Ok(matched)
+}
+
+fn compare(a: &Expense, b: &Expense, sort_by: SortField) -> Ordering {
+    let ordering = match sort_by {
+        SortField::Date => a.get_date().cmp(b.get_date()),
+        SortField::Amount => a.get_amount().cmp(&b.get_amount()),
+        SortField::Description => a
+            .get_description()
+            .to_lowercase()
+            .cmp(&b.get_description().to_lowercase()),
+    };
+
+    // Ties broken by id so that paging is stable
+    ordering.then_with(|| a.get_id().cmp(b.get_id()))
+}
+
+/// Filters, sorts and pages the candidate expenses.
+///
+/// Parameters:
+/// - `candidates`: The expenses to query, possibly already narrowed down by an index.
+/// - `query`: The filter, sort and page to apply.
+///
+/// Returns:
+/// - `ExpensePage`: The requested page plus the count and sum of every match.
+pub fn run_query<'a, I>(
+    candidates: I,
+    query: &ExpenseQuery,
+) -> Result<ExpensePage, Box<dyn StdError>>
+where
+    I: IntoIterator<Item = &'a Expense>,
+{
+    let filter = CompiledFilter::new(&query.filter)?;
+    let page_size = query.page_size.unwrap_or(DEFAULT_PAGE_SIZE).max(1);
+
+    let mut matched: Vec<&Expense> = candidates
+        .into_iter()
+        .filter(|expense| filter.matches(expense))
+        .collect();
+
+    let total_amount: Money = matched
+        .iter()
+        .map(|expense| expense.get_amount())
+        .sum::<Result<Money, _>>()?;
+
+    matched.sort_by(|a, b| match query.sort_order {
+        SortOrder::Asc => compare(a, b, query.sort_by),
+        SortOrder::Desc => compare(b, a, query.sort_by),
+    });
+
+    let expenses = matched
+        .iter()
+        .skip(query.page.saturating_mul(page_size))
+        .take(page_size)
+        .map(|expense| (*expense).clone())
+        .collect();
+
+    Ok(ExpensePage {
+        expenses,
+        total_count: matched.len(),
+        total_amount,
+        page: query.page,
+        page_size,
+    })
+}
@@ -0,0 +1,381 @@
+use crate::model::expense::Expense;
+use crate::model::money::{Currency, Money};
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+use std::error::Error as StdError;
+use std::fmt;
+
+/// A syntax error in a search query, at a character (not byte) position of the input
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SearchParseError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for SearchParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl StdError for SearchParseError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SearchTerm {
+    // `tag:Food`, case-insensitive
+    Tag(String),
+    // `source:Amex`, case-insensitive
+    Source(String),
+    // Bare word or "quoted text", case-insensitive substring of the description
+    Text(String),
+    // `amount>50`, `amount:12.5`
+    Amount(Comparison, Money),
+    // `amount:10..50`, both inclusive
+    AmountRange(Money, Money),
+    // `date:2025-01..2025-03`, both inclusive, either side may be open
+    Date(Option<NaiveDate>, Option<NaiveDate>),
+    // Any term prefixed with `-`
+    Not(Box<SearchTerm>),
+}
+
+impl SearchTerm {
+    pub fn matches(&self, expense: &Expense) -> bool {
+        match self {
+            SearchTerm::Tag(tag) => {
+                let tag = tag.to_lowercase();
+                expense
+                    .get_tags()
+                    .iter()
+                    .any(|candidate| candidate.to_lowercase() == tag)
+            }
+            SearchTerm::Source(source) => expense
+                .get_source()
+                .is_some_and(|candidate| candidate.eq_ignore_ascii_case(source)),
+            SearchTerm::Text(text) => expense
+                .get_description()
+                .to_lowercase()
+                .contains(&text.to_lowercase()),
+            SearchTerm::Amount(comparison, value) => {
+                let amount = expense.get_amount();
+                let value = *value;
+                match comparison {
+                    Comparison::Gt => amount > value,
+                    Comparison::Ge => amount >= value,
+                    Comparison::Lt => amount < value,
+                    Comparison::Le => amount <= value,
+                    Comparison::Eq => amount == value,
+                }
+            }
+            SearchTerm::AmountRange(min, max) => {
+                let amount = expense.get_amount();
+                amount >= *min && amount <= *max
+            }
+            SearchTerm::Date(start, end) => {
+                let date = expense.get_date().date();
+                start.is_none_or(|start| date >= start) && end.is_none_or(|end| date <= end)
+            }
+            SearchTerm::Not(term) => !term.matches(expense),
+        }
+    }
+}
+
+/// A parsed search query, matching expenses that satisfy every term
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchQuery {
+    pub terms: Vec<SearchTerm>,
+}
+
+impl SearchQuery {
+    pub fn matches(&self, expense: &Expense) -> bool {
+        self.terms.iter().all(|term| term.matches(expense))
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn error<T>(position: usize, message: String) -> Result<T, SearchParseError> {
+        Err(SearchParseError { position, message })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// Reads characters up to whitespace or one of `stops`
+    fn read_word(&mut self, stops: &[char]) -> String {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || stops.contains(&c) {
+                break;
+            }
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// Reads a "quoted" string, the position being on the opening quote
+    fn read_quoted(&mut self) -> Result<String, SearchParseError> {
+        let start = self.pos;
+        self.pos += 1;
+
+        let mut text = String::new();
+        loop {
+            match self.peek() {
+                Some('"') => {
+                    self.pos += 1;
+                    return Ok(text);
+                }
+                Some(c) => {
+                    text.push(c);
+                    self.pos += 1;
+                }
+                None => return Self::error(start, "Unterminated quoted text".to_string()),
+            }
+        }
+    }
+
+    /// Reads the value of a `field:` term, quoted or up to the next whitespace
+    fn read_value(&mut self, field: &str) -> Result<(usize, String), SearchParseError> {
+        let start = self.pos;
+        let value = if self.peek() == Some('"') {
+            self.read_quoted()?
+        } else {
+            self.read_word(&[])
+        };
+
+        if value.is_empty() {
+            return Self::error(start, format!("Expected a value after '{}:'", field));
+        }
+
+        Ok((start, value))
+    }
+
+    fn read_comparison(&mut self) -> Option<Comparison> {
+        let comparison = match (self.peek()?, self.chars.get(self.pos + 1)) {
+            ('>', Some('=')) => Comparison::Ge,
+            ('<', Some('=')) => Comparison::Le,
+            ('>', _) => Comparison::Gt,
+            ('<', _) => Comparison::Lt,
+            ('=', _) => Comparison::Eq,
+            _ => return None,
+        };
+
+        self.pos += match comparison {
+            Comparison::Ge | Comparison::Le => 2,
+            _ => 1,
+        };
+        Some(comparison)
+    }
+
+    fn parse_term(&mut self) -> Result<SearchTerm, SearchParseError> {
+        let start = self.pos;
+
+        if self.peek() == Some('-') {
+            self.pos += 1;
+            if self.peek().is_none_or(char::is_whitespace) {
+                return Self::error(start, "Expected a term after '-'".to_string());
+            }
+            let term = self.parse_term()?;
+            if matches!(term, SearchTerm::Not(_)) {
+                return Self::error(start, "A term cannot be negated twice".to_string());
+            }
+            return Ok(SearchTerm::Not(Box::new(term)));
+        }
+
+        if self.peek() == Some('"') {
+            return Ok(SearchTerm::Text(self.read_quoted()?));
+        }
+
+        let word = self.read_word(&[':', '<', '>', '=']);
+        let field = word.to_lowercase();
+
+        match self.peek() {
+            Some(':') => {
+                self.pos += 1;
+                let (value_start, value) = self.read_value(&field)?;
+                match field.as_str() {
+                    "tag" => Ok(SearchTerm::Tag(value)),
+                    "source" => Ok(SearchTerm::Source(value)),
+                    "desc" | "description" => Ok(SearchTerm::Text(value)),
+                    "amount" => parse_amount_value(value_start, &value),
+                    "date" => parse_date_range(value_start, &value),
+                    _ => Self::error(start, format!("Unknown field '{}'", word)),
+                }
+            }
+            Some('<') | Some('>') | Some('=') => {
+                if field != "amount" {
+                    return Self::error(
+                        start,
+                        format!("Comparisons are only supported on amount, not '{}'", word),
+                    );
+                }
+                let comparison = self.read_comparison().ok_or_else(|| SearchParseError {
+                    position: self.pos,
+                    message: "Expected a comparison".to_string(),
+                })?;
+                let (value_start, value) = self.read_value(&field)?;
+                Ok(SearchTerm::Amount(
+                    comparison,
+                    parse_number(value_start, &value)?,
+                ))
+            }
+            _ if word.is_empty() => Self::error(start, "Expected a search term".to_string()),
+            _ => Ok(SearchTerm::Text(word)),
+        }
+    }
+}
+
+/// Reads an amount exactly, refusing values that are not finite or do not fit in `Money`
+fn parse_number(position: usize, value: &str) -> Result<Money, SearchParseError> {
+    Money::parse(value, Currency::STORE).map_err(|_| SearchParseError {
+        position,
+        message: format!("Invalid amount '{}'", value),
+    })
+}
+
+fn parse_amount_value(position: usize, value: &str) -> Result<SearchTerm, SearchParseError> {
+    match value.split_once("..") {
+        Some((min, max)) => {
+            let max_position = position + min.chars().count() + 2;
+            let min = parse_number(position, min)?;
+            let max = parse_number(max_position, max)?;
+            if min > max {
+                return Parser::error(position, "Amount range is reversed".to_string());
+            }
+            Ok(SearchTerm::AmountRange(min, max))
+        }
+        None => Ok(SearchTerm::Amount(
+            Comparison::Eq,
+            parse_number(position, value)?,
+        )),
+    }
+}
+
+/// Parses `YYYY`, `YYYY-MM` or `YYYY-MM-DD` into the first and last day of that period
+fn parse_partial_date(
+    position: usize,
+    value: &str,
+) -> Result<(NaiveDate, NaiveDate), SearchParseError> {
+    let invalid = || SearchParseError {
+        position,
+        message: format!(
+            "Invalid date '{}', expected YYYY, YYYY-MM or YYYY-MM-DD",
+            value
+        ),
+    };
+
+    let parts: Vec<&str> = value.split('-').collect();
+    let numbers: Vec<u32> = parts
+        .iter()
+        .map(|part| part.parse::<u32>().map_err(|_| invalid()))
+        .collect::<Result<_, _>>()?;
+
+    match numbers.as_slice() {
+        [year] => {
+            let year = *year as i32;
+            Ok((
+                NaiveDate::from_ymd_opt(year, 1, 1).ok_or_else(invalid)?,
+                NaiveDate::from_ymd_opt(year, 12, 31).ok_or_else(invalid)?,
+            ))
+        }
+        [year, month] => {
+            let first = NaiveDate::from_ymd_opt(*year as i32, *month, 1).ok_or_else(invalid)?;
+            let next_month = if first.month() == 12 {
+                NaiveDate::from_ymd_opt(first.year() + 1, 1, 1)
+            } else {
+                NaiveDate::from_ymd_opt(first.year(), first.month() + 1, 1)
+            }
+            .ok_or_else(invalid)?;
+            Ok((first, next_month.pred_opt().ok_or_else(invalid)?))
+        }
+        [year, month, day] => {
+            let date = NaiveDate::from_ymd_opt(*year as i32, *month, *day).ok_or_else(invalid)?;
+            Ok((date, date))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+fn parse_date_range(position: usize, value: &str) -> Result<SearchTerm, SearchParseError> {
+    let (start, end) = match value.split_once("..") {
+        Some((start, end)) => {
+            let end_position = position + start.chars().count() + 2;
+            let start = match start {
+                "" => None,
+                start => Some(parse_partial_date(position, start)?.0),
+            };
+            let end = match end {
+                "" => None,
+                end => Some(parse_partial_date(end_position, end)?.1),
+            };
+            if start.is_none() && end.is_none() {
+                return Parser::error(position, "Date range needs at least one side".to_string());
+            }
+            (start, end)
+        }
+        None => {
+            let (first, last) = parse_partial_date(position, value)?;
+            (Some(first), Some(last))
+        }
+    };
+
+    if let (Some(start), Some(end)) = (start, end) {
+        if start > end {
+            return Parser::error(position, "Date range is reversed".to_string());
+        }
+    }
+
+    Ok(SearchTerm::Date(start, end))
+}
+
+/// Parses the compact search syntax used by the table view and saved searches.
+///
+/// Terms are separated by whitespace and must all match:
+/// - `tag:Food`, `source:Amex`: tag or import source, case-insensitive
+/// - `amount>50`, `amount<=10`, `amount:12.5`, `amount:10..50`
+/// - `date:2025`, `date:2025-01..2025-03`, `date:..2025-03-15` (bounds inclusive)
+/// - `"blue bottle"` or a bare word: text contained in the description
+/// - `-term`: negation of any term, e.g. `-tag:Gifts`
+///
+/// Returns:
+/// - `Result<SearchQuery, SearchParseError>`: The query, or the first syntax error with its position
+pub fn parse_search_query(input: &str) -> Result<SearchQuery, SearchParseError> {
+    let mut parser = Parser {
+        chars: input.chars().collect(),
+        pos: 0,
+    };
+    let mut terms = Vec::new();
+
+    loop {
+        parser.skip_whitespace();
+        if parser.peek().is_none() {
+            break;
+        }
+        terms.push(parser.parse_term()?);
+
+        if parser.peek().is_some_and(|c| !c.is_whitespace()) {
+            return Parser::error(parser.pos, "Expected whitespace between terms".to_string());
+        }
+    }
+
+    Ok(SearchQuery { terms })
+}
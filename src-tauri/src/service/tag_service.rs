@@ -0,0 +1,67 @@
+use std::collections::BTreeMap;
+use std::error::Error as StdError;
+
+/// Trims a tag name given by the user, refusing an empty one
+pub fn normalize_tag_name(name: &str) -> Result<String, Box<dyn StdError>> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Tag name must not be empty".into());
+    }
+    Ok(name.to_string())
+}
+
+/// The tags of one expense with every tag of `sources` replaced by `replacement`, or
+/// dropped when there is none. The replacement takes the place of the first replaced tag
+/// and is never listed twice.
+///
+/// Returns None when the expense has none of the `sources`, i.e. it is left untouched.
+pub fn replace_tags(
+    tags: &[String],
+    sources: &[String],
+    replacement: Option<&str>,
+) -> Option<Vec<String>> {
+    if !tags.iter().any(|tag| sources.contains(tag)) {
+        return None;
+    }
+
+    let mut replaced: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = match (sources.contains(tag), replacement) {
+            (false, _) => tag.as_str(),
+            (true, Some(replacement)) => replacement,
+            (true, None) => continue,
+        };
+        if !replaced.iter().any(|kept| kept == tag) {
+            replaced.push(tag.to_string());
+        }
+    }
+
+    Some(replaced)
+}
+
+/// Moves the ledger accounts of `sources` to `replacement` in `tag_accounts`, keeping the
+/// account the replacement already had, or drops them when there is no replacement.
+///
+/// Returns true when `tag_accounts` changed.
+pub fn replace_tag_accounts(
+    tag_accounts: &mut BTreeMap<String, String>,
+    sources: &[String],
+    replacement: Option<&str>,
+) -> bool {
+    let mut moved: Option<String> = None;
+    for source in sources {
+        if let Some(account) = tag_accounts.remove(source) {
+            moved.get_or_insert(account);
+        }
+    }
+
+    match (moved, replacement) {
+        (Some(account), Some(replacement)) => {
+            tag_accounts
+                .entry(replacement.to_string())
+                .or_insert(account);
+            true
+        }
+        (moved, _) => moved.is_some(),
+    }
+}
@@ -0,0 +1,93 @@
+use crate::model::expense::Expense;
+use crate::model::transfer::{transfer_pair_id, TransferPair, TransferStatus};
+use std::collections::{HashMap, HashSet};
+
+/// Finds opposite-signed, equal-magnitude expenses from different sources whose dates are
+/// at most `window_days` apart and suggests them as transfer pairs.
+///
+/// Each expense is used in at most one pair; when several candidates match, the one closest
+/// in date wins. Expenses already linked to a transfer are skipped.
+///
+/// An expense without a source (stored before sources were recorded, or entered manually)
+/// counts as coming from an unknown account and may pair with any other expense. The source
+/// cannot be backfilled, the import history only keeps import dates.
+///
+/// Parameters:
+/// - `expenses`: The expenses to search through.
+/// - `window_days`: The maximum number of days between the two legs of a transfer.
+/// - `excluded_pair_ids`: Pair ids that must not be suggested again (e.g. rejected pairs).
+///
+/// Returns:
+/// - `Vec<TransferPair>`: The suggested pairs, ordered by outflow date.
+pub fn find_transfer_candidates(
+    expenses: &[Expense],
+    window_days: i64,
+    excluded_pair_ids: &HashSet<String>,
+) -> Vec<TransferPair> {
+    let mut outflows: HashMap<i64, Vec<&Expense>> = HashMap::new();
+    let mut inflows: HashMap<i64, Vec<&Expense>> = HashMap::new();
+
+    for expense in expenses {
+        if expense.is_transfer() {
+            continue;
+        }
+
+        let cents = expense.get_amount().abs().minor_units();
+        if cents == 0 {
+            continue;
+        }
+
+        if expense.get_amount().is_positive() {
+            outflows.entry(cents).or_default().push(expense);
+        } else {
+            inflows.entry(cents).or_default().push(expense);
+        }
+    }
+
+    let mut pairs: Vec<(&Expense, TransferPair)> = Vec::new();
+
+    for (cents, mut group_outflows) in outflows {
+        let group_inflows = match inflows.get(&cents) {
+            Some(group) => group,
+            None => continue,
+        };
+
+        group_outflows.sort_by(|a, b| a.get_date().cmp(b.get_date()));
+        let mut used_inflows: HashSet<&str> = HashSet::new();
+
+        for outflow in group_outflows {
+            let best_match = group_inflows
+                .iter()
+                .filter(|inflow| !inflow.has_same_source_as(outflow))
+                .filter(|inflow| !used_inflows.contains(inflow.get_id()))
+                .map(|inflow| {
+                    let gap = (*inflow.get_date() - *outflow.get_date()).num_days().abs();
+                    (gap, *inflow)
+                })
+                .filter(|(gap, inflow)| {
+                    *gap <= window_days
+                        && !excluded_pair_ids
+                            .contains(&transfer_pair_id(outflow.get_id(), inflow.get_id()))
+                })
+                .min_by(|(gap_a, a), (gap_b, b)| {
+                    gap_a.cmp(gap_b).then_with(|| a.get_id().cmp(b.get_id()))
+                });
+
+            if let Some((_, inflow)) = best_match {
+                used_inflows.insert(inflow.get_id());
+                pairs.push((
+                    outflow,
+                    TransferPair::new(outflow.get_id(), inflow.get_id(), TransferStatus::Suggested),
+                ));
+            }
+        }
+    }
+
+    pairs.sort_by(|(a, pair_a), (b, pair_b)| {
+        a.get_date()
+            .cmp(b.get_date())
+            .then_with(|| pair_a.id.cmp(&pair_b.id))
+    });
+
+    pairs.into_iter().map(|(_, pair)| pair).collect()
+}
@@ -1,17 +1,40 @@
+use crate::model::audit::AuditEntry;
 use crate::model::expense::Expense;
+use crate::model::import::{ImportMode, ImportReport};
+use crate::model::migration::MigrationReport;
+use crate::model::money::{CurrencyMismatchError, Money};
+use crate::model::query::{DescriptionMatch, ExpenseFilter, ExpensePage, ExpenseQuery};
+use crate::model::tag::TagSummary;
+use crate::model::transfer::{TransferPair, TransferStatus};
+use crate::model::undo::{UndoResult, UndoState};
+use crate::service::import_service::{merge_key, validate_import};
+use crate::service::ledger_service::{parse_ledger_config, LEDGER_CONFIG_SUB_KEY};
+use crate::service::query_service::{datetime_bounds, matching_expenses, run_query};
+use crate::service::tag_service::{normalize_tag_name, replace_tag_accounts, replace_tags};
+use crate::service::transfer_service::find_transfer_candidates;
+use crate::store::audit::{audit_entries, parse_audit_log, AuditRetention, AUDIT_LOG_SUB_KEY};
+use crate::store::backend::{StorageBackend, EXPENSES_SUB_KEY};
+use crate::store::command_context::{current_command, CommandContext};
+use crate::store::encryption::EncryptedStorage;
+use crate::store::expense_cache::ExpenseCache;
+use crate::store::json_file_backend::JsonFileBackend;
+use crate::store::schema::migrate;
+use crate::store::settings::{BackendKind, StoreSettings, STORE_SETTINGS_FILE};
+use crate::store::sqlite_store::{SqliteStore, SQLITE_DB_FILE};
+use crate::store::transaction::{ConflictError, StoreTransaction};
+use crate::store::undo::{UndoGroup, UndoHistory, UNDOABLE_COMMANDS};
+use crate::utils::generate_uuid;
 use blake3::Hasher;
-use chrono::{DateTime, Utc};
-use serde_json::{Map, Value};
-use std::collections::HashMap;
+use chrono::{NaiveDateTime, Utc};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::error::Error as StdError;
-use std::sync::Arc;
-use std::time::SystemTime;
-use tauri::Wry;
-use tauri_plugin_store::Store;
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
 
 /// All data lives under a single top-level key "store_data":
 /// {
-///   "expenses": { "<hash>": { ... }, ... },
+///   "expenses": { "<id>": { ... }, ... },
 ///   "forecast_config": { ... },
 ///   ...
 /// }
@@ -20,307 +43,1364 @@ use tauri_plugin_store::Store;
 /// to read/write within this object.
 
 pub static STORE_DATA_KEY: &str = "store_data";
-static EXPENSES_SUB_KEY: &str = "expenses";
+/// File name of the JSON store, inside the app data directory
+pub static STORE_FILE: &str = "store.json";
+static TRANSFERS_SUB_KEY: &str = "transfers";
+/// Command the changes of a restored snapshot are logged under
+pub static RESTORE_COMMAND: &str = "restore_backup";
 
 pub struct AddedResult {
     pub added_count: u16,
     pub duplicate_count: u16,
 }
 
-/// Generate a deterministic hash for an Expense based on description, date, and amount
-fn generate_hash_for_new_entry(
-    expense: &Expense,
-    manual: bool,
-) -> Result<String, Box<dyn StdError>> {
-    let mut input = format!(
-        "{}:{}:{}",
+/// Builds the (description, date, amount) identity key of an Expense
+fn identity_key(expense: &Expense) -> String {
+    identity_key_of(
         expense.get_description(),
         expense.get_date(),
-        expense.get_amount()
-    );
+        expense.get_amount(),
+    )
+}
+
+/// Identity key of the given content, `amount` as printed when the fingerprint was made
+pub(crate) fn identity_key_of(
+    description: &str,
+    date: &NaiveDateTime,
+    amount: impl std::fmt::Display,
+) -> String {
+    format!("{}:{}:{}", description, date, amount)
+}
 
-    if manual {
-        let datetime: DateTime<Utc> = SystemTime::now().into();
-        let formatted_time = datetime.format("%Y-%m-%d %H:%M:%S").to_string();
-        input.insert_str(0, &formatted_time);
+/// Hash of an identity key and of the occurrence of the row within its identical ones
+pub(crate) fn hash_identity(identity: &str, occurrence: usize) -> String {
+    let mut input = identity.to_string();
+
+    if occurrence > 0 {
+        input.push_str(&format!("#{}", occurrence));
     }
 
-    let hash = Hasher::new().update(input.as_bytes()).finalize();
-    let hash_str = hash.to_hex().to_string();
+    Hasher::new()
+        .update(input.as_bytes())
+        .finalize()
+        .to_hex()
+        .to_string()
+}
+
+/// Computes the occurrence index of every expense within its (description, date, amount) group,
+/// in the order the rows appear in the source file.
+///
+/// Two identical subway rides on the same day get occurrences 0 and 1, so re-importing the same
+/// (or an overlapping) statement yields the same indices and stays idempotent.
+pub fn occurrence_indices(expenses: &[Expense]) -> Vec<usize> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    expenses
+        .iter()
+        .map(|expense| {
+            let count = seen.entry(identity_key(expense)).or_insert(0);
+            let occurrence = *count;
+            *count += 1;
+            occurrence
+        })
+        .collect()
+}
+
+/// Generate the import fingerprint of an Expense, a deterministic hash of its description,
+/// date, amount and the row's occurrence index within its (description, date, amount) group.
+///
+/// The first occurrence hashes exactly like the ids of the original description/date/amount
+/// scheme, which schema migration 2 keeps as the fingerprints of the older expenses, so
+/// re-importing an old statement still finds its rows. Unlike the id, the fingerprint is
+/// never recomputed after an edit.
+pub fn generate_fingerprint(
+    expense: &Expense,
+    occurrence: usize,
+) -> Result<String, Box<dyn StdError>> {
+    Ok(hash_identity(&identity_key(expense), occurrence))
+}
 
-    Ok(hash_str)
+/// Helper struct for backend store operations.
+///
+/// Generic over where the data is persisted, so the store logic runs without a Tauri app
+/// (e.g. on a `MemoryBackend` in tests). The app uses the boxed default, picked at startup.
+pub struct ExpenseStore<B: StorageBackend = Box<dyn StorageBackend>> {
+    backend: B,
+    // Loaded lazily on first access, None until then or after an invalidation.
+    // Shared with the running reads and transaction, a commit only copies it while one
+    // of them still holds it.
+    cache: RwLock<Option<Arc<ExpenseCache>>>,
+    // Serializes the transactions, so two commands never interleave their read-modify-write
+    write_lock: Mutex<()>,
+    history: Mutex<UndoHistory>,
+    audit_retention: AuditRetention,
 }
 
-/// Helper struct for backend store operations
-pub struct ExpenseStore {
-    store: Arc<Store<Wry>>,
+/// Where the changes of a transaction go in the undo history
+enum HistoryMode {
+    /// A regular command: undoable commands push a step, the others clear the redo stack
+    Record,
+    /// Undoing the step of `command`, the changes become a redo step
+    Undo(String),
+    /// Redoing the step of `command`, the changes become an undo step again
+    Redo(String),
 }
 
 impl ExpenseStore {
-    pub fn new(store: Arc<Store<Wry>>) -> Self {
-        Self { store }
-    }
+    /// Opens the backend selected in the store settings file of `app_data_dir`.
+    /// When it is not the backend opened last, all the data of that one is copied into it
+    /// first, so switching back and forth never reopens stale data.
+    pub fn open_selected_backend(app_data_dir: &Path) -> Result<Self, Box<dyn StdError>> {
+        let settings_path = app_data_dir.join(STORE_SETTINGS_FILE);
+        let mut settings = StoreSettings::load(&settings_path)?;
+        let json_store = JsonFileBackend::open(app_data_dir.join(STORE_FILE))?;
+        let switched = settings.backend != settings.active_backend;
+
+        let store = match settings.backend {
+            BackendKind::Json => {
+                if switched {
+                    let db = SqliteStore::open(app_data_dir.join(SQLITE_DB_FILE))?;
+                    json_store.write_data(&db.read_data()?)?;
+                    Self::copy_audit_log(&db, &json_store)?;
+                    println!("Copied {} data into {}", SQLITE_DB_FILE, STORE_FILE);
+                }
+                Self::new(Box::new(json_store))
+            }
+            // SQLite stores data unencrypted, an encrypted store is never copied into it
+            BackendKind::Sqlite if switched && json_store.vault().is_enabled() => {
+                println!(
+                    "{} is encrypted, keeping it since {} would store the data unencrypted",
+                    STORE_FILE, SQLITE_DB_FILE
+                );
+                settings.backend = BackendKind::Json;
+                Self::new(Box::new(json_store))
+            }
+            BackendKind::Sqlite => {
+                std::fs::create_dir_all(app_data_dir)?;
+                let db = SqliteStore::open(app_data_dir.join(SQLITE_DB_FILE))?;
+                if switched {
+                    db.replace_all_data(&json_store.read_data()?)?;
+                    Self::copy_audit_log(&json_store, &db)?;
+                    println!("Copied {} data into {}", STORE_FILE, SQLITE_DB_FILE);
+                }
+                Self::new(Box::new(db))
+            }
+        };
 
-    fn get_store_data(&self) -> Value {
-        self.store.get(STORE_DATA_KEY).unwrap_or(Value::Null)
-    }
+        if switched {
+            settings.active_backend = settings.backend;
+            settings.save(&settings_path)?;
+        }
 
-    fn save_store_data(&self, data: &Value) -> Result<bool, Box<dyn StdError>> {
-        self.store.set(STORE_DATA_KEY, data.clone());
-        self.store.save()?;
-        Ok(true)
+        Ok(store)
     }
 
-    fn load_expenses(&self) -> Result<Option<HashMap<String, Expense>>, Box<dyn StdError>> {
-        let store_data = self.get_store_data();
-
-        if store_data.is_null() {
-            return Ok(None);
+    /// Replaces the audit log of `to` with the one of `from`, when switching backends
+    fn copy_audit_log(
+        from: &dyn StorageBackend,
+        to: &dyn StorageBackend,
+    ) -> Result<(), Box<dyn StdError>> {
+        if let (Some(from), Some(to)) = (from.audit_log(), to.audit_log()) {
+            to.replace_entries(from.read_entries()?)?;
         }
+        Ok(())
+    }
+}
 
-        let expenses = store_data.get(EXPENSES_SUB_KEY);
+impl<B: StorageBackend> ExpenseStore<B> {
+    pub fn new(backend: B) -> Self {
+        Self {
+            backend,
+            cache: RwLock::new(None),
+            write_lock: Mutex::new(()),
+            history: Mutex::new(UndoHistory::default()),
+            audit_retention: AuditRetention::default(),
+        }
+    }
 
-        let expenses = match expenses {
-            Some(Value::Object(_)) => expenses.unwrap(),
-            _ => return Ok(None),
-        };
+    /// Bounds the audit log with `retention` instead of the default one
+    pub fn with_audit_retention(mut self, retention: AuditRetention) -> Self {
+        self.audit_retention = retention;
+        self
+    }
 
-        let data: HashMap<String, Expense> = serde_json::from_value(expenses.clone())
-            .map_err(|err| format!("Failed to deserialize expenses: {}", err))?;
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
 
-        Ok(Some(data))
+    /// Runs `f` on the cached expenses, loading them from the backend on first access
+    fn with_cache<R>(&self, f: impl FnOnce(&ExpenseCache) -> R) -> Result<R, Box<dyn StdError>> {
+        let cache = self.loaded_cache()?;
+        Ok(f(&cache))
     }
 
-    fn save_expenses(&self, data: &HashMap<String, Expense>) -> Result<bool, Box<dyn StdError>> {
-        let mut store_data = self.get_store_data();
+    /// The cached expenses as currently committed, loaded from the backend on first access
+    fn loaded_cache(&self) -> Result<Arc<ExpenseCache>, Box<dyn StdError>> {
+        {
+            let cache = self
+                .cache
+                .read()
+                .map_err(|_| "Failed to acquire cache read lock")?;
+            if let Some(cache) = cache.as_ref() {
+                return Ok(Arc::clone(cache));
+            }
+        }
 
-        if !store_data.is_object() {
-            store_data = Value::Object(Map::new());
+        let mut cache = self
+            .cache
+            .write()
+            .map_err(|_| "Failed to acquire cache write lock")?;
+        if cache.is_none() {
+            *cache = Some(Arc::new(ExpenseCache::from_stored(
+                self.backend.load_expenses()?,
+            )));
         }
 
-        let json_value: Value = serde_json::to_value(data)
-            .map_err(|err| format!("Failed to serialize expenses: {}", err))?;
+        Ok(Arc::clone(
+            cache.as_ref().ok_or("Expense cache is not loaded")?,
+        ))
+    }
 
-        store_data
-            .as_object_mut()
-            .ok_or("store_data is not an object")?
-            .insert(EXPENSES_SUB_KEY.to_string(), json_value);
+    /// Drops the cached expenses so the next access reloads them from the backend.
+    /// Needed whenever the expenses are replaced as a whole, e.g. by an import.
+    pub fn invalidate_cache(&self) {
+        if let Ok(mut cache) = self.cache.write() {
+            *cache = None;
+        }
+    }
 
-        self.save_store_data(&store_data)
+    /// Runs a multi-step mutation on a working copy of the store, which stages only the
+    /// expenses it changes on top of the cached ones.
+    /// Transactions run one at a time, reads keep going through the cache meanwhile.
+    ///
+    /// Everything staged on the transaction is written in one atomic backend commit once
+    /// `f` returns Ok. When `f` fails nothing is written and the store is left as it was.
+    pub fn transaction<R>(
+        &self,
+        f: impl FnOnce(&mut StoreTransaction) -> Result<R, Box<dyn StdError>>,
+    ) -> Result<R, Box<dyn StdError>> {
+        self.transaction_with(HistoryMode::Record, f)
     }
 
-    pub fn add_expense_as_batch(
+    fn transaction_with<R>(
         &self,
-        expense_batch: Vec<Expense>,
-        manual: bool,
-    ) -> Result<AddedResult, Box<dyn StdError>> {
-        let mut data = self.load_expenses()?.unwrap_or_default();
+        mode: HistoryMode,
+        f: impl FnOnce(&mut StoreTransaction) -> Result<R, Box<dyn StdError>>,
+    ) -> Result<R, Box<dyn StdError>> {
+        let context = current_command();
+        let _guard = self
+            .write_lock
+            .lock()
+            .map_err(|_| "Failed to acquire store write lock")?;
+
+        let base = self.loaded_cache()?;
+        let mut tx = StoreTransaction::new(&self.backend, &base);
+
+        // Dropping the working copy on error is the rollback
+        let result = f(&mut tx)?;
+
+        if !tx.has_changes() {
+            return Ok(result);
+        }
 
-        let mut result = AddedResult {
-            added_count: 0,
-            duplicate_count: 0,
-        };
+        let step = Self::undo_step(&tx, &context)?;
+        let now = Utc::now().naive_utc();
+        let entries = audit_entries(
+            tx.expense_changes(),
+            &context.name,
+            now,
+            &self.audit_retention,
+        );
+
+        if let Err(err) = tx.commit() {
+            self.invalidate_cache();
+            return Err(err);
+        }
 
-        for mut expense in expense_batch {
-            let hash: String = generate_hash_for_new_entry(&expense, manual)?;
+        self.record_audit(entries, now);
+        self.record_history(mode, step)?;
 
-            expense.set_id(&hash);
+        // The expenses were replaced as a whole, reload them on next access
+        if tx.expenses_replaced() {
+            self.invalidate_cache();
+            return Ok(result);
+        }
 
-            if data.contains_key(&hash) {
-                println!(
-                    "Duplicate expense found for Date: {}, Description: {}, Amount: {}",
-                    expense.get_date(),
-                    expense.get_description(),
-                    expense.get_amount()
-                );
-                result.duplicate_count += 1;
-                continue;
+        let staged = tx.into_staged();
+        drop(base);
+
+        let mut cache = self
+            .cache
+            .write()
+            .map_err(|_| "Failed to acquire cache write lock")?;
+        if let Some(cache) = cache.as_mut() {
+            let cache = Arc::make_mut(cache);
+            for (id, expense) in staged {
+                match expense {
+                    Some(expense) => cache.upsert(expense),
+                    None => {
+                        cache.remove(&id);
+                    }
+                }
             }
+        }
+
+        Ok(result)
+    }
+
+    /// Appends the entries of a committed transaction to the audit log of the backend.
+    /// Replacing all expenses at once (an import) is not logged entry by entry,
+    /// the backup taken before the import covers it.
+    ///
+    /// The changes are already committed, so failing to log them is only reported.
+    fn record_audit(&self, entries: Vec<AuditEntry>, now: NaiveDateTime) {
+        let log = match self.backend.audit_log() {
+            Some(log) if !entries.is_empty() => log,
+            _ => return,
+        };
 
-            data.insert(hash, expense);
-            result.added_count += 1;
+        if let Err(err) = log.append_entries(entries, now, &self.audit_retention) {
+            println!("Failed to append to the audit log: {}", err);
         }
+    }
 
-        if result.added_count > 0 {
-            self.save_expenses(&data)?;
+    /// Every entry of the audit log, oldest first
+    fn read_audit_log(&self) -> Result<Vec<AuditEntry>, Box<dyn StdError>> {
+        match self.backend.audit_log() {
+            Some(log) => log.read_entries(),
+            None => Ok(Vec::new()),
         }
+    }
 
-        Ok(result)
+    /// The changes of `tx` as an undo step
+    fn undo_step(
+        tx: &StoreTransaction,
+        context: &CommandContext,
+    ) -> Result<UndoGroup, Box<dyn StdError>> {
+        let mut step = UndoGroup::new(context.invocation, &context.name);
+        step.merge(tx.expense_changes(), tx.value_changes()?);
+        Ok(step)
     }
 
-    pub fn add_expense(
+    fn lock_history(&self) -> Result<std::sync::MutexGuard<'_, UndoHistory>, Box<dyn StdError>> {
+        Ok(self
+            .history
+            .lock()
+            .map_err(|_| "Failed to acquire undo history lock")?)
+    }
+
+    fn record_history(
         &self,
-        mut expense: Expense,
-        manual: bool,
-    ) -> Result<bool, Box<dyn StdError>> {
-        let mut data = self.load_expenses()?.unwrap_or_default();
-
-        let hash: String = generate_hash_for_new_entry(&expense, manual)?;
-        expense.set_id(&hash);
-
-        if data.contains_key(&hash) {
-            println!(
-                "Duplicate expense found for Date: {}, Description: {}, Amount: {}",
-                expense.get_date(),
-                expense.get_description(),
-                expense.get_amount()
-            );
-            return Ok(false);
+        mode: HistoryMode,
+        mut step: UndoGroup,
+    ) -> Result<(), Box<dyn StdError>> {
+        let mut history = self.lock_history()?;
+        match mode {
+            HistoryMode::Record if UNDOABLE_COMMANDS.contains(&step.command.as_str()) => {
+                history.record(step)
+            }
+            HistoryMode::Record => {
+                if !step.is_empty() {
+                    history.clear_redo();
+                }
+            }
+            HistoryMode::Undo(command) => {
+                step.command = command;
+                history.push_redo(step);
+            }
+            HistoryMode::Redo(command) => {
+                step.command = command;
+                history.push_undo(step);
+            }
         }
+        Ok(())
+    }
+
+    /// Commands the next undo and redo would apply to
+    pub fn undo_state(&self) -> Result<UndoState, Box<dyn StdError>> {
+        let history = self.lock_history()?;
+        Ok(UndoState {
+            undo_command: history.undo_command(),
+            redo_command: history.redo_command(),
+        })
+    }
 
-        data.insert(hash, expense);
-        self.save_expenses(&data)?;
+    /// Reverts the last undoable command invocation.
+    /// Fails with a `ConflictError` when something it changed was modified since.
+    ///
+    /// Returns:
+    /// - None when there is nothing to undo
+    pub fn undo(&self) -> Result<Option<UndoResult>, Box<dyn StdError>> {
+        let step = match self.lock_history()?.pop_undo() {
+            Some(step) => step,
+            None => return Ok(None),
+        };
 
-        Ok(true)
+        let mode = HistoryMode::Undo(step.command.clone());
+        match self.transaction_with(mode, |tx| Self::revert_step(tx, &step)) {
+            Ok(keys) => Ok(Some(UndoResult {
+                command: step.command,
+                keys,
+            })),
+            Err(err) => {
+                self.lock_history()?.push_undo(step);
+                Err(err)
+            }
+        }
     }
 
-    pub fn remove_expense(&self, hash: &String) -> Result<bool, Box<dyn StdError>> {
-        let mut data = match self.load_expenses()? {
-            Some(data) => data,
-            None => return Ok(false),
+    /// Applies again the last undone command invocation.
+    /// Fails with a `ConflictError` when something it changed was modified since.
+    ///
+    /// Returns:
+    /// - None when there is nothing to redo
+    pub fn redo(&self) -> Result<Option<UndoResult>, Box<dyn StdError>> {
+        let step = match self.lock_history()?.pop_redo() {
+            Some(step) => step,
+            None => return Ok(None),
         };
 
-        if data.remove(hash).is_some() {
-            self.save_expenses(&data)?;
-            Ok(true)
-        } else {
-            Ok(false)
+        let mode = HistoryMode::Redo(step.command.clone());
+        match self.transaction_with(mode, |tx| Self::revert_step(tx, &step)) {
+            Ok(keys) => Ok(Some(UndoResult {
+                command: step.command,
+                keys,
+            })),
+            Err(err) => {
+                self.lock_history()?.push_redo(step);
+                Err(err)
+            }
         }
     }
 
-    pub fn remove_bulk_expenses(&self, hashes: Vec<String>) -> Result<bool, Box<dyn StdError>> {
-        let mut data = match self.load_expenses()? {
-            Some(data) => data,
-            None => return Err("Store data is null, could not load it to update expense".into()),
+    /// Puts back everything `step` changed as it was before. The redo steps are recorded
+    /// from the undo transactions, so reverting them applies the original change again.
+    /// Returns the sub-keys changed.
+    fn revert_step(
+        tx: &mut StoreTransaction,
+        step: &UndoGroup,
+    ) -> Result<Vec<String>, Box<dyn StdError>> {
+        // Undoing a step bumps the revisions too, so the content is compared instead
+        let mut stale = Vec::new();
+        for (id, (_, after)) in step.expenses.iter() {
+            let unchanged = match (tx.get(id), after) {
+                (None, None) => true,
+                (Some(current), Some(after)) => {
+                    let mut current = current.clone();
+                    current.set_revision(after.get_revision());
+                    current == *after
+                }
+                _ => false,
+            };
+            if !unchanged {
+                stale.push(id.clone());
+            }
+        }
+        for (key, (_, after)) in step.values.iter() {
+            if tx.get_value(key)? != *after {
+                stale.push(key.clone());
+            }
+        }
+        if !stale.is_empty() {
+            return Err(Box::new(ConflictError { ids: stale }));
+        }
+
+        for (key, (before, _)) in step.values.iter() {
+            tx.set_value(key, before.clone().unwrap_or(Value::Null))?;
+        }
+        for (id, (before, _)) in step.expenses.iter() {
+            match before {
+                Some(expense) => tx.upsert(expense.clone()),
+                None => {
+                    tx.remove(id);
+                }
+            }
+        }
+
+        Ok(step.keys())
+    }
+
+    fn transfers_value(
+        transfers: &HashMap<String, TransferPair>,
+    ) -> Result<Value, Box<dyn StdError>> {
+        Ok(serde_json::to_value(transfers)
+            .map_err(|err| format!("Failed to serialize transfers: {}", err))?)
+    }
+
+    fn parse_transfers(
+        value: Option<Value>,
+    ) -> Result<HashMap<String, TransferPair>, Box<dyn StdError>> {
+        let transfers = match value {
+            Some(value @ Value::Object(_)) => value,
+            _ => return Ok(HashMap::new()),
         };
 
-        let missing_hashes: Vec<String> = hashes
-            .iter()
-            .filter(|h| !data.contains_key(*h))
-            .cloned()
+        let data: HashMap<String, TransferPair> = serde_json::from_value(transfers)
+            .map_err(|err| format!("Failed to deserialize transfers: {}", err))?;
+
+        Ok(data)
+    }
+
+    fn load_transfers(&self) -> Result<HashMap<String, TransferPair>, Box<dyn StdError>> {
+        Self::parse_transfers(self.get_json_value(TRANSFERS_SUB_KEY)?)
+    }
+
+    /// Removes every transfer pair that references one of the removed expenses,
+    /// clearing the link on the remaining leg.
+    fn detach_transfers(
+        tx: &mut StoreTransaction,
+        removed_ids: &[String],
+    ) -> Result<(), Box<dyn StdError>> {
+        let mut transfers = Self::parse_transfers(tx.get_value(TRANSFERS_SUB_KEY)?)?;
+
+        let detached: Vec<String> = transfers
+            .values()
+            .filter(|pair| removed_ids.iter().any(|id| pair.contains(id)))
+            .map(|pair| pair.id.clone())
             .collect();
 
-        if !missing_hashes.is_empty() {
+        if detached.is_empty() {
+            return Ok(());
+        }
+
+        for pair_id in detached.iter() {
+            let linked_ids: Vec<String> = tx
+                .expenses()
+                .filter(|expense| expense.get_transfer_id() == Some(pair_id.as_str()))
+                .map(|expense| expense.get_id().to_string())
+                .collect();
+
+            for id in linked_ids {
+                tx.update(&id, |expense| expense.set_transfer_id(None));
+            }
+            transfers.remove(pair_id);
+        }
+
+        tx.set_value(TRANSFERS_SUB_KEY, Self::transfers_value(&transfers)?)
+    }
+
+    /// Removes the given expenses (which must exist), unlinking any transfer they were part of
+    fn remove_in(
+        tx: &mut StoreTransaction,
+        removed_ids: &[String],
+    ) -> Result<(), Box<dyn StdError>> {
+        for hash in removed_ids.iter() {
+            tx.remove(hash)
+                .ok_or_else(|| format!("Failed to remove expense with hash: {}", hash))?;
+        }
+
+        Self::detach_transfers(tx, removed_ids)
+    }
+
+    /// Returns a `ConflictError` listing the edited expenses whose revision is not
+    /// the stored one anymore
+    fn ensure_fresh<'e>(
+        tx: &StoreTransaction,
+        edited: impl IntoIterator<Item = (&'e String, &'e Expense)>,
+    ) -> Result<(), Box<dyn StdError>> {
+        let stale_ids: Vec<String> = edited
+            .into_iter()
+            .filter(|(hash, expense)| {
+                tx.get(hash)
+                    .is_some_and(|stored| stored.get_revision() != expense.get_revision())
+            })
+            .map(|(hash, _)| hash.clone())
+            .collect();
+
+        if !stale_ids.is_empty() {
+            return Err(Box::new(ConflictError { ids: stale_ids }));
+        }
+
+        Ok(())
+    }
+
+    /// Import fingerprints of the expenses in the transaction
+    fn fingerprints(tx: &StoreTransaction) -> HashSet<String> {
+        tx.expenses()
+            .map(|expense| expense.get_fingerprint())
+            .filter(|fingerprint| !fingerprint.is_empty())
+            .map(String::from)
+            .collect()
+    }
+
+    /// Returns an error listing the ids that do not exist in the transaction
+    fn ensure_exist(tx: &StoreTransaction, ids: &[String]) -> Result<(), Box<dyn StdError>> {
+        let missing_ids: Vec<String> = ids.iter().filter(|id| !tx.contains(id)).cloned().collect();
+
+        if !missing_ids.is_empty() {
             return Err(format!(
                 "The following expenses do not exist: {}",
-                missing_hashes.join(", ")
+                missing_ids.join(", ")
             )
             .into());
         }
 
-        for hash in hashes.iter() {
-            data.remove(hash)
-                .ok_or_else(|| format!("Failed to remove expense with hash: {}", hash))?;
+        Ok(())
+    }
+
+    pub fn add_expense_as_batch(
+        &self,
+        expense_batch: Vec<Expense>,
+        manual: bool,
+    ) -> Result<AddedResult, Box<dyn StdError>> {
+        self.transaction(|tx| {
+            let mut result = AddedResult {
+                added_count: 0,
+                duplicate_count: 0,
+            };
+
+            // Manual entries have no fingerprint, so they never count as duplicates
+            let occurrences = if manual {
+                vec![0; expense_batch.len()]
+            } else {
+                occurrence_indices(&expense_batch)
+            };
+
+            let mut fingerprints = Self::fingerprints(tx);
+
+            for (mut expense, occurrence) in expense_batch.into_iter().zip(occurrences) {
+                if !manual {
+                    let fingerprint: String = generate_fingerprint(&expense, occurrence)?;
+                    expense.set_fingerprint(&fingerprint);
+                }
+                expense.set_id(&generate_uuid());
+
+                if !manual && !fingerprints.insert(expense.get_fingerprint().to_string()) {
+                    println!(
+                        "Duplicate expense found for Date: {}, Description: {}, Amount: {}",
+                        expense.get_date(),
+                        expense.get_description(),
+                        expense.get_amount()
+                    );
+                    result.duplicate_count += 1;
+                    continue;
+                }
+
+                tx.upsert(expense);
+                result.added_count += 1;
+            }
+
+            Ok(result)
+        })
+    }
+
+    pub fn add_expense(
+        &self,
+        mut expense: Expense,
+        manual: bool,
+    ) -> Result<bool, Box<dyn StdError>> {
+        if !manual {
+            let fingerprint: String = generate_fingerprint(&expense, 0)?;
+            expense.set_fingerprint(&fingerprint);
+        }
+        expense.set_id(&generate_uuid());
+
+        self.transaction(|tx| {
+            if !manual && Self::fingerprints(tx).contains(expense.get_fingerprint()) {
+                println!(
+                    "Duplicate expense found for Date: {}, Description: {}, Amount: {}",
+                    expense.get_date(),
+                    expense.get_description(),
+                    expense.get_amount()
+                );
+                return Ok(false);
+            }
+
+            tx.upsert(expense);
+            Ok(true)
+        })
+    }
+
+    pub fn remove_expense(&self, hash: &String) -> Result<bool, Box<dyn StdError>> {
+        if !self.exists(hash)? {
+            return Ok(false);
         }
 
-        self.save_expenses(&data)?;
+        self.transaction(|tx| {
+            if !tx.contains(hash) {
+                return Ok(false);
+            }
+
+            Self::remove_in(tx, std::slice::from_ref(hash))?;
+            Ok(true)
+        })
+    }
+
+    pub fn remove_bulk_expenses(&self, hashes: Vec<String>) -> Result<bool, Box<dyn StdError>> {
+        self.transaction(|tx| {
+            if !tx.is_stored() {
+                return Err("Store data is null, could not load it to update expense".into());
+            }
 
-        Ok(true)
+            Self::ensure_exist(tx, &hashes)?;
+            Self::remove_in(tx, &hashes)?;
+            Ok(true)
+        })
     }
 
     pub fn get_expense(&self, hash: &String) -> Result<Option<Expense>, Box<dyn StdError>> {
-        let data = match self.load_expenses()? {
-            Some(data) => data,
-            None => return Ok(None),
-        };
+        self.with_cache(|cache| cache.get(hash).cloned())
+    }
+
+    pub fn get_all_expenses(&self) -> Result<Vec<Expense>, Box<dyn StdError>> {
+        self.with_cache(|cache| cache.values().cloned().collect())
+    }
+
+    /// Expenses dated between `start` and `end` (both inclusive), ordered by date
+    pub fn get_expenses_between(
+        &self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<Vec<Expense>, Box<dyn StdError>> {
+        self.with_cache(|cache| cache.between(start, end).into_iter().cloned().collect())
+    }
+
+    pub fn get_expenses_with_tag(&self, tag: &str) -> Result<Vec<Expense>, Box<dyn StdError>> {
+        self.with_cache(|cache| cache.with_tag(tag).into_iter().cloned().collect())
+    }
 
-        Ok(data.get(hash).cloned())
+    /// Every tag in use with the number of expenses having it and their total, by name
+    pub fn list_tags(&self) -> Result<Vec<TagSummary>, Box<dyn StdError>> {
+        let tags = self.with_cache(|cache| {
+            let mut tags = cache
+                .tag_counts()
+                .into_iter()
+                .map(|(tag, count)| {
+                    Ok(TagSummary {
+                        tag: tag.to_string(),
+                        count,
+                        total: cache
+                            .with_tag(tag)
+                            .iter()
+                            .map(|expense| expense.get_amount())
+                            .sum::<Result<Money, _>>()?,
+                    })
+                })
+                .collect::<Result<Vec<TagSummary>, _>>()?;
+            tags.sort_by(|a, b| a.tag.cmp(&b.tag));
+            Ok::<_, CurrencyMismatchError>(tags)
+        })??;
+        Ok(tags)
+    }
+
+    /// Runs a query over the cached expenses, narrowing the candidates with the date or
+    /// tag index before the remaining criteria are checked one by one.
+    pub fn query_expenses(&self, query: &ExpenseQuery) -> Result<ExpensePage, Box<dyn StdError>> {
+        self.with_cache(|cache| {
+            if let Some((start, end)) = datetime_bounds(&query.filter) {
+                run_query(cache.between(start, end), query)
+            } else if let Some(tag) = query.filter.tags_all.first() {
+                run_query(cache.with_tag(tag), query)
+            } else {
+                run_query(cache.values(), query)
+            }
+        })?
+    }
+
+    /// Every expense matching the filter, oldest first
+    pub fn get_matching_expenses(
+        &self,
+        filter: &ExpenseFilter,
+    ) -> Result<Vec<Expense>, Box<dyn StdError>> {
+        self.with_cache(|cache| {
+            let matched = if let Some((start, end)) = datetime_bounds(filter) {
+                matching_expenses(cache.between(start, end), filter)?
+            } else if let Some(tag) = filter.tags_all.first() {
+                matching_expenses(cache.with_tag(tag), filter)?
+            } else {
+                matching_expenses(cache.values(), filter)?
+            };
+            Ok(matched.into_iter().cloned().collect())
+        })?
+    }
+
+    /// Full-text search over the descriptions, ranked best first
+    pub fn search_descriptions(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<DescriptionMatch>, Box<dyn StdError>> {
+        self.with_cache(|cache| {
+            cache
+                .search_description(query, limit)
+                .into_iter()
+                .map(|(expense, score)| DescriptionMatch {
+                    expense: expense.clone(),
+                    score,
+                })
+                .collect()
+        })
     }
 
     pub fn exists(&self, hash: &String) -> Result<bool, Box<dyn StdError>> {
-        Ok(self
-            .load_expenses()?
-            .map_or(false, |data| data.contains_key(hash)))
+        self.with_cache(|cache| cache.contains(hash))
     }
 
+    /// Replaces the given expenses, all or none: one missing hash or stale revision
+    /// rejects the whole update
     pub fn update_bulk_expenses(
         &self,
         hashes: Vec<String>,
         expenses: Vec<Expense>,
     ) -> Result<bool, Box<dyn StdError>> {
-        let mut data = match self.load_expenses()? {
-            Some(data) => data,
-            None => return Err("Store data is null, could not load it to update expense".into()),
-        };
+        self.transaction(|tx| {
+            if !tx.is_stored() {
+                return Err("Store data is null, could not load it to update expense".into());
+            }
 
-        let missing_hashes: Vec<String> = hashes
-            .iter()
-            .filter(|h| !data.contains_key(*h))
-            .cloned()
-            .collect();
+            Self::ensure_exist(tx, &hashes)?;
+            Self::ensure_fresh(tx, hashes.iter().zip(expenses.iter()))?;
+
+            for (hash, expense) in hashes.iter().zip(expenses.iter()) {
+                let mut expense = expense.clone();
+                if !tx.update(hash, |stored| {
+                    expense.keep_backend_fields_from(stored);
+                    *stored = expense;
+                }) {
+                    return Err(format!("Failed to update expense with hash: {}", hash).into());
+                }
+            }
 
-        if !missing_hashes.is_empty() {
-            return Err(format!(
-                "The following expenses do not exist: {}",
-                missing_hashes.join(", ")
-            )
-            .into());
-        }
+            Ok(true)
+        })
+    }
 
-        for (hash, expense) in hashes.iter().zip(expenses.iter()) {
-            if data.insert(hash.to_string(), expense.clone()).is_none() {
-                return Err(format!("Failed to update expense with hash: {}", hash).into());
-            }
-        }
+    pub fn set_json_value(&self, key: &str, value: Value) -> Result<(), Box<dyn StdError>> {
+        self.transaction(|tx| tx.set_value(key, value))
+    }
 
-        self.save_expenses(&data)?;
+    /// Sets every sub-key of an imported store_data object in a single commit,
+    /// so a failing key leaves the store exactly as it was before the import.
+    ///
+    /// Returns:
+    /// - `Vec<String>`: The imported keys.
+    pub fn import_values(
+        &self,
+        values: serde_json::Map<String, Value>,
+    ) -> Result<Vec<String>, Box<dyn StdError>> {
+        self.transaction(|tx| {
+            let mut keys = Vec::new();
+            for (key, value) in values {
+                tx.set_value(&key, value)
+                    .map_err(|err| format!("Failed to import key '{}': {}", key, err))?;
+                keys.push(key);
+            }
+            Ok(keys)
+        })
+    }
 
-        Ok(true)
+    /// Imports the sub-keys of an exported store_data object in a single commit.
+    /// Known keys are validated first, an invalid one fails the whole import with an
+    /// `ImportValidationError`; unknown keys are skipped.
+    pub fn import_data(
+        &self,
+        values: serde_json::Map<String, Value>,
+        mode: ImportMode,
+    ) -> Result<ImportReport, Box<dyn StdError>> {
+        validate_import(&values)?;
+
+        // Expenses first: removing some detaches their transfers, which an imported
+        // transfers key is then merged with
+        let mut values: Vec<(String, Value)> = values.into_iter().collect();
+        values.sort_by_key(|(key, _)| key != EXPENSES_SUB_KEY);
+
+        self.transaction(|tx| {
+            let mut keys = Vec::new();
+            for (key, value) in values {
+                let current = tx.get_value(&key)?;
+                let (merged, report) = merge_key(&key, current.as_ref(), value, mode);
+                if let Some(merged) = merged {
+                    if key == EXPENSES_SUB_KEY {
+                        Self::import_expenses(tx, merged)?;
+                    } else {
+                        tx.set_value(&key, merged)?;
+                    }
+                }
+                keys.push(report);
+            }
+            Ok(ImportReport { mode, keys })
+        })
     }
 
-    pub fn set_json_value(&self, key: &str, value: Value) -> Result<(), Box<dyn StdError>> {
-        let mut store_data = self.get_store_data();
+    /// Applies the merged expenses one by one, so the audit log records each change
+    fn import_expenses(tx: &mut StoreTransaction, merged: Value) -> Result<(), Box<dyn StdError>> {
+        let merged: HashMap<String, Expense> = serde_json::from_value(merged)
+            .map_err(|err| format!("Failed to deserialize expenses: {}", err))?;
 
-        if !store_data.is_object() {
-            store_data = Value::Object(Map::new());
+        let removed: Vec<String> = tx
+            .expenses()
+            .map(|expense| expense.get_id())
+            .filter(|id| !merged.contains_key(*id))
+            .map(String::from)
+            .collect();
+
+        for (id, expense) in merged {
+            if tx.get(&id) != Some(&expense) {
+                tx.upsert(expense);
+            }
         }
 
-        store_data
-            .as_object_mut()
-            .ok_or("store_data is not an object")?
-            .insert(key.to_string(), value);
+        // Last, so the link of a kept leg to a removed expense is cleared
+        Self::remove_in(tx, &removed)
+    }
 
-        self.save_store_data(&store_data)?;
+    /// Replaces the whole store_data object, e.g. with a restored snapshot.
+    /// Unlike an import, sub-keys missing from `store_data` are removed.
+    /// Older snapshots are migrated to the current schema first.
+    ///
+    /// Every changed expense is logged in the audit log under `RESTORE_COMMAND`, and the
+    /// undo history is dropped since its steps apply to the replaced data.
+    pub fn replace_all_data(&self, store_data: Value) -> Result<(), Box<dyn StdError>> {
+        let mut store_data = match store_data {
+            Value::Object(obj) => obj,
+            _ => return Err("store_data must be an object".into()),
+        };
+        migrate(&mut store_data, false)?;
+        let restored: HashMap<String, Expense> = match store_data.get(EXPENSES_SUB_KEY) {
+            Some(expenses @ Value::Object(_)) => serde_json::from_value(expenses.clone())
+                .map_err(|err| format!("Failed to deserialize expenses: {}", err))?,
+            _ => HashMap::new(),
+        };
+        let store_data = Value::Object(store_data);
+
+        let _guard = self
+            .write_lock
+            .lock()
+            .map_err(|_| "Failed to acquire store write lock")?;
+
+        let current = self.loaded_cache()?;
+        let mut changes: Vec<(String, Option<Expense>, Option<Expense>)> = current
+            .values()
+            .filter(|expense| !restored.contains_key(expense.get_id()))
+            .map(|expense| (expense.get_id().to_string(), Some(expense.clone()), None))
+            .collect();
+        for (id, expense) in restored {
+            if current.get(&id) != Some(&expense) {
+                changes.push((id.clone(), current.get(&id).cloned(), Some(expense)));
+            }
+        }
+        drop(current);
+
+        let result = self.backend.write_data(&store_data);
+        self.invalidate_cache();
+        result?;
+
+        let now = Utc::now().naive_utc();
+        let entries = audit_entries(changes, RESTORE_COMMAND, now, &self.audit_retention);
+        self.record_audit(entries, now);
+        self.lock_history()?.clear();
         Ok(())
     }
 
-    pub fn get_all_store_data(&self) -> Value {
-        self.get_store_data()
+    /// True while the encrypted store waits for its passphrase
+    pub fn is_locked(&self) -> bool {
+        self.backend
+            .encryption()
+            .is_some_and(|encryption| encryption.vault().is_locked())
     }
 
-    pub fn get_json_value(&self, key: &str) -> Result<Option<Value>, Box<dyn StdError>> {
-        let store_data = self.get_store_data();
+    /// Runs an encryption operation (unlock, lock, change of passphrase, ...) on the backend.
+    /// No transaction runs meanwhile, and the cached expenses are dropped afterwards, along
+    /// with the undo history when the store ends up locked, so they are never kept in
+    /// memory while locked.
+    pub fn with_encryption<R>(
+        &self,
+        f: impl FnOnce(&dyn EncryptedStorage) -> Result<R, Box<dyn StdError>>,
+    ) -> Result<R, Box<dyn StdError>> {
+        let encryption = self.backend.encryption().ok_or_else(|| {
+            format!(
+                "The {} storage backend does not support encryption",
+                self.backend.name()
+            )
+        })?;
+
+        let _guard = self
+            .write_lock
+            .lock()
+            .map_err(|_| "Failed to acquire store write lock")?;
 
-        if store_data.is_null() {
-            return Ok(None);
+        let result = f(encryption);
+        self.invalidate_cache();
+        if self.is_locked() {
+            self.lock_history()?.clear();
         }
+        result
+    }
+
+    /// Brings the stored data to the current schema version in a single commit.
+    /// With `dry_run` nothing is written, the report tells what would change.
+    pub fn migrate_schema(&self, dry_run: bool) -> Result<MigrationReport, Box<dyn StdError>> {
+        self.transaction(|tx| {
+            // Nothing is staged yet, so the backend holds the state the transaction started from
+            let mut data = match self.backend.read_data()? {
+                Value::Object(obj) => obj,
+                _ => serde_json::Map::new(),
+            };
+
+            let legacy_log = parse_audit_log(data.get(AUDIT_LOG_SUB_KEY).cloned())?;
+            let report = migrate(&mut data, dry_run)?;
+            if !dry_run {
+                for key in report.keys.iter() {
+                    let value = data.remove(key).unwrap_or(Value::Null);
+                    tx.set_value(key, value)?;
+                }
+
+                // Handed over before the commit, a failed commit at worst logs them twice
+                let moved = report.keys.iter().any(|key| key == AUDIT_LOG_SUB_KEY);
+                if let Some(log) = self.backend.audit_log() {
+                    if moved && !legacy_log.is_empty() {
+                        let now = Utc::now().naive_utc();
+                        log.append_entries(legacy_log, now, &self.audit_retention)?;
+                    }
+                }
+            }
+            Ok(report)
+        })
+    }
 
-        Ok(store_data.get(key).cloned())
+    pub fn get_all_store_data(&self) -> Result<Value, Box<dyn StdError>> {
+        self.backend.read_data()
+    }
+
+    pub fn get_json_value(&self, key: &str) -> Result<Option<Value>, Box<dyn StdError>> {
+        self.backend.get_value(key)
     }
 
+    /// Replaces a stored expense, rejecting the edit with a `ConflictError` when it was
+    /// made on an older revision than the stored one
     pub fn update_expense(
         &self,
         hash: String,
         expense: Expense,
     ) -> Result<bool, Box<dyn StdError>> {
-        let mut data = match self.load_expenses()? {
-            Some(data) => data,
-            None => return Err("Store data is null, could not load it to update expense".into()),
-        };
+        self.transaction(|tx| {
+            if !tx.is_stored() {
+                return Err("Store data is null, could not load it to update expense".into());
+            }
 
-        if !data.contains_key(&hash) {
-            return Ok(false);
+            if !tx.contains(&hash) {
+                return Ok(false);
+            }
+
+            Self::ensure_fresh(tx, [(&hash, &expense)])?;
+
+            let mut expense = expense;
+            tx.update(&hash, |stored| {
+                expense.keep_backend_fields_from(stored);
+                *stored = expense;
+            });
+
+            Ok(true)
+        })
+    }
+
+    /// Merges probable duplicates into the expense kept for review:
+    /// the kept expense receives the union of all tags and the others are removed.
+    pub fn merge_expenses(
+        &self,
+        keep_id: &str,
+        merge_ids: &[String],
+    ) -> Result<Expense, Box<dyn StdError>> {
+        self.transaction(|tx| {
+            if !tx.is_stored() {
+                return Err("Store data is null, could not load it to merge expenses".into());
+            }
+
+            if merge_ids.iter().any(|id| id == keep_id) {
+                return Err("The kept expense cannot also be merged away".into());
+            }
+
+            let all_ids: Vec<String> = std::iter::once(keep_id.to_string())
+                .chain(merge_ids.iter().cloned())
+                .collect();
+            Self::ensure_exist(tx, &all_ids)?;
+
+            let merged_tags: Vec<String> = merge_ids
+                .iter()
+                .filter_map(|id| tx.get(id))
+                .flat_map(|expense| expense.get_tags().iter().cloned())
+                .collect();
+
+            tx.update(keep_id, |kept| {
+                for tag in merged_tags {
+                    if !kept.get_tags().contains(&tag) {
+                        kept.add_tag(&tag);
+                    }
+                }
+            });
+
+            Self::remove_in(tx, merge_ids)?;
+
+            tx.get(keep_id)
+                .cloned()
+                .ok_or_else(|| format!("Expense {} does not exist", keep_id).into())
+        })
+    }
+
+    /// Renames a tag on every expense having it, an expense that also has the new name keeps
+    /// it once. Returns the number of expenses changed.
+    pub fn rename_tag(&self, from: &str, to: &str) -> Result<usize, Box<dyn StdError>> {
+        self.merge_tags(&[from.to_string()], to)
+    }
+
+    /// Replaces each of `sources` by `target` on every expense and in the ledger tag
+    /// accounts, in a single update. Names are trimmed like new tags, sources naming the
+    /// target are left out. Returns the number of expenses changed.
+    pub fn merge_tags(&self, sources: &[String], target: &str) -> Result<usize, Box<dyn StdError>> {
+        let target = normalize_tag_name(target)?;
+        let mut names: Vec<String> = Vec::with_capacity(sources.len());
+        for source in sources {
+            let source = normalize_tag_name(source)?;
+            if source != target && !names.contains(&source) {
+                names.push(source);
+            }
         }
+        let sources = names;
+        if sources.is_empty() {
+            return Err(format!("No tag other than {} to merge into it", target).into());
+        }
+
+        self.replace_tag_everywhere(&sources, Some(&target))
+    }
 
-        if hash != expense.get_id() {
-            return Err(
-                "Expense ID must have the same value as the hash, please check the ID before updating".into(),
-            );
+    /// Removes a tag from every expense, putting `replacement` in its place when given.
+    /// Returns the number of expenses changed.
+    pub fn delete_tag(
+        &self,
+        tag: &str,
+        replacement: Option<&str>,
+    ) -> Result<usize, Box<dyn StdError>> {
+        match replacement {
+            Some(replacement) => self.merge_tags(&[tag.to_string()], replacement),
+            None => self.replace_tag_everywhere(&[normalize_tag_name(tag)?], None),
         }
+    }
 
-        data.insert(hash, expense);
-        self.save_expenses(&data)?;
+    fn replace_tag_everywhere(
+        &self,
+        sources: &[String],
+        replacement: Option<&str>,
+    ) -> Result<usize, Box<dyn StdError>> {
+        self.transaction(|tx| {
+            let changed: Vec<(String, Vec<String>)> = tx
+                .expenses()
+                .filter_map(|expense| {
+                    replace_tags(expense.get_tags(), sources, replacement)
+                        .map(|tags| (expense.get_id().to_string(), tags))
+                })
+                .collect();
+
+            for (id, tags) in &changed {
+                tx.update(id, |expense| expense.set_tags(tags.clone()));
+            }
+
+            let mut ledger_config = parse_ledger_config(tx.get_value(LEDGER_CONFIG_SUB_KEY)?)?;
+            if replace_tag_accounts(&mut ledger_config.tag_accounts, sources, replacement) {
+                let value = serde_json::to_value(&ledger_config)
+                    .map_err(|err| format!("Failed to serialize ledger config: {}", err))?;
+                tx.set_value(LEDGER_CONFIG_SUB_KEY, value)?;
+            }
+
+            Ok(changed.len())
+        })
+    }
+
+    /// Audit log entries of one expense, newest first
+    pub fn get_expense_history(&self, id: &str) -> Result<Vec<AuditEntry>, Box<dyn StdError>> {
+        let mut history: Vec<AuditEntry> = self
+            .read_audit_log()?
+            .into_iter()
+            .filter(|entry| entry.expense_id == id)
+            .collect();
+        history.reverse();
+        Ok(history)
+    }
+
+    /// Brings an expense back to its version right after the audit entry `entry_id`,
+    /// recreating it if it was deleted since (or deleting it if that entry deleted it).
+    ///
+    /// Returns:
+    /// - `Option<Expense>`: The expense as reverted, None if the reverted version is a deletion.
+    pub fn revert_expense(
+        &self,
+        id: &str,
+        entry_id: u64,
+    ) -> Result<Option<Expense>, Box<dyn StdError>> {
+        self.transaction(|tx| {
+            let entry = self
+                .read_audit_log()?
+                .into_iter()
+                .find(|entry| entry.id == entry_id && entry.expense_id == id)
+                .ok_or_else(|| format!("No history entry {} for expense {}", entry_id, id))?;
+
+            let mut version = match entry.after {
+                Some(version) => version,
+                None => {
+                    if tx.contains(id) {
+                        Self::remove_in(tx, &[id.to_string()])?;
+                    }
+                    return Ok(None);
+                }
+            };
+
+            match tx.get(id) {
+                // Transfer links are not part of the history, keep the current one
+                Some(current) => version.keep_backend_fields_from(current),
+                None => version.set_transfer_id(None),
+            }
+            tx.upsert(version);
+
+            Ok(tx.get(id).cloned())
+        })
+    }
+
+    pub fn get_transfers(&self) -> Result<Vec<TransferPair>, Box<dyn StdError>> {
+        Ok(self.load_transfers()?.into_values().collect())
+    }
+
+    /// Re-runs transfer detection over all expenses and stores the resulting suggestions.
+    /// Stale suggestions are replaced, confirmed and rejected pairs are kept as they are.
+    pub fn detect_transfers(
+        &self,
+        window_days: i64,
+    ) -> Result<Vec<TransferPair>, Box<dyn StdError>> {
+        self.transaction(|tx| {
+            let expenses: Vec<Expense> = tx.expenses().cloned().collect();
+            let mut transfers = Self::parse_transfers(tx.get_value(TRANSFERS_SUB_KEY)?)?;
+
+            transfers.retain(|_, pair| pair.status != TransferStatus::Suggested);
+
+            let rejected: HashSet<String> = transfers
+                .values()
+                .filter(|pair| pair.status == TransferStatus::Rejected)
+                .map(|pair| pair.id.clone())
+                .collect();
+
+            let suggestions = find_transfer_candidates(&expenses, window_days, &rejected);
+
+            for pair in suggestions.iter() {
+                transfers.insert(pair.id.clone(), pair.clone());
+            }
+
+            tx.set_value(TRANSFERS_SUB_KEY, Self::transfers_value(&transfers)?)?;
+
+            Ok(suggestions)
+        })
+    }
+
+    /// Links both legs of a pair so they are excluded from spending and income,
+    /// and drops any other suggestion that involves either leg.
+    fn apply_confirmed_pair(
+        tx: &mut StoreTransaction,
+        transfers: &mut HashMap<String, TransferPair>,
+        mut pair: TransferPair,
+    ) -> Result<(), Box<dyn StdError>> {
+        for expense_id in [&pair.outflow_id, &pair.inflow_id] {
+            let expense = tx.get(expense_id).ok_or_else(|| {
+                format!(
+                    "Expense {} of transfer {} does not exist",
+                    expense_id, pair.id
+                )
+            })?;
+
+            if let Some(existing) = expense.get_transfer_id() {
+                if existing != pair.id {
+                    return Err(format!(
+                        "Expense {} is already part of transfer {}",
+                        expense_id, existing
+                    )
+                    .into());
+                }
+            }
+
+            tx.update(expense_id, |expense| {
+                expense.set_transfer_id(Some(pair.id.clone()))
+            });
+        }
+
+        transfers.retain(|id, other| {
+            *id == pair.id
+                || other.status != TransferStatus::Suggested
+                || !(other.contains(&pair.outflow_id) || other.contains(&pair.inflow_id))
+        });
+
+        pair.status = TransferStatus::Confirmed;
+        transfers.insert(pair.id.clone(), pair);
+
+        tx.set_value(TRANSFERS_SUB_KEY, Self::transfers_value(transfers)?)
+    }
+
+    pub fn confirm_transfer(&self, id: &str) -> Result<bool, Box<dyn StdError>> {
+        self.transaction(|tx| {
+            let mut transfers = Self::parse_transfers(tx.get_value(TRANSFERS_SUB_KEY)?)?;
+
+            let pair = match transfers.get(id) {
+                Some(pair) => pair.clone(),
+                None => return Ok(false),
+            };
+
+            Self::apply_confirmed_pair(tx, &mut transfers, pair)?;
+            Ok(true)
+        })
+    }
+
+    /// Marks a pair as rejected so detection never suggests it again,
+    /// unlinking both legs if the pair had been confirmed.
+    pub fn reject_transfer(&self, id: &str) -> Result<bool, Box<dyn StdError>> {
+        self.transaction(|tx| {
+            let mut transfers = Self::parse_transfers(tx.get_value(TRANSFERS_SUB_KEY)?)?;
+
+            let pair = match transfers.get_mut(id) {
+                Some(pair) => pair,
+                None => return Ok(false),
+            };
+            pair.status = TransferStatus::Rejected;
+
+            let linked_ids: Vec<String> = tx
+                .expenses()
+                .filter(|expense| expense.get_transfer_id() == Some(id))
+                .map(|expense| expense.get_id().to_string())
+                .collect();
+            for expense_id in linked_ids {
+                tx.update(&expense_id, |expense| expense.set_transfer_id(None));
+            }
+
+            tx.set_value(TRANSFERS_SUB_KEY, Self::transfers_value(&transfers)?)?;
+            Ok(true)
+        })
+    }
+
+    /// Manually links two expenses as a confirmed transfer pair.
+    /// One of them must be an outflow (positive amount) and the other an inflow (negative amount).
+    pub fn link_transfer(
+        &self,
+        first_id: &str,
+        second_id: &str,
+    ) -> Result<TransferPair, Box<dyn StdError>> {
+        if first_id == second_id {
+            return Err("A transfer needs two different expenses".into());
+        }
 
-        Ok(true)
+        self.transaction(|tx| {
+            let first = tx
+                .get(first_id)
+                .ok_or_else(|| format!("Expense {} does not exist", first_id))?;
+            let second = tx
+                .get(second_id)
+                .ok_or_else(|| format!("Expense {} does not exist", second_id))?;
+
+            let pair = if first.get_amount().is_positive() && second.get_amount().is_negative() {
+                TransferPair::new(first_id, second_id, TransferStatus::Confirmed)
+            } else if first.get_amount().is_negative() && second.get_amount().is_positive() {
+                TransferPair::new(second_id, first_id, TransferStatus::Confirmed)
+            } else {
+                return Err("A transfer needs one positive and one negative amount".into());
+            };
+
+            let mut transfers = Self::parse_transfers(tx.get_value(TRANSFERS_SUB_KEY)?)?;
+            Self::apply_confirmed_pair(tx, &mut transfers, pair.clone())?;
+
+            Ok(transfers.get(&pair.id).cloned().unwrap_or(pair))
+        })
     }
 }
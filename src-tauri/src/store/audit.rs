@@ -0,0 +1,354 @@
+use crate::config::{
+    DEFAULT_AUDIT_BULK_COMMIT_SIZE, DEFAULT_AUDIT_MAX_AGE_DAYS, DEFAULT_AUDIT_MAX_BULK_ENTRIES,
+    DEFAULT_AUDIT_MAX_ENTRIES,
+};
+use crate::model::audit::{AuditAction, AuditEntry};
+use crate::model::expense::Expense;
+use crate::store::encryption::Vault;
+use crate::store::json_file_backend::write_atomically_with;
+use chrono::{Duration, NaiveDateTime};
+use serde_json::Value;
+use std::error::Error as StdError;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Sub-key of store_data holding the audit log before it got its own storage
+pub static AUDIT_LOG_SUB_KEY: &str = "audit_log";
+
+/// Bounds of the audit log
+#[derive(Clone, Copy, Debug)]
+pub struct AuditRetention {
+    /// Entries kept from regular commits
+    pub max_entries: usize,
+    /// Entries kept from bulk commits, see `bulk_commit_size`
+    pub max_bulk_entries: usize,
+    /// Commits changing more expenses than this are bulk commits
+    pub bulk_commit_size: usize,
+    pub max_age: Duration,
+}
+
+impl Default for AuditRetention {
+    fn default() -> Self {
+        AuditRetention {
+            max_entries: DEFAULT_AUDIT_MAX_ENTRIES,
+            max_bulk_entries: DEFAULT_AUDIT_MAX_BULK_ENTRIES,
+            bulk_commit_size: DEFAULT_AUDIT_BULK_COMMIT_SIZE,
+            max_age: Duration::days(DEFAULT_AUDIT_MAX_AGE_DAYS),
+        }
+    }
+}
+
+pub fn parse_audit_log(value: Option<Value>) -> Result<Vec<AuditEntry>, Box<dyn StdError>> {
+    match value {
+        Some(value @ Value::Array(_)) => Ok(serde_json::from_value(value)
+            .map_err(|err| format!("Failed to deserialize audit log: {}", err))?),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// One entry per changed expense of a commit, numbered by the storage when appended.
+/// `changes` holds (expense id, before, after); pairs without any version are ignored.
+pub fn audit_entries(
+    changes: Vec<(String, Option<Expense>, Option<Expense>)>,
+    command: &str,
+    now: NaiveDateTime,
+    retention: &AuditRetention,
+) -> Vec<AuditEntry> {
+    let bulk = changes.len() > retention.bulk_commit_size;
+
+    changes
+        .into_iter()
+        .filter_map(|(expense_id, before, after)| {
+            let action = match (&before, &after) {
+                (None, Some(_)) => AuditAction::Create,
+                (Some(_), Some(_)) => AuditAction::Update,
+                (Some(_), None) => AuditAction::Delete,
+                (None, None) => return None,
+            };
+
+            Some(AuditEntry {
+                id: 0,
+                expense_id,
+                timestamp: now,
+                command: command.to_string(),
+                bulk,
+                action,
+                before,
+                after,
+            })
+        })
+        .collect()
+}
+
+/// Drops the entries older than the retention, then the oldest regular and bulk entries
+/// beyond their own bound, so an import never evicts the history of manual edits.
+pub fn apply_retention(log: &mut Vec<AuditEntry>, now: NaiveDateTime, retention: &AuditRetention) {
+    let oldest = now - retention.max_age;
+    log.retain(|entry| entry.timestamp >= oldest);
+
+    let bulk = log.iter().filter(|entry| entry.bulk).count();
+    let mut extra_bulk = bulk.saturating_sub(retention.max_bulk_entries);
+    let mut extra_regular = (log.len() - bulk).saturating_sub(retention.max_entries);
+    log.retain(|entry| {
+        let extra = if entry.bulk {
+            &mut extra_bulk
+        } else {
+            &mut extra_regular
+        };
+        if *extra > 0 {
+            *extra -= 1;
+            return false;
+        }
+        true
+    });
+}
+
+/// Append-only storage of the audit log, kept apart from store_data so restoring a backup
+/// or importing data never replaces it
+pub trait AuditStorage: Send + Sync {
+    /// Every entry, oldest first
+    fn read_entries(&self) -> Result<Vec<AuditEntry>, Box<dyn StdError>>;
+
+    /// Appends `entries` numbered after the last one, dropping the entries beyond `retention`
+    fn append_entries(
+        &self,
+        entries: Vec<AuditEntry>,
+        now: NaiveDateTime,
+        retention: &AuditRetention,
+    ) -> Result<(), Box<dyn StdError>>;
+
+    /// Replaces the whole log, keeping the ids of `entries`
+    fn replace_entries(&self, entries: Vec<AuditEntry>) -> Result<(), Box<dyn StdError>>;
+}
+
+fn number_entries(entries: &mut [AuditEntry], last_id: u64) {
+    for (entry, id) in entries.iter_mut().zip(last_id + 1..) {
+        entry.id = id;
+    }
+}
+
+/// Audit log held in memory, e.g. for tests
+#[derive(Default)]
+pub struct MemoryAuditLog {
+    entries: Mutex<Vec<AuditEntry>>,
+}
+
+impl AuditStorage for MemoryAuditLog {
+    fn read_entries(&self) -> Result<Vec<AuditEntry>, Box<dyn StdError>> {
+        let entries = self
+            .entries
+            .lock()
+            .map_err(|_| "Failed to acquire audit log lock")?;
+        Ok(entries.clone())
+    }
+
+    fn append_entries(
+        &self,
+        mut entries: Vec<AuditEntry>,
+        now: NaiveDateTime,
+        retention: &AuditRetention,
+    ) -> Result<(), Box<dyn StdError>> {
+        let mut log = self
+            .entries
+            .lock()
+            .map_err(|_| "Failed to acquire audit log lock")?;
+
+        number_entries(&mut entries, log.last().map(|entry| entry.id).unwrap_or(0));
+        log.extend(entries);
+        apply_retention(&mut log, now, retention);
+        Ok(())
+    }
+
+    fn replace_entries(&self, entries: Vec<AuditEntry>) -> Result<(), Box<dyn StdError>> {
+        let mut log = self
+            .entries
+            .lock()
+            .map_err(|_| "Failed to acquire audit log lock")?;
+        *log = entries;
+        Ok(())
+    }
+}
+
+/// What `AuditFile` knows of its content without reading it again
+struct AuditFileState {
+    last_id: u64,
+    regular: usize,
+    bulk: usize,
+    oldest: Option<NaiveDateTime>,
+}
+
+impl AuditFileState {
+    fn of(entries: &[AuditEntry]) -> Self {
+        let bulk = entries.iter().filter(|entry| entry.bulk).count();
+        Self {
+            last_id: entries.last().map(|entry| entry.id).unwrap_or(0),
+            regular: entries.len() - bulk,
+            bulk,
+            oldest: entries.first().map(|entry| entry.timestamp),
+        }
+    }
+
+    /// True once the file holds a tenth more than a bound, or entries a day past the
+    /// maximum age, so it is not rewritten on every commit
+    fn needs_compaction(&self, now: NaiveDateTime, retention: &AuditRetention) -> bool {
+        let over = |count: usize, bound: usize| count > bound + bound / 10;
+        over(self.regular, retention.max_entries)
+            || over(self.bulk, retention.max_bulk_entries)
+            || self
+                .oldest
+                .is_some_and(|oldest| oldest < now - retention.max_age - Duration::days(1))
+    }
+}
+
+/// Audit log in a JSON Lines file next to the store file, appended to on every commit.
+/// With encryption enabled each line is sealed on its own with the key of the `Vault`.
+pub struct AuditFile {
+    path: PathBuf,
+    vault: Arc<Vault>,
+    state: Mutex<Option<AuditFileState>>,
+}
+
+impl AuditFile {
+    pub fn new<P: AsRef<Path>>(path: P, vault: Arc<Vault>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            vault,
+            state: Mutex::new(None),
+        }
+    }
+
+    fn encode(&self, entry: &AuditEntry) -> Result<Vec<u8>, Box<dyn StdError>> {
+        let line = serde_json::to_vec(entry)
+            .map_err(|err| format!("Failed to serialize audit entry: {}", err))?;
+        let mut line = self.vault.seal(&line)?;
+        line.push(b'\n');
+        Ok(line)
+    }
+
+    /// Reads every entry, and whether the last line was cut short (by a crash while
+    /// appending it) and skipped
+    fn read_file(&self) -> Result<(Vec<AuditEntry>, bool), Box<dyn StdError>> {
+        if !self.path.exists() {
+            return Ok((Vec::new(), false));
+        }
+
+        let content = fs::read(&self.path)?;
+        let lines: Vec<&[u8]> = content
+            .split(|byte| *byte == b'\n')
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        let mut entries = Vec::with_capacity(lines.len());
+        for (index, line) in lines.iter().enumerate() {
+            let parsed = self.vault.unseal(line).and_then(|plaintext| {
+                serde_json::from_slice::<AuditEntry>(&plaintext).map_err(|err| err.into())
+            });
+            match parsed {
+                Ok(entry) => entries.push(entry),
+                Err(_) if index + 1 == lines.len() && !content.ends_with(b"\n") => {
+                    return Ok((entries, true));
+                }
+                Err(err) => {
+                    return Err(format!(
+                        "Invalid audit log {} line {}: {}",
+                        self.path.display(),
+                        index + 1,
+                        err
+                    )
+                    .into())
+                }
+            }
+        }
+
+        Ok((entries, false))
+    }
+
+    fn write_file(&self, entries: &[AuditEntry]) -> Result<(), Box<dyn StdError>> {
+        write_atomically_with(&self.path, |writer| {
+            for entry in entries {
+                writer.write_all(&self.encode(entry)?)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Rewrites the file with the current state of the vault, after its key changed.
+    /// `entries` must have been read with the previous key.
+    pub fn reseal(&self, entries: &[AuditEntry]) -> Result<(), Box<dyn StdError>> {
+        if entries.is_empty() && !self.path.exists() {
+            return Ok(());
+        }
+        self.write_file(entries)
+    }
+}
+
+impl AuditStorage for AuditFile {
+    fn read_entries(&self) -> Result<Vec<AuditEntry>, Box<dyn StdError>> {
+        Ok(self.read_file()?.0)
+    }
+
+    fn append_entries(
+        &self,
+        mut entries: Vec<AuditEntry>,
+        now: NaiveDateTime,
+        retention: &AuditRetention,
+    ) -> Result<(), Box<dyn StdError>> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| "Failed to acquire audit log lock")?;
+
+        if state.is_none() {
+            let (existing, truncated) = self.read_file()?;
+            if truncated {
+                self.write_file(&existing)?;
+            }
+            *state = Some(AuditFileState::of(&existing));
+        }
+        let current = state.as_mut().ok_or("Audit log state is not loaded")?;
+
+        number_entries(&mut entries, current.last_id);
+        let mut content = Vec::new();
+        for entry in entries.iter() {
+            content.extend(self.encode(entry)?);
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(&content)?;
+        file.sync_data()?;
+
+        let appended = AuditFileState::of(&entries);
+        current.last_id = current.last_id.max(appended.last_id);
+        current.regular += appended.regular;
+        current.bulk += appended.bulk;
+        current.oldest = current.oldest.or(appended.oldest);
+
+        if current.needs_compaction(now, retention) {
+            let mut log = self.read_entries()?;
+            apply_retention(&mut log, now, retention);
+            self.write_file(&log)?;
+            *current = AuditFileState {
+                last_id: current.last_id,
+                ..AuditFileState::of(&log)
+            };
+        }
+
+        Ok(())
+    }
+
+    fn replace_entries(&self, entries: Vec<AuditEntry>) -> Result<(), Box<dyn StdError>> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| "Failed to acquire audit log lock")?;
+
+        self.write_file(&entries)?;
+        *state = Some(AuditFileState::of(&entries));
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+use crate::model::expense::Expense;
+use crate::store::audit::AuditStorage;
+use crate::store::encryption::EncryptedStorage;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::error::Error as StdError;
+
+/// Sub-key of store_data holding the expenses map
+pub static EXPENSES_SUB_KEY: &str = "expenses";
+
+/// Persistence used by the expense store.
+///
+/// A backend only has to read and write the whole "store_data" object; the granular
+/// operations have default implementations on top of that, which backends able to
+/// write single rows (e.g. SQLite) override.
+pub trait StorageBackend: Send + Sync {
+    /// Short name of the backend, reported to the UI
+    fn name(&self) -> &'static str;
+
+    /// Returns the whole store_data object, or Null when nothing was stored yet
+    fn read_data(&self) -> Result<Value, Box<dyn StdError>>;
+
+    /// Replaces the whole store_data object
+    fn write_data(&self, data: &Value) -> Result<(), Box<dyn StdError>>;
+
+    fn load_expenses(&self) -> Result<Option<HashMap<String, Expense>>, Box<dyn StdError>> {
+        let store_data = self.read_data()?;
+
+        let expenses = match store_data.get(EXPENSES_SUB_KEY) {
+            Some(expenses @ Value::Object(_)) => expenses.clone(),
+            _ => return Ok(None),
+        };
+
+        let data: HashMap<String, Expense> = serde_json::from_value(expenses)
+            .map_err(|err| format!("Failed to deserialize expenses: {}", err))?;
+
+        Ok(Some(data))
+    }
+
+    fn get_value(&self, key: &str) -> Result<Option<Value>, Box<dyn StdError>> {
+        Ok(self.read_data()?.get(key).cloned())
+    }
+
+    fn set_value(&self, key: &str, value: Value) -> Result<(), Box<dyn StdError>> {
+        self.commit_values(vec![(key, value)])
+    }
+
+    /// Persists the result of a mutation: the expenses that were added or changed, the ids
+    /// of the removed ones and any other store_data sub-keys to set, all in a single write.
+    fn commit_changes(
+        &self,
+        upserted: &[&Expense],
+        removed_ids: &[String],
+        values: Vec<(&str, Value)>,
+    ) -> Result<(), Box<dyn StdError>> {
+        let mut store_data = self.read_data()?;
+
+        if !store_data.is_object() {
+            store_data = Value::Object(Map::new());
+        }
+
+        let obj = store_data
+            .as_object_mut()
+            .ok_or("store_data is not an object")?;
+        let expenses = obj
+            .entry(EXPENSES_SUB_KEY)
+            .or_insert_with(|| Value::Object(Map::new()));
+        if !expenses.is_object() {
+            *expenses = Value::Object(Map::new());
+        }
+        let expenses = expenses
+            .as_object_mut()
+            .ok_or("expenses is not an object")?;
+
+        for id in removed_ids {
+            expenses.remove(id);
+        }
+        for expense in upserted {
+            let value = serde_json::to_value(expense)
+                .map_err(|err| format!("Failed to serialize expenses: {}", err))?;
+            expenses.insert(expense.get_id().to_string(), value);
+        }
+        for (key, value) in values {
+            obj.insert(key.to_string(), value);
+        }
+
+        self.write_data(&store_data)
+    }
+
+    /// Sets the given store_data sub-keys in a single write
+    fn commit_values(&self, values: Vec<(&str, Value)>) -> Result<(), Box<dyn StdError>> {
+        let mut store_data = self.read_data()?;
+
+        if !store_data.is_object() {
+            store_data = Value::Object(Map::new());
+        }
+
+        let obj = store_data
+            .as_object_mut()
+            .ok_or("store_data is not an object")?;
+        for (key, value) in values {
+            obj.insert(key.to_string(), value);
+        }
+
+        self.write_data(&store_data)
+    }
+
+    /// Encryption at rest, None for backends that do not support it
+    fn encryption(&self) -> Option<&dyn EncryptedStorage> {
+        None
+    }
+
+    /// Storage of the audit log, None for backends that do not keep one
+    fn audit_log(&self) -> Option<&dyn AuditStorage> {
+        None
+    }
+}
+
+impl<B: StorageBackend + ?Sized> StorageBackend for Box<B> {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn read_data(&self) -> Result<Value, Box<dyn StdError>> {
+        (**self).read_data()
+    }
+
+    fn write_data(&self, data: &Value) -> Result<(), Box<dyn StdError>> {
+        (**self).write_data(data)
+    }
+
+    fn load_expenses(&self) -> Result<Option<HashMap<String, Expense>>, Box<dyn StdError>> {
+        (**self).load_expenses()
+    }
+
+    fn get_value(&self, key: &str) -> Result<Option<Value>, Box<dyn StdError>> {
+        (**self).get_value(key)
+    }
+
+    fn set_value(&self, key: &str, value: Value) -> Result<(), Box<dyn StdError>> {
+        (**self).set_value(key, value)
+    }
+
+    fn commit_changes(
+        &self,
+        upserted: &[&Expense],
+        removed_ids: &[String],
+        values: Vec<(&str, Value)>,
+    ) -> Result<(), Box<dyn StdError>> {
+        (**self).commit_changes(upserted, removed_ids, values)
+    }
+
+    fn commit_values(&self, values: Vec<(&str, Value)>) -> Result<(), Box<dyn StdError>> {
+        (**self).commit_values(values)
+    }
+
+    fn encryption(&self) -> Option<&dyn EncryptedStorage> {
+        (**self).encryption()
+    }
+
+    fn audit_log(&self) -> Option<&dyn AuditStorage> {
+        (**self).audit_log()
+    }
+}
@@ -0,0 +1,326 @@
+use crate::model::backup::{
+    BackupDetails, BackupInfo, BackupKeySummary, BackupReason, RetentionPolicy,
+};
+use crate::model::expense::Expense;
+use crate::model::export::{ExportPayload, EXPORT_VERSION};
+use crate::store::backend::EXPENSES_SUB_KEY;
+use crate::store::encryption::Vault;
+use crate::store::json_file_backend::write_atomically;
+use chrono::{Datelike, NaiveDateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::error::Error as StdError;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+/// Directory of the snapshots, inside the app data directory
+pub static BACKUPS_DIR: &str = "backups";
+
+static BACKUP_PREFIX: &str = "store-";
+static BACKUP_EXTENSION: &str = ".json.gz";
+static BACKUP_TIME_FORMAT: &str = "%Y%m%dT%H%M%S%3f";
+
+/// Builds the file name of a snapshot, e.g. `store-20250301T093000000-startup.json.gz`
+fn backup_file_name(created_at: &NaiveDateTime, reason: BackupReason) -> String {
+    format!(
+        "{}{}-{}{}",
+        BACKUP_PREFIX,
+        created_at.format(BACKUP_TIME_FORMAT),
+        reason.as_str(),
+        BACKUP_EXTENSION
+    )
+}
+
+/// Parses a snapshot file name back into its time and reason.
+/// Anything else (including paths) is rejected, so an id can never leave the backups directory.
+fn parse_backup_file_name(name: &str) -> Option<(NaiveDateTime, BackupReason)> {
+    let stem = name
+        .strip_prefix(BACKUP_PREFIX)?
+        .strip_suffix(BACKUP_EXTENSION)?;
+    let (time, reason) = stem.split_once('-')?;
+
+    let created_at = NaiveDateTime::parse_from_str(time, BACKUP_TIME_FORMAT).ok()?;
+    Some((created_at, BackupReason::parse(reason)?))
+}
+
+/// Maps a snapshot time to the (year, day / week / month) period it belongs to
+type PeriodOf = fn(&NaiveDateTime) -> (i32, u32);
+
+/// Returns the snapshots to delete under `retention`: everything that is neither the newest
+/// snapshot nor the latest one of a retained day, ISO week or month.
+pub fn snapshots_to_prune(backups: &[BackupInfo], retention: &RetentionPolicy) -> Vec<String> {
+    let mut sorted: Vec<&BackupInfo> = backups.iter().collect();
+    sorted.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+
+    let mut keep: HashSet<&str> = HashSet::new();
+    if let Some(newest) = sorted.first() {
+        keep.insert(&newest.id);
+    }
+
+    let periods: [(usize, PeriodOf); 3] = [
+        (retention.daily, |date| (date.year(), date.ordinal())),
+        (retention.weekly, |date| {
+            let week = date.iso_week();
+            (week.year(), week.week())
+        }),
+        (retention.monthly, |date| (date.year(), date.month())),
+    ];
+
+    for (count, period_of) in periods {
+        let mut seen: HashSet<(i32, u32)> = HashSet::new();
+        for backup in sorted.iter() {
+            if seen.len() >= count {
+                break;
+            }
+            // Newest first, so the first snapshot of each period is its latest
+            if seen.insert(period_of(&backup.created_at)) {
+                keep.insert(&backup.id);
+            }
+        }
+    }
+
+    sorted
+        .iter()
+        .filter(|backup| !keep.contains(backup.id.as_str()))
+        .map(|backup| backup.id.clone())
+        .collect()
+}
+
+/// Timestamped, gzip compressed snapshots of the full store (the `export_all_data` payload),
+/// pruned after each new snapshot according to the retention policy.
+pub struct BackupManager {
+    dir: PathBuf,
+    retention: RwLock<RetentionPolicy>,
+    // Snapshots are sealed like the store itself when it is encrypted
+    vault: Arc<Vault>,
+}
+
+impl BackupManager {
+    pub fn new<P: AsRef<Path>>(dir: P, retention: RetentionPolicy) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            retention: RwLock::new(retention),
+            vault: Vault::disabled(),
+        }
+    }
+
+    /// Seals the snapshots with the key of `vault`, the one of the encrypted store
+    pub fn with_vault(mut self, vault: Arc<Vault>) -> Self {
+        self.vault = vault;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn retention(&self) -> Result<RetentionPolicy, Box<dyn StdError>> {
+        let retention = self
+            .retention
+            .read()
+            .map_err(|_| "Failed to acquire retention read lock")?;
+        Ok(*retention)
+    }
+
+    /// Changes the retention policy and prunes the snapshots it no longer keeps
+    pub fn set_retention(&self, retention: RetentionPolicy) -> Result<(), Box<dyn StdError>> {
+        {
+            let mut current = self
+                .retention
+                .write()
+                .map_err(|_| "Failed to acquire retention write lock")?;
+            *current = retention;
+        }
+
+        self.prune()?;
+        Ok(())
+    }
+
+    /// Takes a snapshot of `store_data` now. Nothing is written for an empty store.
+    ///
+    /// Returns:
+    /// - `Option<BackupInfo>`: The new snapshot, or None if the store was empty.
+    pub fn create_snapshot(
+        &self,
+        store_data: &Value,
+        reason: BackupReason,
+    ) -> Result<Option<BackupInfo>, Box<dyn StdError>> {
+        self.create_snapshot_at(store_data, reason, Utc::now().naive_utc())
+    }
+
+    /// Same as `create_snapshot`, taken at the given UTC time
+    pub fn create_snapshot_at(
+        &self,
+        store_data: &Value,
+        reason: BackupReason,
+        created_at: NaiveDateTime,
+    ) -> Result<Option<BackupInfo>, Box<dyn StdError>> {
+        let is_empty = match store_data {
+            Value::Null => true,
+            Value::Object(obj) => obj.is_empty(),
+            _ => false,
+        };
+        if is_empty {
+            return Ok(None);
+        }
+
+        let payload = ExportPayload {
+            version: EXPORT_VERSION,
+            data: store_data.clone(),
+        };
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&serde_json::to_vec(&payload)?)?;
+        let compressed = encoder.finish()?;
+
+        let content = self.vault.seal(&compressed)?;
+
+        let id = backup_file_name(&created_at, reason);
+        write_atomically(&self.dir.join(&id), &content)?;
+
+        self.prune()?;
+
+        Ok(Some(BackupInfo {
+            id,
+            created_at,
+            reason,
+            size_bytes: content.len() as u64,
+        }))
+    }
+
+    /// Lists the snapshots, newest first
+    pub fn list(&self) -> Result<Vec<BackupInfo>, Box<dyn StdError>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut backups = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            if let Some((created_at, reason)) = parse_backup_file_name(&name) {
+                backups.push(BackupInfo {
+                    id: name,
+                    created_at,
+                    reason,
+                    size_bytes: entry.metadata()?.len(),
+                });
+            }
+        }
+
+        backups.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+        Ok(backups)
+    }
+
+    /// Deletes the snapshots the retention policy does not keep
+    ///
+    /// Returns:
+    /// - `Vec<String>`: The ids of the deleted snapshots.
+    pub fn prune(&self) -> Result<Vec<String>, Box<dyn StdError>> {
+        let pruned = snapshots_to_prune(&self.list()?, &self.retention()?);
+
+        for id in pruned.iter() {
+            fs::remove_file(self.dir.join(id))?;
+        }
+
+        Ok(pruned)
+    }
+
+    fn info(&self, id: &str) -> Result<BackupInfo, Box<dyn StdError>> {
+        let (created_at, reason) =
+            parse_backup_file_name(id).ok_or_else(|| format!("Invalid backup id: {}", id))?;
+
+        let path = self.dir.join(id);
+        if !path.exists() {
+            return Err(format!("Backup {} does not exist", id).into());
+        }
+
+        Ok(BackupInfo {
+            id: id.to_string(),
+            created_at,
+            reason,
+            size_bytes: fs::metadata(&path)?.len(),
+        })
+    }
+
+    /// Reads back the payload of a snapshot
+    pub fn load(&self, id: &str) -> Result<ExportPayload, Box<dyn StdError>> {
+        self.info(id)?;
+
+        let compressed = self.vault.unseal(&fs::read(self.dir.join(id))?)?;
+
+        let mut content = String::new();
+        GzDecoder::new(compressed.as_slice()).read_to_string(&mut content)?;
+
+        let payload: ExportPayload = serde_json::from_str(&content)
+            .map_err(|err| format!("Backup {} is corrupted: {}", id, err))?;
+        Ok(payload)
+    }
+
+    /// Runs `change`, which switches the key of the vault (e.g. a new passphrase), then
+    /// rewrites every snapshot sealed with the new key.
+    pub fn reseal_all<R>(
+        &self,
+        change: impl FnOnce() -> Result<R, Box<dyn StdError>>,
+    ) -> Result<R, Box<dyn StdError>> {
+        let mut snapshots = Vec::new();
+        for info in self.list()? {
+            let compressed = self.vault.unseal(&fs::read(self.dir.join(&info.id))?)?;
+            snapshots.push((info.id, compressed));
+        }
+
+        let result = change()?;
+
+        for (id, compressed) in snapshots {
+            write_atomically(&self.dir.join(id), &self.vault.seal(&compressed)?)?;
+        }
+
+        Ok(result)
+    }
+
+    /// Summary of what a snapshot holds, without restoring it
+    pub fn inspect(&self, id: &str) -> Result<BackupDetails, Box<dyn StdError>> {
+        let info = self.info(id)?;
+        let payload = self.load(id)?;
+
+        let mut keys: Vec<BackupKeySummary> = payload
+            .data
+            .as_object()
+            .map(|obj| {
+                obj.iter()
+                    .map(|(key, value)| BackupKeySummary {
+                        key: key.clone(),
+                        entries: match value {
+                            Value::Object(obj) => obj.len(),
+                            Value::Array(items) => items.len(),
+                            Value::Null => 0,
+                            _ => 1,
+                        },
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        keys.sort_by(|a, b| a.key.cmp(&b.key));
+
+        let expenses: HashMap<String, Expense> = match payload.data.get(EXPENSES_SUB_KEY) {
+            Some(value @ Value::Object(_)) => serde_json::from_value(value.clone())
+                .map_err(|err| format!("Backup {} has invalid expenses: {}", id, err))?,
+            _ => HashMap::new(),
+        };
+
+        Ok(BackupDetails {
+            info,
+            version: payload.version,
+            keys,
+            expense_count: expenses.len(),
+            first_expense_date: expenses.values().map(|e| *e.get_date()).min(),
+            last_expense_date: expenses.values().map(|e| *e.get_date()).max(),
+        })
+    }
+}
@@ -0,0 +1,45 @@
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Recorded when a change is not made by a command (tests, startup, ...)
+static INTERNAL_COMMAND: &str = "internal";
+
+static NEXT_INVOCATION: AtomicU64 = AtomicU64::new(1);
+
+/// The command a store change is made for
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandContext {
+    pub name: String,
+    /// Unique per call of the command, to group all the transactions it runs
+    pub invocation: u64,
+}
+
+thread_local! {
+    static CURRENT_COMMAND: RefCell<Option<CommandContext>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` on behalf of the command `command`, which is recorded with every change `f`
+/// makes (audit log, undo history). Synchronous commands run on the thread calling the
+/// handler, so the invoke handler wraps every command with it.
+pub fn run_as_command<R>(command: &str, f: impl FnOnce() -> R) -> R {
+    let context = CommandContext {
+        name: command.to_string(),
+        invocation: NEXT_INVOCATION.fetch_add(1, Ordering::Relaxed),
+    };
+
+    let previous = CURRENT_COMMAND.with(|current| current.replace(Some(context)));
+    let result = f();
+    CURRENT_COMMAND.with(|current| *current.borrow_mut() = previous);
+    result
+}
+
+/// Command running on this thread. Outside of any command every transaction is its own
+/// invocation.
+pub fn current_command() -> CommandContext {
+    CURRENT_COMMAND.with(|current| {
+        current.borrow().clone().unwrap_or_else(|| CommandContext {
+            name: INTERNAL_COMMAND.to_string(),
+            invocation: NEXT_INVOCATION.fetch_add(1, Ordering::Relaxed),
+        })
+    })
+}
@@ -0,0 +1,347 @@
+use crate::config::MIN_PASSPHRASE_LENGTH;
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use std::error::Error as StdError;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+/// Marks a sealed file, always the first field of the serialized envelope
+static ENVELOPE_FORMAT: &str = "expense-tracker-encrypted";
+static ENVELOPE_PREFIX: &str = "{\"format\":\"expense-tracker-encrypted\"";
+const ENVELOPE_VERSION: u32 = 1;
+
+const KEY_LENGTH: usize = 32;
+const SALT_LENGTH: usize = 16;
+
+/// Returned by every store access while the encrypted store is locked
+#[derive(Debug, Clone, Copy)]
+pub struct StoreLockedError;
+
+impl fmt::Display for StoreLockedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The store is locked, unlock it with its passphrase first"
+        )
+    }
+}
+
+impl StdError for StoreLockedError {}
+
+/// Argon2id parameters the key of a sealed file was derived with
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KdfParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+    /// Hex encoded random salt
+    pub salt: String,
+}
+
+impl KdfParams {
+    /// Argon2id defaults with a fresh random salt
+    pub fn generate() -> Self {
+        Self::generate_with(
+            Params::DEFAULT_M_COST,
+            Params::DEFAULT_T_COST,
+            Params::DEFAULT_P_COST,
+        )
+    }
+
+    pub fn generate_with(memory_kib: u32, iterations: u32, parallelism: u32) -> Self {
+        let mut salt = [0u8; SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+
+        Self {
+            memory_kib,
+            iterations,
+            parallelism,
+            salt: hex::encode(salt),
+        }
+    }
+
+    pub fn derive_key(&self, passphrase: &str) -> Result<EncryptionKey, Box<dyn StdError>> {
+        let params = Params::new(
+            self.memory_kib,
+            self.iterations,
+            self.parallelism,
+            Some(KEY_LENGTH),
+        )
+        .map_err(|err| format!("Invalid key derivation parameters: {}", err))?;
+        let salt = hex::decode(&self.salt).map_err(|err| format!("Invalid salt: {}", err))?;
+
+        let mut key = [0u8; KEY_LENGTH];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|err| format!("Failed to derive the key: {}", err))?;
+
+        Ok(EncryptionKey {
+            key,
+            kdf: self.clone(),
+        })
+    }
+}
+
+/// Rejects passphrases too short to be worth the encryption
+pub fn validate_passphrase(passphrase: &str) -> Result<(), Box<dyn StdError>> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+        return Err(format!(
+            "The passphrase must have at least {} characters",
+            MIN_PASSPHRASE_LENGTH
+        )
+        .into());
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Envelope {
+    format: String,
+    version: u32,
+    kdf: KdfParams,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl Envelope {
+    /// Authenticated along with the ciphertext, so the header cannot be tampered with
+    fn associated_data(&self) -> Result<Vec<u8>, Box<dyn StdError>> {
+        Ok(serde_json::to_vec(&(
+            &self.format,
+            self.version,
+            &self.kdf,
+        ))?)
+    }
+}
+
+/// True if `content` was written by `EncryptionKey::seal`
+pub fn is_sealed(content: &[u8]) -> bool {
+    content.starts_with(ENVELOPE_PREFIX.as_bytes())
+}
+
+/// Reads the key derivation parameters of a sealed file, needed to unlock it
+pub fn sealed_kdf_params(content: &[u8]) -> Result<KdfParams, Box<dyn StdError>> {
+    let envelope: Envelope = serde_json::from_slice(content)
+        .map_err(|err| format!("Invalid encrypted file: {}", err))?;
+    Ok(envelope.kdf)
+}
+
+/// Key derived from the passphrase, with the parameters needed to derive it again
+#[derive(Clone)]
+pub struct EncryptionKey {
+    key: [u8; KEY_LENGTH],
+    kdf: KdfParams,
+}
+
+impl EncryptionKey {
+    pub fn kdf(&self) -> &KdfParams {
+        &self.kdf
+    }
+
+    /// True if `passphrase` derives this same key
+    pub fn matches(&self, passphrase: &str) -> Result<bool, Box<dyn StdError>> {
+        Ok(self.kdf.derive_key(passphrase)?.key == self.key)
+    }
+
+    /// Encrypts `plaintext` with XChaCha20-Poly1305 under a random nonce
+    pub fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>, Box<dyn StdError>> {
+        let cipher = XChaCha20Poly1305::new(&self.key.into());
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+        let mut envelope = Envelope {
+            format: ENVELOPE_FORMAT.to_string(),
+            version: ENVELOPE_VERSION,
+            kdf: self.kdf.clone(),
+            nonce: hex::encode(nonce),
+            ciphertext: String::new(),
+        };
+        let aad = envelope.associated_data()?;
+
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| "Failed to encrypt data")?;
+        envelope.ciphertext = hex::encode(ciphertext);
+
+        Ok(serde_json::to_vec(&envelope)?)
+    }
+
+    /// Decrypts a sealed file, failing if it was not sealed with this key or was altered
+    pub fn open(&self, content: &[u8]) -> Result<Vec<u8>, Box<dyn StdError>> {
+        let envelope: Envelope = serde_json::from_slice(content)
+            .map_err(|err| format!("Invalid encrypted file: {}", err))?;
+
+        if envelope.format != ENVELOPE_FORMAT || envelope.version > ENVELOPE_VERSION {
+            return Err(format!("Unsupported encrypted file version {}", envelope.version).into());
+        }
+        if envelope.kdf != self.kdf {
+            return Err("The file was encrypted with another passphrase".into());
+        }
+
+        let nonce =
+            hex::decode(&envelope.nonce).map_err(|err| format!("Invalid nonce: {}", err))?;
+        if nonce.len() != 24 {
+            return Err("Invalid nonce length".into());
+        }
+        let ciphertext = hex::decode(&envelope.ciphertext)
+            .map_err(|err| format!("Invalid ciphertext: {}", err))?;
+        let aad = envelope.associated_data()?;
+
+        let cipher = XChaCha20Poly1305::new(&self.key.into());
+        let plaintext = cipher
+            .decrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| "Wrong passphrase or corrupted data")?;
+
+        Ok(plaintext)
+    }
+}
+
+impl Drop for EncryptionKey {
+    fn drop(&mut self) {
+        self.key.fill(0);
+    }
+}
+
+enum VaultState {
+    /// Files are written in plain text
+    Disabled,
+    /// Encrypted, the key is not known until the store is unlocked
+    Locked,
+    Unlocked(EncryptionKey),
+}
+
+/// Holds the key of the encrypted store while it is unlocked.
+///
+/// Shared by everything persisting store data (the store file and the backups),
+/// so they all seal with the same key and all lock together.
+pub struct Vault {
+    state: RwLock<VaultState>,
+}
+
+impl Vault {
+    pub fn disabled() -> Arc<Self> {
+        Arc::new(Self {
+            state: RwLock::new(VaultState::Disabled),
+        })
+    }
+
+    pub fn locked() -> Arc<Self> {
+        Arc::new(Self {
+            state: RwLock::new(VaultState::Locked),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.state
+            .read()
+            .map(|state| !matches!(*state, VaultState::Disabled))
+            .unwrap_or(true)
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.state
+            .read()
+            .map(|state| matches!(*state, VaultState::Locked))
+            .unwrap_or(true)
+    }
+
+    /// Current key, None when encryption is disabled
+    pub fn key(&self) -> Result<Option<EncryptionKey>, Box<dyn StdError>> {
+        let state = self
+            .state
+            .read()
+            .map_err(|_| "Failed to acquire vault read lock")?;
+        match &*state {
+            VaultState::Disabled => Ok(None),
+            VaultState::Locked => Err(Box::new(StoreLockedError)),
+            VaultState::Unlocked(key) => Ok(Some(key.clone())),
+        }
+    }
+
+    /// Unlocks with `key`, or disables encryption when None
+    pub fn set_key(&self, key: Option<EncryptionKey>) -> Result<(), Box<dyn StdError>> {
+        let mut state = self
+            .state
+            .write()
+            .map_err(|_| "Failed to acquire vault write lock")?;
+        *state = match key {
+            Some(key) => VaultState::Unlocked(key),
+            None => VaultState::Disabled,
+        };
+        Ok(())
+    }
+
+    /// Forgets the key, does nothing when encryption is disabled
+    pub fn lock(&self) -> Result<(), Box<dyn StdError>> {
+        let mut state = self
+            .state
+            .write()
+            .map_err(|_| "Failed to acquire vault write lock")?;
+        if !matches!(*state, VaultState::Disabled) {
+            *state = VaultState::Locked;
+        }
+        Ok(())
+    }
+
+    /// Encrypts `plaintext` with the current key, or returns it as is when disabled
+    pub fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>, Box<dyn StdError>> {
+        match self.key()? {
+            Some(key) => key.seal(plaintext),
+            None => Ok(plaintext.to_vec()),
+        }
+    }
+
+    /// Decrypts a sealed file with the current key. Plain files (e.g. backups taken
+    /// before encryption was enabled) are returned as they are.
+    pub fn unseal(&self, content: &[u8]) -> Result<Vec<u8>, Box<dyn StdError>> {
+        if !is_sealed(content) {
+            return Ok(content.to_vec());
+        }
+
+        match self.key()? {
+            Some(key) => key.open(content),
+            None => Err("The file is encrypted but encryption is not enabled".into()),
+        }
+    }
+}
+
+/// Encryption at rest of a storage backend
+pub trait EncryptedStorage {
+    /// Vault of the backend, to share with the backups
+    fn vault(&self) -> Arc<Vault>;
+
+    /// Derives the key from `passphrase` and loads the decrypted data
+    fn unlock(&self, passphrase: &str) -> Result<(), Box<dyn StdError>>;
+
+    /// Forgets the key and the decrypted data
+    fn lock(&self) -> Result<(), Box<dyn StdError>>;
+
+    /// Encrypts the plain text store with a key derived from `passphrase`
+    fn enable_encryption(&self, passphrase: &str) -> Result<(), Box<dyn StdError>>;
+
+    /// Re-encrypts the store with a key derived from `new_passphrase`
+    fn change_passphrase(
+        &self,
+        current_passphrase: &str,
+        new_passphrase: &str,
+    ) -> Result<(), Box<dyn StdError>>;
+
+    /// Writes the store back in plain text
+    fn disable_encryption(&self, passphrase: &str) -> Result<(), Box<dyn StdError>>;
+}
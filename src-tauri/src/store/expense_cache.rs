@@ -0,0 +1,129 @@
+use crate::model::expense::Expense;
+use crate::store::text_index::TextIndex;
+use chrono::NaiveDateTime;
+use std::collections::{BTreeSet, HashMap};
+
+/// Loaded, indexed collection of the stored expenses (by id, date, tag and description text).
+///
+/// Kept by the expense store so lookups do not deserialize the whole expenses map again,
+/// and updated in place after every successful write.
+#[derive(Clone, Debug, Default)]
+pub struct ExpenseCache {
+    by_id: HashMap<String, Expense>,
+    by_date: BTreeSet<(NaiveDateTime, String)>,
+    by_tag: HashMap<String, BTreeSet<String>>,
+    by_text: TextIndex,
+    // False while the backend has no expenses key at all
+    stored: bool,
+}
+
+impl ExpenseCache {
+    /// Builds the indexes from what the backend returned, None meaning nothing is stored yet
+    pub fn from_stored(data: Option<HashMap<String, Expense>>) -> Self {
+        let stored = data.is_some();
+        let mut cache = Self::default();
+
+        for (_, expense) in data.unwrap_or_default() {
+            cache.upsert(expense);
+        }
+        cache.stored = stored;
+
+        cache
+    }
+
+    pub fn is_stored(&self) -> bool {
+        self.stored
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_id.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_id.is_empty()
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Expense> {
+        self.by_id.get(id)
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.by_id.contains_key(id)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &Expense> {
+        self.by_id.values()
+    }
+
+    /// Expenses dated between `start` and `end` (both inclusive), ordered by date
+    pub fn between(&self, start: NaiveDateTime, end: NaiveDateTime) -> Vec<&Expense> {
+        if start > end {
+            return Vec::new();
+        }
+
+        self.by_date
+            .range((start, String::new())..)
+            .take_while(|(date, _)| *date <= end)
+            .filter_map(|(_, id)| self.by_id.get(id))
+            .collect()
+    }
+
+    /// Expenses having the given tag, ordered by id
+    pub fn with_tag(&self, tag: &str) -> Vec<&Expense> {
+        self.by_tag
+            .get(tag)
+            .map(|ids| ids.iter().filter_map(|id| self.by_id.get(id)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Expenses whose description matches the text search, best match first
+    pub fn search_description(&self, query: &str, limit: usize) -> Vec<(&Expense, f64)> {
+        self.by_text
+            .search(query, limit)
+            .into_iter()
+            .filter_map(|(id, score)| self.by_id.get(&id).map(|expense| (expense, score)))
+            .collect()
+    }
+
+    /// Every tag in use with the number of expenses having it
+    pub fn tag_counts(&self) -> HashMap<&str, usize> {
+        self.by_tag
+            .iter()
+            .map(|(tag, ids)| (tag.as_str(), ids.len()))
+            .collect()
+    }
+
+    /// Inserts or replaces an expense, keeping the indexes in sync
+    pub fn upsert(&mut self, expense: Expense) {
+        let id = expense.get_id().to_string();
+        self.remove(&id);
+
+        self.by_date.insert((*expense.get_date(), id.clone()));
+        self.by_text.add(&id, expense.get_description());
+        for tag in expense.get_tags() {
+            self.by_tag
+                .entry(tag.clone())
+                .or_default()
+                .insert(id.clone());
+        }
+        self.by_id.insert(id, expense);
+        self.stored = true;
+    }
+
+    pub fn remove(&mut self, id: &str) -> Option<Expense> {
+        let expense = self.by_id.remove(id)?;
+
+        self.by_date.remove(&(*expense.get_date(), id.to_string()));
+        self.by_text.remove(id, expense.get_description());
+        for tag in expense.get_tags() {
+            if let Some(ids) = self.by_tag.get_mut(tag) {
+                ids.remove(id);
+                if ids.is_empty() {
+                    self.by_tag.remove(tag);
+                }
+            }
+        }
+
+        Some(expense)
+    }
+}
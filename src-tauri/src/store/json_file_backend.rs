@@ -0,0 +1,248 @@
+use crate::store::app_store::STORE_DATA_KEY;
+use crate::store::audit::{AuditFile, AuditStorage};
+use crate::store::backend::StorageBackend;
+use crate::store::encryption::{
+    is_sealed, sealed_kdf_params, validate_passphrase, EncryptedStorage, EncryptionKey, KdfParams,
+    StoreLockedError, Vault,
+};
+use serde_json::{Map, Value};
+use std::error::Error as StdError;
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+/// Keeps store_data in a plain JSON file, without needing a running Tauri app.
+///
+/// The file uses the same layout as store.json (`{ "store_data": { ... } }`),
+/// so it can open a store.json directly. Every write is atomic, see `write_atomically`.
+/// When encryption is enabled the whole file is sealed with the key of its `Vault`.
+/// The audit log is appended to a separate file next to it, e.g. store.audit.jsonl.
+pub struct JsonFileBackend {
+    path: PathBuf,
+    data: RwLock<Value>,
+    vault: Arc<Vault>,
+    audit: AuditFile,
+}
+
+impl JsonFileBackend {
+    /// Opens the file at `path`, starting empty when it does not exist yet.
+    /// An encrypted file is opened locked, see `EncryptedStorage::unlock`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn StdError>> {
+        let path = path.as_ref().to_path_buf();
+
+        let content = if path.exists() {
+            Some(fs::read(&path)?)
+        } else {
+            None
+        };
+
+        let (data, vault) = match content {
+            Some(content) if is_sealed(&content) => (Value::Null, Vault::locked()),
+            Some(content) => (Self::parse_file(&path, &content)?, Vault::disabled()),
+            None => (Value::Null, Vault::disabled()),
+        };
+
+        Ok(Self {
+            audit: AuditFile::new(path.with_extension("audit.jsonl"), Arc::clone(&vault)),
+            path,
+            data: RwLock::new(data),
+            vault,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn parse_file(path: &Path, content: &[u8]) -> Result<Value, Box<dyn StdError>> {
+        let file: Value = serde_json::from_slice(content)
+            .map_err(|err| format!("Invalid store file {}: {}", path.display(), err))?;
+        Ok(file.get(STORE_DATA_KEY).cloned().unwrap_or(Value::Null))
+    }
+
+    /// Writes `data` sealed with the current state of the vault
+    fn persist(&self, data: &Value) -> Result<(), Box<dyn StdError>> {
+        let mut file = Map::new();
+        file.insert(STORE_DATA_KEY.to_string(), data.clone());
+
+        let content = self.vault.seal(&serde_json::to_vec_pretty(&file)?)?;
+        write_atomically(&self.path, &content)
+    }
+
+    /// Switches the vault to `key` and rewrites the file and the audit log with it,
+    /// restoring the previous key if a write fails
+    fn reseal(&self, key: Option<EncryptionKey>) -> Result<(), Box<dyn StdError>> {
+        let data = self.read_data()?;
+        let entries = self.audit.read_entries()?;
+        let previous = self.vault.key()?;
+
+        self.vault.set_key(key)?;
+        if let Err(err) = self
+            .persist(&data)
+            .and_then(|_| self.audit.reseal(&entries))
+        {
+            self.vault.set_key(previous)?;
+            // The store file may already be sealed with the new key
+            self.persist(&data)?;
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    /// Current key, after checking `passphrase` derives it
+    fn verified_key(&self, passphrase: &str) -> Result<EncryptionKey, Box<dyn StdError>> {
+        let key = self
+            .vault
+            .key()?
+            .ok_or("Encryption is not enabled for the store")?;
+
+        if !key.matches(passphrase)? {
+            return Err("Wrong passphrase".into());
+        }
+        Ok(key)
+    }
+}
+
+impl StorageBackend for JsonFileBackend {
+    fn name(&self) -> &'static str {
+        "json-file"
+    }
+
+    fn read_data(&self) -> Result<Value, Box<dyn StdError>> {
+        if self.vault.is_locked() {
+            return Err(Box::new(StoreLockedError));
+        }
+
+        let data = self
+            .data
+            .read()
+            .map_err(|_| "Failed to acquire read lock")?;
+        Ok(data.clone())
+    }
+
+    fn write_data(&self, data: &Value) -> Result<(), Box<dyn StdError>> {
+        let mut current = self
+            .data
+            .write()
+            .map_err(|_| "Failed to acquire write lock")?;
+
+        self.persist(data)?;
+
+        *current = data.clone();
+        Ok(())
+    }
+
+    fn encryption(&self) -> Option<&dyn EncryptedStorage> {
+        Some(self)
+    }
+
+    fn audit_log(&self) -> Option<&dyn AuditStorage> {
+        Some(&self.audit)
+    }
+}
+
+impl EncryptedStorage for JsonFileBackend {
+    fn vault(&self) -> Arc<Vault> {
+        Arc::clone(&self.vault)
+    }
+
+    fn unlock(&self, passphrase: &str) -> Result<(), Box<dyn StdError>> {
+        if !self.vault.is_enabled() {
+            return Err("Encryption is not enabled for the store".into());
+        }
+        if !self.vault.is_locked() {
+            return Ok(());
+        }
+
+        let content = fs::read(&self.path)?;
+        let key = sealed_kdf_params(&content)?.derive_key(passphrase)?;
+        let plaintext = key.open(&content).map_err(|_| "Wrong passphrase")?;
+        let data = Self::parse_file(&self.path, &plaintext)?;
+
+        let mut current = self
+            .data
+            .write()
+            .map_err(|_| "Failed to acquire write lock")?;
+        *current = data;
+        self.vault.set_key(Some(key))
+    }
+
+    fn lock(&self) -> Result<(), Box<dyn StdError>> {
+        if !self.vault.is_enabled() {
+            return Err("Encryption is not enabled for the store".into());
+        }
+
+        let mut current = self
+            .data
+            .write()
+            .map_err(|_| "Failed to acquire write lock")?;
+        self.vault.lock()?;
+        *current = Value::Null;
+        Ok(())
+    }
+
+    fn enable_encryption(&self, passphrase: &str) -> Result<(), Box<dyn StdError>> {
+        if self.vault.is_enabled() {
+            return Err("Encryption is already enabled for the store".into());
+        }
+        validate_passphrase(passphrase)?;
+
+        self.reseal(Some(KdfParams::generate().derive_key(passphrase)?))
+    }
+
+    fn change_passphrase(
+        &self,
+        current_passphrase: &str,
+        new_passphrase: &str,
+    ) -> Result<(), Box<dyn StdError>> {
+        self.verified_key(current_passphrase)?;
+        validate_passphrase(new_passphrase)?;
+
+        // A new salt too, so the old key is useless for anything written from now on
+        self.reseal(Some(KdfParams::generate().derive_key(new_passphrase)?))
+    }
+
+    fn disable_encryption(&self, passphrase: &str) -> Result<(), Box<dyn StdError>> {
+        self.verified_key(passphrase)?;
+        self.reseal(None)
+    }
+}
+
+/// Replaces the file at `path` with `content` so that a crash at any point leaves
+/// either the old or the new file on disk, never a truncated one.
+///
+/// The content goes to a temporary file in the same directory, which is flushed to
+/// disk and then renamed over the target (a rename within a directory is atomic).
+pub fn write_atomically(path: &Path, content: &[u8]) -> Result<(), Box<dyn StdError>> {
+    write_atomically_with(path, |writer| Ok(writer.write_all(content)?))
+}
+
+/// Same as `write_atomically`, with the content streamed by `write` instead of held in memory
+pub fn write_atomically_with(
+    path: &Path,
+    write: impl FnOnce(&mut dyn Write) -> Result<(), Box<dyn StdError>>,
+) -> Result<(), Box<dyn StdError>> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(dir)?;
+
+    let temp = tempfile::NamedTempFile::new_in(dir)?;
+    let mut writer = BufWriter::new(temp);
+    write(&mut writer)?;
+    let temp = writer
+        .into_inner()
+        .map_err(|err| format!("Failed to write {}: {}", path.display(), err))?;
+    temp.as_file().sync_all()?;
+    temp.persist(path)
+        .map_err(|err| format!("Failed to replace {}: {}", path.display(), err))?;
+
+    // Persist the rename itself
+    #[cfg(unix)]
+    fs::File::open(dir)?.sync_all()?;
+
+    Ok(())
+}
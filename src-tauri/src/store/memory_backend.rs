@@ -0,0 +1,58 @@
+use crate::store::audit::{AuditStorage, MemoryAuditLog};
+use crate::store::backend::StorageBackend;
+use serde_json::Value;
+use std::error::Error as StdError;
+use std::sync::RwLock;
+
+/// Keeps store_data in memory only, e.g. for tests
+pub struct MemoryBackend {
+    data: RwLock<Value>,
+    audit: MemoryAuditLog,
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self::with_data(Value::Null)
+    }
+
+    /// Starts from the given store_data object
+    pub fn with_data(data: Value) -> Self {
+        Self {
+            data: RwLock::new(data),
+            audit: MemoryAuditLog::default(),
+        }
+    }
+}
+
+impl Default for MemoryBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StorageBackend for MemoryBackend {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn read_data(&self) -> Result<Value, Box<dyn StdError>> {
+        let data = self
+            .data
+            .read()
+            .map_err(|_| "Failed to acquire read lock")?;
+        Ok(data.clone())
+    }
+
+    fn write_data(&self, data: &Value) -> Result<(), Box<dyn StdError>> {
+        let mut current = self
+            .data
+            .write()
+            .map_err(|_| "Failed to acquire write lock")?;
+        *current = data.clone();
+        Ok(())
+    }
+
+    fn audit_log(&self) -> Option<&dyn AuditStorage> {
+        Some(&self.audit)
+    }
+}
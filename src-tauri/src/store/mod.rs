@@ -1 +1,16 @@
 pub mod app_store;
+pub mod audit;
+pub mod backend;
+pub mod backup;
+pub mod command_context;
+pub mod encryption;
+pub mod expense_cache;
+pub mod json_file_backend;
+pub mod memory_backend;
+pub mod schema;
+pub mod settings;
+pub mod sqlite_store;
+pub mod tauri_store_backend;
+pub mod text_index;
+pub mod transaction;
+pub mod undo;
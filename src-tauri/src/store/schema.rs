@@ -0,0 +1,280 @@
+use crate::model::migration::{MigrationReport, MigrationStepReport};
+use crate::model::money::{Currency, Money};
+use crate::store::app_store::{hash_identity, identity_key_of};
+use crate::store::audit::AUDIT_LOG_SUB_KEY;
+use crate::store::backend::EXPENSES_SUB_KEY;
+use chrono::NaiveDateTime;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::error::Error as StdError;
+
+/// Sub-key of store_data holding the version of its layout, missing before versioning
+pub static SCHEMA_VERSION_SUB_KEY: &str = "schema_version";
+
+type MigrationFn = fn(&mut Map<String, Value>) -> Result<Vec<String>, Box<dyn StdError>>;
+
+struct Migration {
+    description: &'static str,
+    apply: MigrationFn,
+}
+
+/// Applied in order to store_data, the schema version is the index + 1 of the last applied
+/// migration. Never edit an existing entry, append a new one instead.
+const MIGRATIONS: &[Migration] = &[
+    // 1: layout of the first versioned release
+    Migration {
+        description: "Key expenses by their id",
+        apply: key_expenses_by_id,
+    },
+    // 2: ids no longer follow the content, imports are deduplicated by fingerprint
+    Migration {
+        description: "Keep the content hash ids as import fingerprints",
+        apply: fingerprint_expenses,
+    },
+    // 3: amounts are exact cents, converted shekels used to keep repeating decimals
+    Migration {
+        description: "Round expense amounts to cents",
+        apply: round_expense_amounts,
+    },
+    // 4: the audit log has its own storage, see `AuditStorage`
+    Migration {
+        description: "Move the audit log out of store_data",
+        apply: remove_audit_log,
+    },
+];
+
+/// Schema version written by this version of the app
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// Version of a store_data object, 0 when it predates the versioning
+pub fn schema_version(data: &Map<String, Value>) -> Result<u32, Box<dyn StdError>> {
+    match data.get(SCHEMA_VERSION_SUB_KEY) {
+        None | Some(Value::Null) => Ok(0),
+        Some(value) => value
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| format!("Invalid schema version: {}", value).into()),
+    }
+}
+
+/// Runs every migration `data` has not been through yet, then records the new version.
+/// In a dry run `data` is left untouched and only the report is produced.
+///
+/// Fails when `data` comes from a newer version of the app.
+pub fn migrate(
+    data: &mut Map<String, Value>,
+    dry_run: bool,
+) -> Result<MigrationReport, Box<dyn StdError>> {
+    let from_version = schema_version(data)?;
+    if from_version > SCHEMA_VERSION {
+        return Err(format!(
+            "Store schema version {} is newer than supported version {}",
+            from_version, SCHEMA_VERSION
+        )
+        .into());
+    }
+
+    let mut migrated = data.clone();
+    let mut steps = Vec::new();
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(from_version as usize) {
+        let version = index as u32 + 1;
+        let changes = (migration.apply)(&mut migrated)
+            .map_err(|err| format!("Failed to apply migration {}: {}", version, err))?;
+        migrated.insert(SCHEMA_VERSION_SUB_KEY.to_string(), Value::from(version));
+
+        steps.push(MigrationStepReport {
+            version,
+            description: migration.description.to_string(),
+            changes,
+        });
+    }
+
+    let mut keys: Vec<String> = migrated
+        .iter()
+        .filter(|(key, value)| data.get(key.as_str()) != Some(value))
+        .map(|(key, _)| key.clone())
+        .collect();
+    keys.extend(
+        data.keys()
+            .filter(|key| !migrated.contains_key(key.as_str()))
+            .cloned(),
+    );
+
+    if !dry_run {
+        *data = migrated;
+    }
+
+    Ok(MigrationReport {
+        from_version,
+        to_version: SCHEMA_VERSION,
+        dry_run,
+        steps,
+        keys,
+    })
+}
+
+/// The expenses were once saved as a list, and edits could leave an entry under a key
+/// other than its id. Stores them as an object keyed by id.
+fn key_expenses_by_id(data: &mut Map<String, Value>) -> Result<Vec<String>, Box<dyn StdError>> {
+    let mut changes = Vec::new();
+
+    let entries: Vec<(Option<String>, Value)> = match data.remove(EXPENSES_SUB_KEY) {
+        None => return Ok(changes),
+        Some(Value::Null) => {
+            data.insert(EXPENSES_SUB_KEY.to_string(), Value::Null);
+            return Ok(changes);
+        }
+        Some(Value::Array(list)) => {
+            changes.push(format!(
+                "Converted a list of {} expenses to a map",
+                list.len()
+            ));
+            list.into_iter().map(|expense| (None, expense)).collect()
+        }
+        Some(Value::Object(map)) => map
+            .into_iter()
+            .map(|(key, expense)| (Some(key), expense))
+            .collect(),
+        Some(other) => return Err(format!("Expenses are not a map: {}", other).into()),
+    };
+
+    let mut expenses = Map::new();
+    for (index, (key, expense)) in entries.into_iter().enumerate() {
+        let id = match expense.get("id").and_then(Value::as_str) {
+            Some(id) => id.to_string(),
+            None => {
+                return Err(
+                    format!("Expense {} has no id", key.unwrap_or(index.to_string())).into(),
+                )
+            }
+        };
+
+        if let Some(key) = key.filter(|key| *key != id) {
+            changes.push(format!(
+                "Moved expense stored under {} to its id {}",
+                key, id
+            ));
+        }
+        if expenses.insert(id.clone(), expense).is_some() {
+            return Err(format!("Two expenses share the id {}", id).into());
+        }
+    }
+
+    data.insert(EXPENSES_SUB_KEY.to_string(), Value::Object(expenses));
+    Ok(changes)
+}
+
+/// Ids used to be the hash of the imported content, which is now the separate fingerprint
+/// checked on import. The ids stay as they are, since transfers, the audit log and SSDI pay
+/// periods refer to them, and each becomes the fingerprint of its expense.
+fn fingerprint_expenses(data: &mut Map<String, Value>) -> Result<Vec<String>, Box<dyn StdError>> {
+    let expenses = match data.get_mut(EXPENSES_SUB_KEY) {
+        Some(Value::Object(expenses)) => expenses,
+        _ => return Ok(Vec::new()),
+    };
+
+    let mut count = 0;
+    for (id, expense) in expenses.iter_mut() {
+        let expense = match expense.as_object_mut() {
+            Some(expense) => expense,
+            None => return Err(format!("Expense {} is not an object", id).into()),
+        };
+        if !expense.contains_key("fingerprint") {
+            expense.insert("fingerprint".to_string(), Value::from(id.clone()));
+            count += 1;
+        }
+    }
+
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+    Ok(vec![format!(
+        "Kept the ids of {} expenses as their import fingerprints",
+        count
+    )])
+}
+
+/// Amounts were `f64`, and converted shekels kept repeating decimals (100 ILS stored as
+/// 28.571428571428573). Rounds every amount to cents. The fingerprints hashed from the
+/// unrounded amount are hashed again from the rounded one, the way an import now computes
+/// them, so re-importing the same statement still finds its rows.
+fn round_expense_amounts(data: &mut Map<String, Value>) -> Result<Vec<String>, Box<dyn StdError>> {
+    let expenses = match data.get_mut(EXPENSES_SUB_KEY) {
+        Some(Value::Object(expenses)) => expenses,
+        _ => return Ok(Vec::new()),
+    };
+
+    // Identity keys (before, after) of the rounded rows with a fingerprint, and how many rows
+    // share each old key, the bound of the occurrence hashed along with it
+    let mut rounded_rows = Vec::new();
+    let mut group_sizes: HashMap<String, usize> = HashMap::new();
+    for (id, expense) in expenses.iter() {
+        let amount = match expense.get("amount").and_then(Value::as_f64) {
+            Some(amount) => amount,
+            None => return Err(format!("Expense {} has no amount", id).into()),
+        };
+        let rounded = Money::from_major(amount, Currency::STORE)
+            .ok_or_else(|| format!("Expense {} has an invalid amount: {}", id, amount))?;
+        if rounded.to_f64() == amount {
+            continue;
+        }
+
+        let description = expense.get("description").and_then(Value::as_str);
+        let date = expense
+            .get("date")
+            .and_then(|date| serde_json::from_value::<NaiveDateTime>(date.clone()).ok());
+        let has_fingerprint = expense
+            .get("fingerprint")
+            .and_then(Value::as_str)
+            .is_some_and(|fingerprint| !fingerprint.is_empty());
+        let keys = match (description, date) {
+            (Some(description), Some(date)) if has_fingerprint => {
+                let old_key = identity_key_of(description, &date, amount);
+                *group_sizes.entry(old_key.clone()).or_insert(0) += 1;
+                Some((old_key, identity_key_of(description, &date, rounded)))
+            }
+            _ => None,
+        };
+        rounded_rows.push((id.clone(), amount, rounded, keys));
+    }
+
+    let mut changes = Vec::new();
+    for (id, amount, rounded, keys) in rounded_rows {
+        let expense = expenses
+            .get_mut(&id)
+            .ok_or_else(|| format!("Expense {} disappeared", id))?;
+        changes.push(format!(
+            "Rounded the amount of {} from {} to {}",
+            id, amount, rounded
+        ));
+        expense["amount"] = Value::from(rounded.to_f64());
+
+        let (old_key, new_key) = match keys {
+            Some(keys) => keys,
+            None => continue,
+        };
+        let fingerprint = expense["fingerprint"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let occurrence = (0..group_sizes[&old_key])
+            .find(|occurrence| hash_identity(&old_key, *occurrence) == fingerprint);
+        if let Some(occurrence) = occurrence {
+            expense["fingerprint"] = Value::from(hash_identity(&new_key, occurrence));
+        }
+    }
+
+    Ok(changes)
+}
+
+/// The audit log was kept in store_data, so every restore or import replaced it. It is
+/// dropped here; `ExpenseStore::migrate_schema` hands the entries over to the audit storage.
+fn remove_audit_log(data: &mut Map<String, Value>) -> Result<Vec<String>, Box<dyn StdError>> {
+    match data.remove(AUDIT_LOG_SUB_KEY) {
+        Some(Value::Array(entries)) if !entries.is_empty() => Ok(vec![format!(
+            "Moved {} audit log entries out of store_data",
+            entries.len()
+        )]),
+        _ => Ok(Vec::new()),
+    }
+}
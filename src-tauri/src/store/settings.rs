@@ -0,0 +1,51 @@
+use crate::model::backup::RetentionPolicy;
+use crate::store::json_file_backend::write_atomically;
+use serde::{Deserialize, Serialize};
+use std::error::Error as StdError;
+use std::fs;
+use std::path::Path;
+
+/// File, next to store.json, holding the settings needed before the store can be opened
+pub static STORE_SETTINGS_FILE: &str = "store_settings.json";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendKind {
+    /// Everything in store.json, rewritten atomically on each commit
+    #[default]
+    Json,
+    /// Embedded SQLite database (expenses.db)
+    Sqlite,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreSettings {
+    /// Backend selected for the next start
+    #[serde(default)]
+    pub backend: BackendKind,
+    /// Backend holding the current data, i.e. the one opened last. When `backend` differs,
+    /// the data is copied into it on the next start. Only JSON existed before this was kept.
+    #[serde(default)]
+    pub active_backend: BackendKind,
+    #[serde(default)]
+    pub backup_retention: RetentionPolicy,
+}
+
+impl StoreSettings {
+    /// Loads the settings, falling back to the defaults when the file does not exist yet
+    pub fn load(path: &Path) -> Result<Self, Box<dyn StdError>> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path)?;
+        let settings = serde_json::from_str(&content)
+            .map_err(|err| format!("Invalid store settings file: {}", err))?;
+        Ok(settings)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn StdError>> {
+        write_atomically(path, serde_json::to_string_pretty(self)?.as_bytes())
+    }
+}
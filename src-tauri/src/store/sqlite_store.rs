@@ -0,0 +1,407 @@
+use crate::model::audit::AuditEntry;
+use crate::model::expense::Expense;
+use crate::store::audit::{AuditRetention, AuditStorage};
+use crate::store::backend::{StorageBackend, EXPENSES_SUB_KEY};
+use chrono::NaiveDateTime;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+/// File name of the database, inside the app data directory
+pub static SQLITE_DB_FILE: &str = "expenses.db";
+
+/// Versioned schema migrations, applied in order.
+/// The schema version is tracked by SQLite's `user_version` pragma (index + 1 of the last
+/// applied migration). Never edit an existing entry, append a new one instead.
+const MIGRATIONS: &[&str] = &[
+    // 1: expenses with indexed tags and import source (used to pair transfers between
+    // accounts), generic key/values for the other store_data sub-keys
+    "CREATE TABLE expenses (
+        id TEXT PRIMARY KEY,
+        description TEXT NOT NULL,
+        amount REAL NOT NULL,
+        date TEXT NOT NULL,
+        source TEXT,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX idx_expenses_date ON expenses(date);
+    CREATE INDEX idx_expenses_source ON expenses(source);
+    CREATE TABLE expense_tags (
+        expense_id TEXT NOT NULL REFERENCES expenses(id) ON DELETE CASCADE,
+        tag TEXT NOT NULL,
+        PRIMARY KEY (expense_id, tag)
+    );
+    CREATE INDEX idx_expense_tags_tag ON expense_tags(tag);
+    CREATE TABLE store_values (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );",
+    // 2: audit log, appended to and never replaced along with store_data
+    "CREATE TABLE audit_log (
+        id INTEGER PRIMARY KEY,
+        timestamp TEXT NOT NULL,
+        bulk INTEGER NOT NULL,
+        entry TEXT NOT NULL
+    );
+    CREATE INDEX idx_audit_log_bulk ON audit_log(bulk, id);",
+];
+
+/// Format of the audit timestamps, which sorts as text in time order
+const AUDIT_TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.6f";
+
+/// Embedded SQLite storage for the expense store.
+///
+/// Each expense is one row (plus one row per tag), so adding, updating or removing an
+/// expense only writes the affected rows instead of re-serializing the whole store.
+/// The full expense JSON is kept in `data` so fields added later round-trip untouched,
+/// the other columns exist for indexing.
+pub struct SqliteStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    /// Opens (or creates) the database at `path` and applies any pending migration
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn StdError>> {
+        let conn = Connection::open(path)?;
+        Self::from_connection(conn)
+    }
+
+    /// Opens a private in-memory database, mostly useful for tests
+    pub fn open_in_memory() -> Result<Self, Box<dyn StdError>> {
+        let conn = Connection::open_in_memory()?;
+        Self::from_connection(conn)
+    }
+
+    fn from_connection(mut conn: Connection) -> Result<Self, Box<dyn StdError>> {
+        conn.execute_batch("PRAGMA foreign_keys = ON; PRAGMA journal_mode = WAL;")?;
+        Self::run_migrations(&mut conn)?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn run_migrations(conn: &mut Connection) -> Result<(), Box<dyn StdError>> {
+        let current: usize =
+            conn.pragma_query_value(None, "user_version", |row| row.get::<_, i64>(0))? as usize;
+
+        if current > MIGRATIONS.len() {
+            return Err(format!(
+                "Database schema version {} is newer than supported version {}",
+                current,
+                MIGRATIONS.len()
+            )
+            .into());
+        }
+
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(current) {
+            let tx = conn.transaction()?;
+            tx.execute_batch(migration)
+                .map_err(|err| format!("Failed to apply migration {}: {}", index + 1, err))?;
+            tx.pragma_update(None, "user_version", (index + 1) as i64)?;
+            tx.commit()?;
+        }
+
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Connection>, Box<dyn StdError>> {
+        self.conn
+            .lock()
+            .map_err(|_| "Failed to acquire database lock".into())
+    }
+
+    /// Returns the schema version of the opened database
+    pub fn schema_version(&self) -> Result<usize, Box<dyn StdError>> {
+        let conn = self.lock()?;
+        let version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        Ok(version as usize)
+    }
+
+    fn write_expense(tx: &Transaction, expense: &Expense) -> Result<(), Box<dyn StdError>> {
+        let data = serde_json::to_string(expense)
+            .map_err(|err| format!("Failed to serialize expense: {}", err))?;
+
+        tx.execute(
+            "INSERT OR REPLACE INTO expenses (id, description, amount, date, source, data)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                expense.get_id(),
+                expense.get_description(),
+                expense.get_amount().to_f64(),
+                expense.get_date().format("%Y-%m-%dT%H:%M:%S").to_string(),
+                expense.get_source(),
+                data
+            ],
+        )?;
+
+        tx.execute(
+            "DELETE FROM expense_tags WHERE expense_id = ?1",
+            params![expense.get_id()],
+        )?;
+        for tag in expense.get_tags() {
+            tx.execute(
+                "INSERT OR IGNORE INTO expense_tags (expense_id, tag) VALUES (?1, ?2)",
+                params![expense.get_id(), tag],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes one store_data sub-key; "expenses" replaces all expense rows
+    fn write_value(tx: &Transaction, key: &str, value: &Value) -> Result<(), Box<dyn StdError>> {
+        if key == EXPENSES_SUB_KEY {
+            let expenses: HashMap<String, Expense> = serde_json::from_value(value.clone())
+                .map_err(|err| format!("Failed to deserialize expenses: {}", err))?;
+
+            tx.execute("DELETE FROM expenses", [])?;
+            for expense in expenses.values() {
+                Self::write_expense(tx, expense)?;
+            }
+            return Ok(());
+        }
+
+        tx.execute(
+            "INSERT OR REPLACE INTO store_values (key, value) VALUES (?1, ?2)",
+            params![key, value.to_string()],
+        )?;
+        Ok(())
+    }
+
+    fn load_expense_rows(&self) -> Result<HashMap<String, Expense>, Box<dyn StdError>> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare_cached("SELECT data FROM expenses")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+        let mut data = HashMap::new();
+        for row in rows {
+            let expense: Expense = serde_json::from_str(&row?)
+                .map_err(|err| format!("Failed to deserialize expense: {}", err))?;
+            data.insert(expense.get_id().to_string(), expense);
+        }
+
+        Ok(data)
+    }
+
+    /// Writes the given expense rows, deletes the removed ones and sets the given
+    /// store_data sub-keys, all in a single SQLite transaction.
+    pub fn apply_changes(
+        &self,
+        upserted: &[&Expense],
+        removed_ids: &[String],
+        values: &[(&str, Value)],
+    ) -> Result<(), Box<dyn StdError>> {
+        let mut conn = self.lock()?;
+        let tx = conn.transaction()?;
+
+        for id in removed_ids {
+            tx.execute("DELETE FROM expenses WHERE id = ?1", params![id])?;
+        }
+        for expense in upserted {
+            Self::write_expense(&tx, expense)?;
+        }
+        for (key, value) in values {
+            Self::write_value(&tx, key, value)?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Assembles the same object the JSON backend keeps under "store_data"
+    pub fn get_all_data(&self) -> Result<Value, Box<dyn StdError>> {
+        let mut data = Map::new();
+
+        {
+            let conn = self.lock()?;
+            let mut stmt = conn.prepare_cached("SELECT key, value FROM store_values")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?;
+
+            for row in rows {
+                let (key, raw) = row?;
+                data.insert(key, serde_json::from_str(&raw)?);
+            }
+        }
+
+        let expenses = serde_json::to_value(self.load_expense_rows()?)
+            .map_err(|err| format!("Failed to serialize expenses: {}", err))?;
+        data.insert(EXPENSES_SUB_KEY.to_string(), expenses);
+
+        Ok(Value::Object(data))
+    }
+
+    fn insert_audit_entries(
+        tx: &Transaction,
+        entries: &[AuditEntry],
+    ) -> Result<(), Box<dyn StdError>> {
+        for entry in entries {
+            let data = serde_json::to_string(entry)
+                .map_err(|err| format!("Failed to serialize audit entry: {}", err))?;
+            tx.execute(
+                "INSERT INTO audit_log (id, timestamp, bulk, entry) VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    entry.id as i64,
+                    entry.timestamp.format(AUDIT_TIMESTAMP_FORMAT).to_string(),
+                    entry.bulk,
+                    data
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Replaces every expense row and store_data sub-key with the content of `store_data`.
+    /// The audit log is left as it is.
+    pub fn replace_all_data(&self, store_data: &Value) -> Result<(), Box<dyn StdError>> {
+        let mut conn = self.lock()?;
+        let tx = conn.transaction()?;
+
+        tx.execute("DELETE FROM expenses", [])?;
+        tx.execute("DELETE FROM store_values", [])?;
+        if let Some(obj) = store_data.as_object() {
+            for (key, value) in obj {
+                Self::write_value(&tx, key, value)?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+impl StorageBackend for SqliteStore {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    fn read_data(&self) -> Result<Value, Box<dyn StdError>> {
+        self.get_all_data()
+    }
+
+    fn write_data(&self, data: &Value) -> Result<(), Box<dyn StdError>> {
+        self.replace_all_data(data)
+    }
+
+    fn load_expenses(&self) -> Result<Option<HashMap<String, Expense>>, Box<dyn StdError>> {
+        Ok(Some(self.load_expense_rows()?))
+    }
+
+    fn get_value(&self, key: &str) -> Result<Option<Value>, Box<dyn StdError>> {
+        if key == EXPENSES_SUB_KEY {
+            let expenses = self.load_expense_rows()?;
+            let value = serde_json::to_value(expenses)
+                .map_err(|err| format!("Failed to serialize expenses: {}", err))?;
+            return Ok(Some(value));
+        }
+
+        let conn = self.lock()?;
+        let raw: Option<String> = conn
+            .query_row(
+                "SELECT value FROM store_values WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        match raw {
+            Some(raw) => Ok(Some(serde_json::from_str(&raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn set_value(&self, key: &str, value: Value) -> Result<(), Box<dyn StdError>> {
+        self.apply_changes(&[], &[], &[(key, value)])
+    }
+
+    /// Only writes the changed rows and deletes the removed ones, in a single transaction
+    fn commit_changes(
+        &self,
+        upserted: &[&Expense],
+        removed_ids: &[String],
+        values: Vec<(&str, Value)>,
+    ) -> Result<(), Box<dyn StdError>> {
+        self.apply_changes(upserted, removed_ids, &values)
+    }
+
+    fn commit_values(&self, values: Vec<(&str, Value)>) -> Result<(), Box<dyn StdError>> {
+        self.apply_changes(&[], &[], &values)
+    }
+
+    fn audit_log(&self) -> Option<&dyn AuditStorage> {
+        Some(self)
+    }
+}
+
+/// The audit log is a table of its own, bounded with a few deletes after each append
+impl AuditStorage for SqliteStore {
+    fn read_entries(&self) -> Result<Vec<AuditEntry>, Box<dyn StdError>> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare_cached("SELECT entry FROM audit_log ORDER BY id")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            let entry: AuditEntry = serde_json::from_str(&row?)
+                .map_err(|err| format!("Failed to deserialize audit entry: {}", err))?;
+            entries.push(entry);
+        }
+
+        Ok(entries)
+    }
+
+    fn append_entries(
+        &self,
+        mut entries: Vec<AuditEntry>,
+        now: NaiveDateTime,
+        retention: &AuditRetention,
+    ) -> Result<(), Box<dyn StdError>> {
+        let mut conn = self.lock()?;
+        let tx = conn.transaction()?;
+
+        let last_id: i64 =
+            tx.query_row("SELECT COALESCE(MAX(id), 0) FROM audit_log", [], |row| {
+                row.get(0)
+            })?;
+        for (entry, id) in entries.iter_mut().zip(last_id as u64 + 1..) {
+            entry.id = id;
+        }
+        Self::insert_audit_entries(&tx, &entries)?;
+
+        let oldest = now - retention.max_age;
+        tx.execute(
+            "DELETE FROM audit_log WHERE timestamp < ?1",
+            params![oldest.format(AUDIT_TIMESTAMP_FORMAT).to_string()],
+        )?;
+        for (bulk, max_entries) in [
+            (false, retention.max_entries),
+            (true, retention.max_bulk_entries),
+        ] {
+            tx.execute(
+                "DELETE FROM audit_log WHERE bulk = ?1 AND id <= (
+                    SELECT id FROM audit_log WHERE bulk = ?1 ORDER BY id DESC LIMIT 1 OFFSET ?2
+                )",
+                params![bulk, max_entries as i64],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn replace_entries(&self, entries: Vec<AuditEntry>) -> Result<(), Box<dyn StdError>> {
+        let mut conn = self.lock()?;
+        let tx = conn.transaction()?;
+
+        tx.execute("DELETE FROM audit_log", [])?;
+        Self::insert_audit_entries(&tx, &entries)?;
+
+        tx.commit()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use crate::store::app_store::STORE_DATA_KEY;
+use crate::store::backend::StorageBackend;
+use serde_json::Value;
+use std::error::Error as StdError;
+use std::sync::Arc;
+use tauri::Wry;
+use tauri_plugin_store::Store;
+
+/// Keeps store_data in store.json through tauri-plugin-store, rewritten on every save
+pub struct TauriStoreBackend {
+    store: Arc<Store<Wry>>,
+}
+
+impl TauriStoreBackend {
+    pub fn new(store: Arc<Store<Wry>>) -> Self {
+        Self { store }
+    }
+}
+
+impl StorageBackend for TauriStoreBackend {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn read_data(&self) -> Result<Value, Box<dyn StdError>> {
+        Ok(self.store.get(STORE_DATA_KEY).unwrap_or(Value::Null))
+    }
+
+    fn write_data(&self, data: &Value) -> Result<(), Box<dyn StdError>> {
+        self.store.set(STORE_DATA_KEY, data.clone());
+        self.store.save()?;
+        Ok(())
+    }
+}
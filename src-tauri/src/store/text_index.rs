@@ -0,0 +1,228 @@
+use crate::service::duplicate_service::levenshtein;
+use std::collections::{BTreeMap, HashMap};
+
+// Weight of a query token matching a term exactly, as a prefix, or within the edit distance
+const EXACT_WEIGHT: f64 = 1.0;
+const PREFIX_WEIGHT: f64 = 0.7;
+const FUZZY_WEIGHT: f64 = 0.4;
+
+// BM25 term-frequency saturation and length normalization
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+
+// Hebrew prefix letters (ו ה ב ל מ ש כ) that attach to the following word
+const HEBREW_PREFIXES: [char; 7] = ['ו', 'ה', 'ב', 'ל', 'מ', 'ש', 'כ'];
+
+/// Maps Hebrew final letters to their regular form, so "שלום" and "שלומ" index the same
+fn normalize_final_letter(c: char) -> char {
+    match c {
+        'ך' => 'כ',
+        'ם' => 'מ',
+        'ן' => 'נ',
+        'ף' => 'פ',
+        'ץ' => 'צ',
+        _ => c,
+    }
+}
+
+/// Hebrew vowel points and cantillation marks, which descriptions rarely agree on
+fn is_hebrew_mark(c: char) -> bool {
+    matches!(c, '\u{0591}'..='\u{05BD}' | '\u{05BF}' | '\u{05C1}'..='\u{05C2}' | '\u{05C4}'..='\u{05C5}' | '\u{05C7}')
+}
+
+/// Apostrophes and Hebrew geresh/gershayim are dropped inside words ("Mario's", "צה״ל")
+fn is_joiner(c: char) -> bool {
+    matches!(c, '\'' | '’' | '"' | '׳' | '״')
+}
+
+/// Splits a text into normalized search tokens.
+///
+/// Tokens are lowercased, stripped of Hebrew vowel points and apostrophes/gershayim,
+/// and Hebrew final letters are mapped to their regular form.
+pub fn tokenize_text(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+
+    for c in text.chars() {
+        if is_hebrew_mark(c) || is_joiner(c) {
+            continue;
+        }
+
+        if c.is_alphanumeric() {
+            current.extend(c.to_lowercase().map(normalize_final_letter));
+        } else if !current.is_empty() {
+            tokens.push(std::mem::take(&mut current));
+        }
+    }
+
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+/// The terms indexed for a description: its tokens, plus each Hebrew token without its
+/// attached prefix letter so "ברמי" is also found by "רמי"
+fn index_terms(description: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+
+    for token in tokenize_text(description) {
+        let mut chars = token.chars();
+        if let Some(first) = chars.next() {
+            if HEBREW_PREFIXES.contains(&first) && token.chars().count() >= 4 {
+                terms.push(chars.as_str().to_string());
+            }
+        }
+        terms.push(token);
+    }
+
+    terms
+}
+
+fn max_edit_distance(token: &str) -> usize {
+    match token.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Inverted index over expense descriptions, supporting exact, prefix and fuzzy matching
+/// with BM25 ranking. Kept in sync one expense at a time by the expense cache.
+#[derive(Clone, Debug, Default)]
+pub struct TextIndex {
+    // term -> expense id -> occurrences of the term in the description
+    postings: BTreeMap<String, HashMap<String, u32>>,
+    // expense id -> number of indexed terms
+    lengths: HashMap<String, usize>,
+    total_length: usize,
+}
+
+impl TextIndex {
+    pub fn add(&mut self, id: &str, description: &str) {
+        let terms = index_terms(description);
+
+        self.total_length += terms.len();
+        self.lengths.insert(id.to_string(), terms.len());
+        for term in terms {
+            *self
+                .postings
+                .entry(term)
+                .or_default()
+                .entry(id.to_string())
+                .or_insert(0) += 1;
+        }
+    }
+
+    /// Removes an expense, given the description it was indexed with
+    pub fn remove(&mut self, id: &str, description: &str) {
+        if let Some(length) = self.lengths.remove(id) {
+            self.total_length -= length;
+        }
+
+        for term in index_terms(description) {
+            if let Some(documents) = self.postings.get_mut(&term) {
+                documents.remove(id);
+                if documents.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+    }
+
+    /// Terms matching a query token, with the weight of the kind of match
+    fn matching_terms(&self, token: &str) -> Vec<(&String, f64)> {
+        let mut matches: HashMap<&String, f64> = HashMap::new();
+
+        for (term, _) in self
+            .postings
+            .range(token.to_string()..)
+            .take_while(|(term, _)| term.starts_with(token))
+        {
+            let weight = if term == token {
+                EXACT_WEIGHT
+            } else {
+                PREFIX_WEIGHT
+            };
+            matches.insert(term, weight);
+        }
+
+        let max_distance = max_edit_distance(token);
+        if max_distance > 0 {
+            let token_length = token.chars().count();
+            for term in self.postings.keys() {
+                if matches.contains_key(term)
+                    || term.chars().count().abs_diff(token_length) > max_distance
+                {
+                    continue;
+                }
+                if levenshtein(term, token) <= max_distance {
+                    matches.insert(term, FUZZY_WEIGHT);
+                }
+            }
+        }
+
+        matches.into_iter().collect()
+    }
+
+    /// Searches the indexed descriptions. Every query token must match (exactly, as a
+    /// prefix of a word, or within a small edit distance); results are ranked by BM25,
+    /// exact matches counting more than prefix and fuzzy ones.
+    ///
+    /// Returns:
+    /// - `Vec<(String, f64)>`: Expense ids with their score, best first.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<(String, f64)> {
+        let tokens = tokenize_text(query);
+        if tokens.is_empty() || self.lengths.is_empty() {
+            return Vec::new();
+        }
+
+        let document_count = self.lengths.len() as f64;
+        let average_length = (self.total_length as f64 / document_count).max(1.0);
+        let mut scores: HashMap<&str, f64> = HashMap::new();
+
+        for (index, token) in tokens.iter().enumerate() {
+            // Best contribution of this token for each expense
+            let mut token_scores: HashMap<&str, f64> = HashMap::new();
+
+            for (term, weight) in self.matching_terms(token) {
+                let documents = &self.postings[term];
+                let frequency = documents.len() as f64;
+                let idf = ((document_count - frequency + 0.5) / (frequency + 0.5) + 1.0).ln();
+
+                for (id, count) in documents {
+                    let length = self.lengths.get(id).copied().unwrap_or(0) as f64;
+                    let tf = *count as f64;
+                    let saturation = tf * (BM25_K1 + 1.0)
+                        / (tf + BM25_K1 * (1.0 - BM25_B + BM25_B * length / average_length));
+                    let score = weight * idf * saturation;
+
+                    let best = token_scores.entry(id.as_str()).or_insert(0.0);
+                    *best = best.max(score);
+                }
+            }
+
+            if index == 0 {
+                scores = token_scores;
+            } else {
+                scores.retain(|id, _| token_scores.contains_key(id));
+                for (id, score) in scores.iter_mut() {
+                    *score += token_scores[id];
+                }
+            }
+
+            if scores.is_empty() {
+                return Vec::new();
+            }
+        }
+
+        let mut results: Vec<(String, f64)> = scores
+            .into_iter()
+            .map(|(id, score)| (id.to_string(), score))
+            .collect();
+        results.sort_by(|(id_a, a), (id_b, b)| b.total_cmp(a).then_with(|| id_a.cmp(id_b)));
+        results.truncate(limit);
+        results
+    }
+}
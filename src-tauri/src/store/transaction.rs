@@ -0,0 +1,260 @@
+use crate::model::expense::Expense;
+use crate::store::backend::{StorageBackend, EXPENSES_SUB_KEY};
+use crate::store::expense_cache::ExpenseCache;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error as StdError;
+use std::fmt;
+
+/// Returned when a write was based on an outdated copy of some expenses,
+/// i.e. another command changed them after the caller read them.
+#[derive(Debug, Clone)]
+pub struct ConflictError {
+    pub ids: Vec<String>,
+}
+
+impl fmt::Display for ConflictError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The following expenses were changed by another operation, reload them and retry: {}",
+            self.ids.join(", ")
+        )
+    }
+}
+
+impl StdError for ConflictError {}
+
+/// (key, before, after) of a store_data sub-key set by a transaction
+pub type ValueChange = (String, Option<Value>, Option<Value>);
+
+/// Working copy of the store for one multi-step mutation.
+///
+/// Every change is staged here; `ExpenseStore::transaction` commits all of them in a
+/// single atomic write once the mutation succeeds, or drops them if it fails, so the
+/// store never ends up half-updated. Only the touched expenses are copied, every other
+/// one is read from the committed cache.
+pub struct StoreTransaction<'a> {
+    backend: &'a dyn StorageBackend,
+    base: &'a ExpenseCache,
+    // Expenses put (Some) or removed (None) by the transaction
+    staged: BTreeMap<String, Option<Expense>>,
+    stored: bool,
+    values: BTreeMap<String, Value>,
+    // Version of each sub-key set through `set_value` when the transaction started
+    value_originals: BTreeMap<String, Option<Value>>,
+    // The expenses key was overwritten as a whole (e.g. by an import), `staged` then holds
+    // every expense and `base` is no longer read
+    expenses_replaced: bool,
+}
+
+impl<'a> StoreTransaction<'a> {
+    pub fn new(backend: &'a dyn StorageBackend, base: &'a ExpenseCache) -> Self {
+        Self {
+            backend,
+            base,
+            staged: BTreeMap::new(),
+            stored: base.is_stored(),
+            values: BTreeMap::new(),
+            value_originals: BTreeMap::new(),
+            expenses_replaced: false,
+        }
+    }
+
+    /// False when the store had no expenses key when the transaction started
+    pub fn is_stored(&self) -> bool {
+        self.stored
+    }
+
+    /// Every expense as staged in this transaction, in no particular order
+    pub fn expenses(&self) -> impl Iterator<Item = &Expense> + '_ {
+        let unchanged = (!self.expenses_replaced)
+            .then(|| {
+                self.base
+                    .values()
+                    .filter(|expense| !self.staged.contains_key(expense.get_id()))
+            })
+            .into_iter()
+            .flatten();
+
+        unchanged.chain(self.staged.values().flatten())
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Expense> {
+        match self.staged.get(id) {
+            Some(staged) => staged.as_ref(),
+            None if self.expenses_replaced => None,
+            None => self.base.get(id),
+        }
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.get(id).is_some()
+    }
+
+    /// Inserts or replaces an expense, under its own id.
+    /// A replaced expense gets the next revision of the stored one.
+    pub fn upsert(&mut self, mut expense: Expense) {
+        let id = expense.get_id().to_string();
+        if let Some(stored) = self.get(&id) {
+            expense.set_revision(stored.get_revision() + 1);
+        }
+        self.staged.insert(id, Some(expense));
+        self.stored = true;
+    }
+
+    /// Applies `f` to an existing expense and bumps its revision,
+    /// returning false if it does not exist
+    pub fn update(&mut self, id: &str, f: impl FnOnce(&mut Expense)) -> bool {
+        let mut expense = match self.get(id) {
+            Some(expense) => expense.clone(),
+            None => return false,
+        };
+
+        let revision = expense.get_revision();
+        f(&mut expense);
+        expense.set_revision(revision + 1);
+        self.staged.insert(id.to_string(), Some(expense));
+        true
+    }
+
+    pub fn remove(&mut self, id: &str) -> Option<Expense> {
+        let expense = self.get(id)?.clone();
+        self.staged.insert(id.to_string(), None);
+        Some(expense)
+    }
+
+    fn expenses_value<'e>(
+        expenses: impl Iterator<Item = &'e Expense>,
+    ) -> Result<Value, Box<dyn StdError>> {
+        let map: HashMap<&str, &Expense> = expenses
+            .map(|expense| (expense.get_id(), expense))
+            .collect();
+        Ok(serde_json::to_value(map)
+            .map_err(|err| format!("Failed to serialize expenses: {}", err))?)
+    }
+
+    /// Reads a store_data sub-key, as staged in this transaction or else as stored
+    pub fn get_value(&self, key: &str) -> Result<Option<Value>, Box<dyn StdError>> {
+        if key == EXPENSES_SUB_KEY {
+            let value = Self::expenses_value(self.expenses())?;
+            return Ok(self.stored.then_some(value));
+        }
+
+        match self.values.get(key) {
+            Some(value) => Ok(Some(value.clone())),
+            None => self.backend.get_value(key),
+        }
+    }
+
+    /// Stages a store_data sub-key. Setting "expenses" replaces the working copy as a whole.
+    pub fn set_value(&mut self, key: &str, value: Value) -> Result<(), Box<dyn StdError>> {
+        if !self.value_originals.contains_key(key) {
+            let original = if key == EXPENSES_SUB_KEY {
+                match self.base.is_stored() {
+                    true => Some(Self::expenses_value(self.base.values())?),
+                    false => None,
+                }
+            } else {
+                self.backend.get_value(key)?
+            };
+            self.value_originals.insert(key.to_string(), original);
+        }
+
+        if key == EXPENSES_SUB_KEY {
+            let expenses: HashMap<String, Expense> = serde_json::from_value(value)
+                .map_err(|err| format!("Failed to deserialize expenses: {}", err))?;
+
+            self.staged = expenses
+                .into_iter()
+                .map(|(id, expense)| (id, Some(expense)))
+                .collect();
+            self.stored = true;
+            self.expenses_replaced = true;
+            return Ok(());
+        }
+
+        self.values.insert(key.to_string(), value);
+        Ok(())
+    }
+
+    pub fn has_changes(&self) -> bool {
+        self.expenses_replaced || !self.staged.is_empty() || !self.values.is_empty()
+    }
+
+    pub fn expenses_replaced(&self) -> bool {
+        self.expenses_replaced
+    }
+
+    pub fn changed_ids(&self) -> Vec<String> {
+        self.staged
+            .iter()
+            .filter(|(_, expense)| expense.is_some())
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    pub fn removed_ids(&self) -> Vec<String> {
+        self.staged
+            .iter()
+            .filter(|(_, expense)| expense.is_none())
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// (id, before, after) of every expense created, changed or removed by the transaction.
+    /// Empty when the expenses were replaced as a whole.
+    pub fn expense_changes(&self) -> Vec<(String, Option<Expense>, Option<Expense>)> {
+        if self.expenses_replaced {
+            return Vec::new();
+        }
+
+        self.staged
+            .iter()
+            .filter_map(|(id, after)| {
+                let before = self.base.get(id).cloned();
+                (before.is_some() || after.is_some()).then(|| (id.clone(), before, after.clone()))
+            })
+            .collect()
+    }
+
+    /// Every sub-key set by the transaction and actually changed
+    pub fn value_changes(&self) -> Result<Vec<ValueChange>, Box<dyn StdError>> {
+        let mut changes = Vec::new();
+        for (key, before) in self.value_originals.iter() {
+            let after = self.get_value(key)?;
+            if *before != after {
+                changes.push((key.clone(), before.clone(), after));
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Writes every staged change to the backend in a single commit
+    pub fn commit(&self) -> Result<(), Box<dyn StdError>> {
+        let mut values: Vec<(&str, Value)> = self
+            .values
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.clone()))
+            .collect();
+
+        if self.expenses_replaced {
+            values.push((EXPENSES_SUB_KEY, Self::expenses_value(self.expenses())?));
+            return self.backend.commit_values(values);
+        }
+
+        // Only other sub-keys changed, leave the expenses untouched
+        if self.staged.is_empty() {
+            return self.backend.commit_values(values);
+        }
+
+        let upserted: Vec<&Expense> = self.staged.values().flatten().collect();
+        self.backend
+            .commit_changes(&upserted, &self.removed_ids(), values)
+    }
+
+    /// The staged expenses, to apply to the cache once committed
+    pub fn into_staged(self) -> BTreeMap<String, Option<Expense>> {
+        self.staged
+    }
+}
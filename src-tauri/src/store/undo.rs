@@ -0,0 +1,162 @@
+use crate::config::DEFAULT_UNDO_LIMIT;
+use crate::model::expense::Expense;
+use crate::store::backend::EXPENSES_SUB_KEY;
+use crate::store::transaction::ValueChange;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Commands whose changes can be undone
+pub const UNDOABLE_COMMANDS: &[&str] = &[
+    "update_expense",
+    "update_bulk_expenses",
+    "remove_expense",
+    "remove_bulk_expenses",
+    "add_expense_manual",
+    "store_set_json_value",
+    "rename_tag",
+    "merge_tags",
+    "delete_tag",
+];
+
+/// Every change made by one command invocation, as (before, after) per expense and sub-key
+#[derive(Clone, Debug)]
+pub struct UndoGroup {
+    pub invocation: u64,
+    pub command: String,
+    pub expenses: BTreeMap<String, (Option<Expense>, Option<Expense>)>,
+    pub values: BTreeMap<String, (Option<Value>, Option<Value>)>,
+}
+
+impl UndoGroup {
+    pub fn new(invocation: u64, command: &str) -> Self {
+        Self {
+            invocation,
+            command: command.to_string(),
+            expenses: BTreeMap::new(),
+            values: BTreeMap::new(),
+        }
+    }
+
+    /// Adds the changes of one more transaction, keeping the first `before` and the
+    /// last `after` of anything changed twice
+    pub fn merge(
+        &mut self,
+        expense_changes: Vec<(String, Option<Expense>, Option<Expense>)>,
+        value_changes: Vec<ValueChange>,
+    ) {
+        for (id, before, after) in expense_changes {
+            self.expenses
+                .entry(id)
+                .and_modify(|change| change.1 = after.clone())
+                .or_insert((before, after));
+        }
+        for (key, before, after) in value_changes {
+            self.values
+                .entry(key)
+                .and_modify(|change| change.1 = after.clone())
+                .or_insert((before, after));
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.expenses.is_empty() && self.values.is_empty()
+    }
+
+    /// store_data sub-keys the group changes
+    pub fn keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.values.keys().cloned().collect();
+        if !self.expenses.is_empty() && !keys.iter().any(|key| key == EXPENSES_SUB_KEY) {
+            keys.push(EXPENSES_SUB_KEY.to_string());
+        }
+        keys
+    }
+}
+
+/// Undo and redo stacks of the store, kept in memory for the session
+pub struct UndoHistory {
+    undo: Vec<UndoGroup>,
+    redo: Vec<UndoGroup>,
+    limit: usize,
+}
+
+impl Default for UndoHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_UNDO_LIMIT)
+    }
+}
+
+impl UndoHistory {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            limit,
+        }
+    }
+
+    /// Records a new change: merged into the last step when made by the same invocation.
+    /// Anything that could be redone is dropped.
+    pub fn record(&mut self, group: UndoGroup) {
+        if group.is_empty() {
+            return;
+        }
+
+        self.redo.clear();
+        match self.undo.last_mut() {
+            Some(last) if last.invocation == group.invocation => {
+                let UndoGroup {
+                    expenses, values, ..
+                } = group;
+                last.merge(
+                    expenses
+                        .into_iter()
+                        .map(|(id, (before, after))| (id, before, after))
+                        .collect(),
+                    values
+                        .into_iter()
+                        .map(|(key, (before, after))| (key, before, after))
+                        .collect(),
+                );
+            }
+            _ => self.push_undo(group),
+        }
+    }
+
+    /// Called for changes that cannot be undone, which make the redo stack meaningless
+    pub fn clear_redo(&mut self) {
+        self.redo.clear();
+    }
+
+    /// Drops every step, once the data they apply to was replaced as a whole
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    pub fn push_undo(&mut self, group: UndoGroup) {
+        self.undo.push(group);
+        if self.undo.len() > self.limit {
+            self.undo.remove(0);
+        }
+    }
+
+    pub fn push_redo(&mut self, group: UndoGroup) {
+        self.redo.push(group);
+    }
+
+    pub fn pop_undo(&mut self) -> Option<UndoGroup> {
+        self.undo.pop()
+    }
+
+    pub fn pop_redo(&mut self) -> Option<UndoGroup> {
+        self.redo.pop()
+    }
+
+    pub fn undo_command(&self) -> Option<String> {
+        self.undo.last().map(|group| group.command.clone())
+    }
+
+    pub fn redo_command(&self) -> Option<String> {
+        self.redo.last().map(|group| group.command.clone())
+    }
+}
@@ -0,0 +1,47 @@
+//! Fixtures shared by the integration tests, pulled in with `mod common;`.
+// Each test file is its own crate and only uses some of them
+#![allow(dead_code)]
+
+use chrono::{NaiveDate, NaiveDateTime};
+use tauri_app_lib::model::expense::Expense;
+
+/// Helper function to build a date of 2025 for test
+///
+/// Returns:
+/// `NaiveDateTime` Midnight of the given day
+pub fn setup_date(month: u32, day: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2025, month, day)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+}
+
+/// Helper function to set up an expense for test
+///
+/// Returns:
+/// `Expense` An expense dated at midnight of the given day of 2025, with the given tags
+pub fn setup_expense(
+    description: &str,
+    amount: f64,
+    month: u32,
+    day: u32,
+    tags: &[&str],
+) -> Expense {
+    let mut expense = Expense::new(description.to_string(), amount, setup_date(month, day));
+    for tag in tags {
+        expense.add_tag(tag);
+    }
+    expense
+}
+
+/// Helper function to give an expense the id and import source it is stored with
+///
+/// Returns:
+/// `Expense` The expense with the given id, and source when given
+pub fn as_stored(mut expense: Expense, id: &str, source: Option<&str>) -> Expense {
+    expense.set_id(id);
+    if let Some(source) = source {
+        expense.set_source(source);
+    }
+    expense
+}
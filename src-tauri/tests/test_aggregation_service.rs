@@ -0,0 +1,104 @@
+use chrono::NaiveDate;
+use tauri_app_lib::model::aggregation::{ExpenseKind, Mode};
+use tauri_app_lib::model::expense::Expense;
+use tauri_app_lib::model::money::Money;
+use tauri_app_lib::service::aggregation_service::{aggregate, bucket_key, bucket_start};
+
+mod common;
+use common::setup_expense;
+
+#[test]
+fn test_aggregate_monthly_totals_and_median() {
+    // Setup
+    let expenses = vec![
+        setup_expense("Test", 10.0, 1, 3, &["Food"]),
+        setup_expense("Test", 30.0, 1, 20, &["Food"]),
+        setup_expense("Test", 200.0, 1, 28, &["Rent"]),
+        setup_expense("Test", 15.0, 2, 1, &["Food"]),
+    ];
+
+    // Invoke
+    let result = aggregate(&expenses, Mode::Monthly, false).unwrap();
+
+    // Analysis
+    assert_eq!(result.len(), 2);
+    assert_eq!(result[0].key, "2025-01");
+    assert_eq!(result[0].total, Money::from(240.0));
+    assert_eq!(result[0].count, 3);
+    assert_eq!(result[0].average, Money::from(80.0));
+    assert_eq!(result[0].median, Money::from(30.0));
+    assert_eq!(result[1].key, "2025-02");
+}
+
+#[test]
+fn test_aggregate_by_tag_with_untagged() {
+    // Setup
+    let expenses = vec![
+        setup_expense("Test", 12.0, 3, 1, &["Food", "Dining"]),
+        setup_expense("Test", 8.0, 3, 2, &[]),
+    ];
+
+    // Invoke
+    let result = aggregate(&expenses, Mode::Yearly, true).unwrap();
+
+    // Analysis
+    let tags: Vec<(&str, f64)> = result
+        .iter()
+        .map(|bucket| (bucket.tag.as_deref().unwrap(), bucket.total.to_f64()))
+        .collect();
+    assert_eq!(
+        tags,
+        vec![("Dining", 12.0), ("Food", 12.0), ("Untagged", 8.0)]
+    );
+}
+
+#[test]
+fn test_weekly_bucket_across_year_boundary() {
+    // Setup
+    let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+
+    // Invoke
+    let start = bucket_start(date, Mode::Weekly);
+    let key = bucket_key(date, Mode::Weekly);
+
+    // Analysis
+    assert_eq!(start, NaiveDate::from_ymd_opt(2024, 12, 30).unwrap());
+    assert_eq!(key, "2025-W01");
+}
+
+#[test]
+fn test_expense_kind_excludes_income_savings_and_transfers() {
+    // Setup
+    let mut transfer = setup_expense("Test", 500.0, 3, 1, &[]);
+    transfer.set_transfer_id(Some("pair".to_string()));
+    let mut savings_transfer = setup_expense("Test", 250.0, 3, 2, &["Savings"]);
+    savings_transfer.set_transfer_id(Some("savings_pair".to_string()));
+    let expenses = [
+        setup_expense("Test", 40.0, 3, 1, &["Food"]),
+        setup_expense("Test", -3000.0, 3, 1, &["Income"]),
+        setup_expense("Test", 250.0, 3, 1, &["Savings"]),
+        transfer,
+        savings_transfer,
+    ];
+
+    // Invoke
+    let spending: Vec<&Expense> = expenses
+        .iter()
+        .filter(|expense| ExpenseKind::Expenses.includes(expense))
+        .collect();
+    let income: Vec<&Expense> = expenses
+        .iter()
+        .filter(|expense| ExpenseKind::Income.includes(expense))
+        .collect();
+    let savings: Vec<&Expense> = expenses
+        .iter()
+        .filter(|expense| ExpenseKind::Savings.includes(expense))
+        .collect();
+
+    // Analysis
+    assert_eq!(spending.len(), 1);
+    assert_eq!(spending[0].get_amount(), Money::from(40.0));
+    assert_eq!(income.len(), 1);
+    assert_eq!(savings.len(), 1);
+    assert!(!savings[0].is_transfer());
+}
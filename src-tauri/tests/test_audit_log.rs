@@ -0,0 +1,291 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use serde_json::Value;
+use tauri_app_lib::model::audit::AuditAction;
+use tauri_app_lib::model::expense::Expense;
+use tauri_app_lib::store::app_store::{ExpenseStore, RESTORE_COMMAND};
+use tauri_app_lib::store::audit::{
+    audit_entries, AuditRetention, AuditStorage, MemoryAuditLog, AUDIT_LOG_SUB_KEY,
+};
+use tauri_app_lib::store::backend::StorageBackend;
+use tauri_app_lib::store::command_context::run_as_command;
+use tauri_app_lib::store::json_file_backend::JsonFileBackend;
+use tauri_app_lib::store::memory_backend::MemoryBackend;
+use tempfile::tempdir;
+
+mod common;
+use common::setup_expense;
+
+/// Helper function to set up a store holding one expense, returned as stored
+fn setup_store_with_expense() -> (ExpenseStore<MemoryBackend>, Expense) {
+    let store = ExpenseStore::new(MemoryBackend::new());
+    store
+        .add_expense_as_batch(vec![setup_expense("Bookstore", 25.0, 5, 2, &[])], false)
+        .unwrap();
+    let expense = store.get_all_expenses().unwrap().remove(0);
+    (store, expense)
+}
+
+fn setup_time(day: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2025, 5, day)
+        .unwrap()
+        .and_hms_opt(12, 0, 0)
+        .unwrap()
+}
+
+#[test]
+fn test_history_records_create_update_delete() {
+    // Setup
+    let (store, expense) = setup_store_with_expense();
+    let id = expense.get_id().to_string();
+    let mut edited = expense.clone();
+    edited.add_tag("Books");
+
+    // Invoke
+    run_as_command("update_expense", || {
+        store.update_expense(id.clone(), edited).unwrap();
+    });
+    store.remove_expense(&id).unwrap();
+
+    // Analysis
+    let history = store.get_expense_history(&id).unwrap();
+    let actions: Vec<AuditAction> = history.iter().map(|entry| entry.action).collect();
+    assert_eq!(
+        actions,
+        vec![
+            AuditAction::Delete,
+            AuditAction::Update,
+            AuditAction::Create
+        ]
+    );
+    assert_eq!(history[1].command, "update_expense");
+    assert!(history[1].before.as_ref().unwrap().get_tags().is_empty());
+    assert_eq!(
+        history[1].after.as_ref().unwrap().get_tags(),
+        &vec!["Books"]
+    );
+    assert!(history[0].after.is_none());
+}
+
+#[test]
+fn test_revert_restores_prior_version() {
+    // Setup
+    let (store, expense) = setup_store_with_expense();
+    let id = expense.get_id().to_string();
+    let created_entry = store.get_expense_history(&id).unwrap()[0].id;
+
+    let mut edited = expense.clone();
+    edited.add_tag("Wrong");
+    store.update_expense(id.clone(), edited).unwrap();
+
+    // Invoke
+    let reverted = store.revert_expense(&id, created_entry).unwrap().unwrap();
+
+    // Analysis
+    assert!(reverted.get_tags().is_empty());
+    assert_eq!(reverted.get_revision(), 2);
+    assert_eq!(store.get_expense_history(&id).unwrap().len(), 3);
+}
+
+#[test]
+fn test_revert_recreates_deleted_expense() {
+    // Setup
+    let (store, expense) = setup_store_with_expense();
+    let id = expense.get_id().to_string();
+    let created_entry = store.get_expense_history(&id).unwrap()[0].id;
+    store.remove_expense(&id).unwrap();
+
+    // Invoke
+    store.revert_expense(&id, created_entry).unwrap();
+
+    // Analysis
+    let restored = store.get_expense(&id).unwrap().unwrap();
+    assert_eq!(restored.get_description(), "Bookstore");
+}
+
+#[test]
+fn test_revert_unknown_entry_fails() {
+    // Setup
+    let (store, expense) = setup_store_with_expense();
+
+    // Invoke
+    let result = store.revert_expense(expense.get_id(), 999);
+
+    // Analysis
+    assert!(result.is_err());
+}
+
+fn setup_changes(
+    prefix: &str,
+    count: usize,
+    day: u32,
+) -> Vec<(String, Option<Expense>, Option<Expense>)> {
+    (0..count)
+        .map(|i| {
+            let expense = setup_expense(&format!("{} {}", prefix, i), 1.0, 5, day, &[]);
+            (format!("{}-{}", prefix, i), None, Some(expense))
+        })
+        .collect()
+}
+
+#[test]
+fn test_retention_bounds_regular_and_bulk_entries_apart() {
+    // Setup
+    let retention = AuditRetention {
+        max_entries: 2,
+        max_bulk_entries: 2,
+        bulk_commit_size: 3,
+        max_age: Duration::days(10),
+    };
+    let log = MemoryAuditLog::default();
+    for (prefix, count, day) in [("old", 1, 1), ("manual", 2, 20)] {
+        let entries = audit_entries(
+            setup_changes(prefix, count, day),
+            "add_expense_manual",
+            setup_time(day),
+            &retention,
+        );
+        log.append_entries(entries, setup_time(day), &retention)
+            .unwrap();
+    }
+
+    // Invoke
+    let imported = audit_entries(
+        setup_changes("import", 4, 20),
+        "parse_csv_from_path",
+        setup_time(20),
+        &retention,
+    );
+    log.append_entries(imported, setup_time(20), &retention)
+        .unwrap();
+
+    // Analysis
+    let entries = log.read_entries().unwrap();
+    let ids: Vec<&str> = entries
+        .iter()
+        .map(|entry| entry.expense_id.as_str())
+        .collect();
+    assert_eq!(ids, vec!["manual-0", "manual-1", "import-2", "import-3"]);
+    assert!(!entries[0].bulk);
+    assert!(entries[3].bulk);
+    assert_eq!(entries.last().unwrap().id, 7);
+}
+
+#[test]
+fn test_bulk_import_keeps_manual_history() {
+    // Setup
+    let retention = AuditRetention {
+        max_entries: 5,
+        max_bulk_entries: 10,
+        bulk_commit_size: 3,
+        ..AuditRetention::default()
+    };
+    let store = ExpenseStore::new(MemoryBackend::new()).with_audit_retention(retention);
+    store
+        .add_expense_as_batch(vec![setup_expense("Bookstore", 25.0, 5, 2, &[])], false)
+        .unwrap();
+    let id = store.get_all_expenses().unwrap()[0].get_id().to_string();
+
+    // Invoke
+    for batch in 0..3 {
+        let imported = (0..20)
+            .map(|i| setup_expense(&format!("Import {} {}", batch, i), 1.0, 5, 3, &[]))
+            .collect();
+        store.add_expense_as_batch(imported, false).unwrap();
+    }
+
+    // Analysis
+    assert_eq!(store.get_expense_history(&id).unwrap().len(), 1);
+    let entries = store.backend().audit_log().unwrap().read_entries().unwrap();
+    assert_eq!(entries.iter().filter(|entry| entry.bulk).count(), 10);
+}
+
+#[test]
+fn test_restoring_data_keeps_history() {
+    // Setup
+    let (store, expense) = setup_store_with_expense();
+    let id = expense.get_id().to_string();
+    let snapshot = store.get_all_store_data().unwrap();
+    let mut edited = expense.clone();
+    edited.add_tag("Books");
+    store.update_expense(id.clone(), edited).unwrap();
+
+    // Invoke
+    store.replace_all_data(snapshot).unwrap();
+
+    // Analysis
+    let history = store.get_expense_history(&id).unwrap();
+    assert_eq!(history.len(), 3);
+    assert_eq!(history[0].command, RESTORE_COMMAND);
+    assert_eq!(
+        history[0].before.as_ref().unwrap().get_tags(),
+        &vec!["Books"]
+    );
+    assert!(history[0].after.as_ref().unwrap().get_tags().is_empty());
+    assert!(store.get_json_value(AUDIT_LOG_SUB_KEY).unwrap().is_none());
+}
+
+#[test]
+fn test_migration_moves_log_out_of_store_data() {
+    // Setup
+    let (old_store, expense) = setup_store_with_expense();
+    let legacy_log = old_store
+        .backend()
+        .audit_log()
+        .unwrap()
+        .read_entries()
+        .unwrap();
+    let mut data = old_store.get_all_store_data().unwrap();
+    data["schema_version"] = Value::from(3);
+    data[AUDIT_LOG_SUB_KEY] = serde_json::to_value(&legacy_log).unwrap();
+    let store = ExpenseStore::new(MemoryBackend::with_data(data));
+
+    // Invoke
+    store.migrate_schema(false).unwrap();
+
+    // Analysis
+    let history = store.get_expense_history(expense.get_id()).unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].action, AuditAction::Create);
+    assert_eq!(
+        store.get_json_value(AUDIT_LOG_SUB_KEY).unwrap(),
+        Some(Value::Null)
+    );
+}
+
+#[test]
+fn test_json_file_log_is_appended_and_encrypted() {
+    // Setup
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("store.json");
+    let store = ExpenseStore::new(JsonFileBackend::open(&path).unwrap());
+    store
+        .add_expense_as_batch(vec![setup_expense("Bookstore", 25.0, 5, 2, &[])], false)
+        .unwrap();
+    let id = store.get_all_expenses().unwrap()[0].get_id().to_string();
+    let plain = std::fs::read_to_string(dir.path().join("store.audit.jsonl")).unwrap();
+
+    // Invoke
+    store
+        .with_encryption(|encryption| encryption.enable_encryption("correct horse battery"))
+        .unwrap();
+    store.remove_expense(&id).unwrap();
+    drop(store);
+
+    // Analysis
+    assert!(plain.contains("Bookstore"));
+    let sealed = std::fs::read_to_string(dir.path().join("store.audit.jsonl")).unwrap();
+    assert_eq!(sealed.lines().count(), 2);
+    assert!(!sealed.contains("Bookstore"));
+
+    let reopened = ExpenseStore::new(JsonFileBackend::open(&path).unwrap());
+    reopened
+        .with_encryption(|encryption| encryption.unlock("correct horse battery"))
+        .unwrap();
+    let actions: Vec<AuditAction> = reopened
+        .get_expense_history(&id)
+        .unwrap()
+        .iter()
+        .map(|entry| entry.action)
+        .collect();
+    assert_eq!(actions, vec![AuditAction::Delete, AuditAction::Create]);
+}
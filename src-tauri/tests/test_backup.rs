@@ -0,0 +1,180 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde_json::json;
+use tauri_app_lib::model::backup::{BackupInfo, BackupReason, RetentionPolicy};
+use tauri_app_lib::model::expense::Expense;
+use tauri_app_lib::store::app_store::ExpenseStore;
+use tauri_app_lib::store::backup::{snapshots_to_prune, BackupManager};
+use tauri_app_lib::store::memory_backend::MemoryBackend;
+use tempfile::tempdir;
+
+/// Helper function to build a point in time of 2025
+///
+/// Returns:
+/// `NaiveDateTime` The given day at the given hour
+fn setup_time(month: u32, day: u32, hour: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2025, month, day)
+        .unwrap()
+        .and_hms_opt(hour, 0, 0)
+        .unwrap()
+}
+
+/// Helper function to set up a snapshot listing entry
+fn setup_info(id: &str, created_at: NaiveDateTime) -> BackupInfo {
+    BackupInfo {
+        id: id.to_string(),
+        created_at,
+        reason: BackupReason::Startup,
+        size_bytes: 0,
+    }
+}
+
+#[test]
+fn test_snapshot_roundtrip_and_inspect() {
+    // Setup
+    let dir = tempdir().unwrap();
+    let backups = BackupManager::new(dir.path(), RetentionPolicy::default());
+    let mut expense = Expense::new("Rent".to_string(), 1500.0, setup_time(3, 1, 0));
+    expense.set_id("rent");
+    let store_data = json!({
+        "expenses": { "rent": expense },
+        "import_history": ["2025-03-02"],
+    });
+
+    // Invoke
+    let info = backups
+        .create_snapshot_at(&store_data, BackupReason::Import, setup_time(3, 2, 9))
+        .unwrap()
+        .unwrap();
+
+    // Analysis
+    assert_eq!(backups.list().unwrap().len(), 1);
+    assert_eq!(backups.load(&info.id).unwrap().data, store_data);
+
+    let details = backups.inspect(&info.id).unwrap();
+    assert_eq!(details.info.reason, BackupReason::Import);
+    assert_eq!(details.expense_count, 1);
+    assert_eq!(details.first_expense_date, Some(setup_time(3, 1, 0)));
+    assert_eq!(details.keys.len(), 2);
+}
+
+#[test]
+fn test_empty_store_is_not_snapshotted() {
+    // Setup
+    let dir = tempdir().unwrap();
+    let backups = BackupManager::new(dir.path(), RetentionPolicy::default());
+
+    // Invoke
+    let info = backups
+        .create_snapshot(&serde_json::Value::Null, BackupReason::Startup)
+        .unwrap();
+
+    // Analysis
+    assert!(info.is_none());
+    assert!(backups.list().unwrap().is_empty());
+}
+
+#[test]
+fn test_retention_keeps_latest_of_each_period() {
+    // Setup
+    let retention = RetentionPolicy {
+        daily: 2,
+        weekly: 1,
+        monthly: 2,
+    };
+    let backups = vec![
+        setup_info("march-10-late", setup_time(3, 10, 18)),
+        setup_info("march-10-early", setup_time(3, 10, 8)),
+        setup_info("march-9", setup_time(3, 9, 8)),
+        setup_info("march-8", setup_time(3, 8, 8)),
+        setup_info("february-20", setup_time(2, 20, 8)),
+        setup_info("february-3", setup_time(2, 3, 8)),
+        setup_info("january-5", setup_time(1, 5, 8)),
+    ];
+
+    // Invoke
+    let mut pruned = snapshots_to_prune(&backups, &retention);
+
+    // Analysis
+    pruned.sort();
+    assert_eq!(
+        pruned,
+        vec!["february-3", "january-5", "march-10-early", "march-8"]
+    );
+}
+
+#[test]
+fn test_snapshot_prunes_old_snapshots() {
+    // Setup
+    let dir = tempdir().unwrap();
+    let retention = RetentionPolicy {
+        daily: 1,
+        weekly: 0,
+        monthly: 0,
+    };
+    let backups = BackupManager::new(dir.path(), retention);
+    let store_data = json!({ "import_history": [] });
+
+    // Invoke
+    for day in 1..=3 {
+        backups
+            .create_snapshot_at(&store_data, BackupReason::Startup, setup_time(3, day, 9))
+            .unwrap();
+    }
+
+    // Analysis
+    let list = backups.list().unwrap();
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0].created_at, setup_time(3, 3, 9));
+}
+
+#[test]
+fn test_invalid_backup_id_is_rejected() {
+    // Setup
+    let dir = tempdir().unwrap();
+    let backups = BackupManager::new(dir.path().join("backups"), RetentionPolicy::default());
+    std::fs::write(dir.path().join("store.json"), "{}").unwrap();
+
+    // Invoke
+    let result = backups.load("../store.json");
+
+    // Analysis
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_restore_replaces_all_keys() {
+    // Setup
+    let dir = tempdir().unwrap();
+    let backups = BackupManager::new(dir.path(), RetentionPolicy::default());
+    let store = ExpenseStore::new(MemoryBackend::new());
+    store
+        .set_json_value("import_history", json!(["2025-03-01"]))
+        .unwrap();
+    let info = backups
+        .create_snapshot(&store.get_all_store_data().unwrap(), BackupReason::Startup)
+        .unwrap()
+        .unwrap();
+
+    store
+        .add_expense(
+            Expense::new("Coffee".to_string(), 4.5, setup_time(3, 2, 0)),
+            false,
+        )
+        .unwrap();
+    store
+        .set_json_value("forecast_config", json!({ "months": 6 }))
+        .unwrap();
+
+    // Invoke
+    store
+        .replace_all_data(backups.load(&info.id).unwrap().data)
+        .unwrap();
+
+    // Analysis
+    assert!(store.get_all_expenses().unwrap().is_empty());
+    assert_eq!(store.get_json_value("forecast_config").unwrap(), None);
+    assert_eq!(
+        store.get_json_value("import_history").unwrap(),
+        Some(json!(["2025-03-01"]))
+    );
+}
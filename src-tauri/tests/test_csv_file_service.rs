@@ -6,6 +6,7 @@ use std::io::Error as IoError;
 use std::io::Write;
 use std::num::ParseFloatError;
 use tauri_app_lib::definition::csv_definition::SHEKEL_TO_DOLLAR_DIVISION;
+use tauri_app_lib::model::money::Money;
 use tempfile::Builder;
 
 use tauri_app_lib::definition::csv_definition::{
@@ -109,7 +110,7 @@ fn test_validate_and_parse_string_true() {
 #[test]
 fn test_validate_and_parse_float_ok_1() {
     // Setup
-    let expected = ParsedValue::Float(1.0);
+    let expected = ParsedValue::Amount(Some(Money::from(1.0)));
 
     // Invoke
     let result = cast_raw_value(
@@ -125,7 +126,7 @@ fn test_validate_and_parse_float_ok_1() {
 #[test]
 fn test_validate_and_parse_float_negative() {
     // Setup
-    let expected = ParsedValue::Float(-123.45);
+    let expected = ParsedValue::Amount(Some(Money::from(-123.45)));
 
     // Invoke
     let result = cast_raw_value(
@@ -141,8 +142,7 @@ fn test_validate_and_parse_float_negative() {
 #[test]
 fn test_validate_and_parse_float_extremely_large() {
     // Setup
-    let large_number = "1.7976931348623157e308"; // Close to f64::MAX
-    let expected = ParsedValue::Float(1.7976931348623157e308);
+    let large_number = "1.7976931348623157e308"; // Close to f64::MAX, too many cents to count
 
     // Invoke
     let result = cast_raw_value(
@@ -152,17 +152,16 @@ fn test_validate_and_parse_float_extremely_large() {
 
     // Analysis
     assert!(
-        result.is_ok(),
-        "Expected validation to succeed for extremely large number"
+        result.is_err(),
+        "Expected validation to fail for extremely large number"
     );
-    assert_eq!(result.unwrap(), expected);
 }
 
 #[test]
 fn test_validate_and_parse_float_extremely_small() {
     // Setup
     let small_number = "2.2250738585072014e-308"; // Close to f64::MIN_POSITIVE
-    let expected = ParsedValue::Float(2.2250738585072014e-308);
+    let expected = ParsedValue::Amount(Some(Money::ZERO)); // Rounded to the nearest cent
 
     // Invoke
     let result = cast_raw_value(
@@ -196,7 +195,7 @@ fn test_validate_and_parse_float_overflow() {
 #[test]
 fn test_validate_and_parse_float_inversed() {
     // Setup
-    let expected = ParsedValue::Float(-123.45);
+    let expected = ParsedValue::Amount(Some(Money::from(-123.45)));
 
     // Invoke
     let result = cast_raw_value(
@@ -212,7 +211,7 @@ fn test_validate_and_parse_float_inversed() {
 #[test]
 fn test_validate_and_parse_float_zero() {
     // Setup
-    let expected = ParsedValue::Float(0.0);
+    let expected = ParsedValue::Amount(Some(Money::from(0.0)));
 
     // Invoke
     let result = cast_raw_value(
@@ -236,13 +235,9 @@ fn test_validate_and_parse_float_empty_string_not_required() {
     // Analysis
     assert!(
         result.is_ok(),
-        "Expected validation to be ok still since it is a NaN"
+        "Expected validation to be ok still since it is optional"
     );
-    if let Ok(ParsedValue::Float(val)) = result {
-        assert!(val.is_nan());
-    } else {
-        panic!("Expected a NaN Float");
-    }
+    assert_eq!(result.unwrap(), ParsedValue::Amount(None));
 }
 
 #[test]
@@ -262,7 +257,7 @@ fn test_validate_and_parse_float_empty_string_required() {
 #[test]
 fn test_validate_and_parse_float_ok_2() {
     // Setup
-    let expected = ParsedValue::Float(1000000.0);
+    let expected = ParsedValue::Amount(Some(Money::from(1000000.0)));
 
     // Invoke
     let result = cast_raw_value(
@@ -408,7 +403,7 @@ fn test_default_currency() {
     // Analysis
     assert!(result.is_ok(), "Expected parsing to succeed");
     let expense = result.unwrap();
-    assert_eq!(expense.get_amount(), expected_amount);
+    assert_eq!(expense.get_amount(), Money::from(expected_amount));
 }
 
 #[test]
@@ -436,7 +431,7 @@ fn test_default_currency_empty() {
     // Analysis
     assert!(result.is_ok(), "Expected parsing to succeed");
     let expense = result.unwrap();
-    assert_eq!(expense.get_amount(), expected_amount);
+    assert_eq!(expense.get_amount(), Money::from(expected_amount));
 }
 
 #[test]
@@ -471,7 +466,7 @@ fn test_currency_role_with_second_amount() {
     // Analysis
     assert!(result.is_ok(), "Expected parsing to succeed");
     let expense = result.unwrap();
-    assert_eq!(expense.get_amount(), expected_amount);
+    assert_eq!(expense.get_amount(), Money::from(expected_amount));
 }
 
 #[test]
@@ -544,7 +539,7 @@ fn test_currency_role_no_override() {
         "Expected parsing to succeed, leaving the currency as shekel"
     );
     let expense = result.unwrap();
-    assert_eq!(expense.get_amount(), expected_amount);
+    assert_eq!(expense.get_amount(), Money::from(expected_amount));
 }
 
 #[test]
@@ -708,7 +703,7 @@ fn test_parse_record_with_optional_amount_and_credit() {
     let expense = result.unwrap();
     assert_eq!(
         expense.get_amount(),
-        expected_amount,
+        Money::from(expected_amount),
         "Expected amount to be overridden by credit value"
     );
 }
@@ -762,7 +757,7 @@ fn test_parse_record_with_credit_debit() {
     let expense = result.unwrap();
     assert_eq!(
         expense.get_amount(),
-        expected_amount,
+        Money::from(expected_amount),
         "Expected amount to be overridden by credit value"
     );
 }
@@ -784,7 +779,7 @@ fn test_parse_record_with_valid_data() {
     let expense = result.unwrap();
     assert_eq!(expense.get_date().to_string(), expected_date);
     assert_eq!(expense.get_description(), expected_description);
-    assert_eq!(expense.get_amount(), expected_amount);
+    assert_eq!(expense.get_amount(), Money::from(expected_amount));
 }
 
 #[test]
@@ -1050,7 +1045,7 @@ fn test_parse_record_with_inversed_amount() {
         "Expected parsing to succeed with inversed amount"
     );
     let expense = result.unwrap();
-    assert_eq!(expense.get_amount(), expected_amount);
+    assert_eq!(expense.get_amount(), Money::from(expected_amount));
 }
 
 #[test]
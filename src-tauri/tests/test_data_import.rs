@@ -0,0 +1,258 @@
+use serde_json::{json, Map, Value};
+use tauri_app_lib::model::expense::Expense;
+use tauri_app_lib::model::import::{ImportMode, KeyImportReport, KeyImportStatus};
+use tauri_app_lib::service::import_service::{validate_import, ImportValidationError};
+use tauri_app_lib::store::app_store::ExpenseStore;
+use tauri_app_lib::store::memory_backend::MemoryBackend;
+
+mod common;
+use common::setup_expense;
+
+/// Helper function to set up a store holding two expenses and one grant
+///
+/// Returns:
+/// - The store
+/// - Its exported store_data
+fn setup_store() -> (ExpenseStore<MemoryBackend>, Map<String, Value>) {
+    let store = ExpenseStore::new(MemoryBackend::new());
+    store
+        .add_expense_as_batch(
+            vec![
+                setup_expense("Bookstore", 25.0, 6, 2, &[]),
+                setup_expense("Bakery", 4.5, 6, 3, &[]),
+            ],
+            false,
+        )
+        .unwrap();
+    store
+        .set_json_value("grants", json!({ "g1": setup_grant("g1", "Initial") }))
+        .unwrap();
+
+    let data = store
+        .get_all_store_data()
+        .unwrap()
+        .as_object()
+        .unwrap()
+        .clone();
+    (store, data)
+}
+
+fn setup_grant(id: &str, name: &str) -> Value {
+    json!({
+        "id": id,
+        "name": name,
+        "stockId": "s1",
+        "grantPrice": 10.0,
+        "totalShares": 400.0
+    })
+}
+
+fn expense_value(expense: &Expense) -> Value {
+    serde_json::to_value(expense).unwrap()
+}
+
+fn report_for<'r>(reports: &'r [KeyImportReport], key: &str) -> &'r KeyImportReport {
+    reports.iter().find(|report| report.key == key).unwrap()
+}
+
+#[test]
+fn test_validation_rejects_malformed_keys() {
+    // Setup
+    let values = json!({
+        "grants": { "g1": { "id": "g1", "name": "Missing fields" } },
+        "expenses": { "a": { "id": "b", "description": "x", "amount": 1.0, "tags": [], "date": "2025-06-01T00:00:00" } },
+        "custom_csv_definitions": "not a list",
+        "garbage": 42
+    });
+
+    // Invoke
+    let err: ImportValidationError = validate_import(values.as_object().unwrap()).unwrap_err();
+
+    // Analysis
+    let keys: Vec<&String> = err.errors.keys().collect();
+    assert_eq!(keys, vec!["custom_csv_definitions", "expenses", "grants"]);
+}
+
+#[test]
+fn test_invalid_import_leaves_store_untouched() {
+    // Setup
+    let (store, data) = setup_store();
+    let values = json!({
+        "forecast_config": { "startBalance": "lots" },
+        "grants": {}
+    });
+
+    // Invoke
+    let result = store.import_data(values.as_object().unwrap().clone(), ImportMode::Replace);
+
+    // Analysis
+    assert!(result
+        .unwrap_err()
+        .downcast_ref::<ImportValidationError>()
+        .is_some());
+    assert_eq!(store.get_all_store_data().unwrap(), Value::Object(data));
+}
+
+#[test]
+fn test_replace_mode_overwrites_keys() {
+    // Setup
+    let (store, _) = setup_store();
+    let kept = setup_expense("Grocer", 60.0, 6, 9, &[]);
+    let mut kept_value = expense_value(&kept);
+    kept_value["id"] = json!("imported-id");
+    let values = json!({
+        "expenses": { "imported-id": kept_value },
+        "grants": { "g2": setup_grant("g2", "Refresh") }
+    });
+
+    // Invoke
+    let report = store
+        .import_data(values.as_object().unwrap().clone(), ImportMode::Replace)
+        .unwrap();
+
+    // Analysis
+    let expenses = report_for(&report.keys, "expenses");
+    assert_eq!((expenses.added, expenses.removed), (1, 2));
+    let expenses = store.get_all_expenses().unwrap();
+    assert_eq!(expenses.len(), 1);
+    assert_eq!(expenses[0].get_description(), "Grocer");
+    let grants = store.get_json_value("grants").unwrap().unwrap();
+    assert!(grants.get("g1").is_none());
+    assert!(grants.get("g2").is_some());
+}
+
+#[test]
+fn test_merge_mode_keeps_newer_records() {
+    // Setup
+    let (store, data) = setup_store();
+    // A partial backup holding one of the expenses, edited, and a new grant
+    let mut partial = data["expenses"].as_object().unwrap().clone();
+    let (first_id, mut first) = partial
+        .iter()
+        .next()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .unwrap();
+    first["tags"] = json!(["Books"]);
+    partial.clear();
+    partial.insert(first_id.clone(), first);
+    let values = json!({
+        "expenses": partial,
+        "grants": { "g2": setup_grant("g2", "Refresh") }
+    });
+
+    // Invoke
+    let report = store
+        .import_data(values.as_object().unwrap().clone(), ImportMode::Merge)
+        .unwrap();
+
+    // Analysis
+    let expenses_report = report_for(&report.keys, "expenses");
+    assert_eq!((expenses_report.updated, expenses_report.removed), (1, 0));
+    assert_eq!(store.get_all_expenses().unwrap().len(), 2);
+    let merged = store.get_expense(&first_id).unwrap().unwrap();
+    assert_eq!(merged.get_tags(), &vec!["Books".to_string()]);
+    let grants = store.get_json_value("grants").unwrap().unwrap();
+    assert_eq!(grants.as_object().unwrap().len(), 2);
+}
+
+#[test]
+fn test_only_missing_mode_skips_existing_records() {
+    // Setup
+    let (store, _) = setup_store();
+    let values = json!({
+        "grants": {
+            "g1": setup_grant("g1", "Renamed"),
+            "g2": setup_grant("g2", "Refresh")
+        },
+        "ssdi_config": { "year": 2025, "sgaByYear": { "2025": 1620.0 } }
+    });
+
+    // Invoke
+    let report = store
+        .import_data(values.as_object().unwrap().clone(), ImportMode::OnlyMissing)
+        .unwrap();
+
+    // Analysis
+    let grants_report = report_for(&report.keys, "grants");
+    assert_eq!((grants_report.added, grants_report.skipped), (1, 1));
+    let grants = store.get_json_value("grants").unwrap().unwrap();
+    assert_eq!(grants["g1"]["name"], "Initial");
+    assert_eq!(report_for(&report.keys, "ssdi_config").added, 1);
+}
+
+#[test]
+fn test_reimporting_export_is_unchanged() {
+    // Setup
+    let (store, data) = setup_store();
+
+    // Invoke
+    let report = store.import_data(data, ImportMode::Replace).unwrap();
+
+    // Analysis
+    assert!(report.changed_keys().is_empty());
+    let expenses = report_for(&report.keys, "expenses");
+    assert_eq!(expenses.status, KeyImportStatus::Unchanged);
+    assert_eq!(expenses.unchanged, 2);
+}
+
+#[test]
+fn test_unknown_keys_are_skipped() {
+    // Setup
+    let (store, _) = setup_store();
+    let values = json!({ "garbage": [1, 2, 3], "audit_log": [] });
+
+    // Invoke
+    let report = store
+        .import_data(values.as_object().unwrap().clone(), ImportMode::Merge)
+        .unwrap();
+
+    // Analysis
+    assert!(report
+        .keys
+        .iter()
+        .all(|key| key.status == KeyImportStatus::Skipped));
+    assert!(store.get_json_value("garbage").unwrap().is_none());
+}
+
+#[test]
+fn test_csv_definitions_merge_by_id() {
+    // Setup
+    let store = ExpenseStore::new(MemoryBackend::new());
+    let definition = |id: &str, name: &str| {
+        json!({
+            "id": id,
+            "name": name,
+            "hasHeaders": true,
+            "dateColumn": { "index": 0, "format": "%Y-%m-%d" },
+            "descriptionColumn": { "index": 1 },
+            "amountColumn": { "index": 2, "inverted": false }
+        })
+    };
+    store
+        .set_json_value(
+            "custom_csv_definitions",
+            json!([definition("bank", "Bank")]),
+        )
+        .unwrap();
+    let values = json!({
+        "custom_csv_definitions": [definition("bank", "Bank v2"), definition("card", "Card")]
+    });
+
+    // Invoke
+    store
+        .import_data(values.as_object().unwrap().clone(), ImportMode::Merge)
+        .unwrap();
+
+    // Analysis
+    let definitions = store
+        .get_json_value("custom_csv_definitions")
+        .unwrap()
+        .unwrap();
+    let names: Vec<&str> = definitions
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|definition| definition["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["Bank v2", "Card"]);
+}
@@ -0,0 +1,158 @@
+use tauri_app_lib::service::duplicate_service::{
+    description_similarity, find_probable_duplicates, levenshtein,
+};
+
+mod common;
+use common::{as_stored, setup_expense};
+
+#[test]
+fn test_levenshtein_distance() {
+    assert_eq!(levenshtein("kitten", "sitting"), 3);
+    assert_eq!(levenshtein("", "abc"), 3);
+    assert_eq!(levenshtein("same", "same"), 0);
+}
+
+#[test]
+fn test_description_similarity_reordered_and_typo() {
+    // Invoke
+    let reordered = description_similarity("BLUE BOTTLE COFFEE", "coffee blue bottle");
+    let typo = description_similarity("Trader Joes", "Trader Joe's");
+    let unrelated = description_similarity("Shell Gas", "Netflix");
+
+    // Analysis
+    assert_eq!(reordered, 1.0);
+    assert!(typo > 0.8, "Expected typo to stay similar, got {}", typo);
+    assert!(
+        unrelated < 0.3,
+        "Expected unrelated text, got {}",
+        unrelated
+    );
+}
+
+#[test]
+fn test_find_probable_duplicates_manual_and_import() {
+    // Setup
+    let expenses = vec![
+        as_stored(
+            setup_expense("BLUE BOTTLE COFFEE SF", 6.5, 6, 4, &[]),
+            "amex",
+            Some("Amex"),
+        ),
+        as_stored(
+            setup_expense("Blue Bottle coffee", 6.5, 6, 5, &[]),
+            "manual",
+            None,
+        ),
+        as_stored(setup_expense("Groceries", 6.5, 6, 5, &[]), "other", None),
+    ];
+
+    // Invoke
+    let result = find_probable_duplicates(&expenses, 3, 0.75);
+
+    // Analysis
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].expense_ids, vec!["amex", "manual"]);
+    assert_eq!(result[0].pairs[0].day_gap, 1);
+}
+
+#[test]
+fn test_find_probable_duplicates_same_source_ignored() {
+    // Setup
+    let expenses = vec![
+        as_stored(
+            setup_expense("MTA Subway", 2.75, 6, 4, &[]),
+            "ride_1",
+            Some("Amex"),
+        ),
+        as_stored(
+            setup_expense("MTA Subway", 2.75, 6, 4, &[]),
+            "ride_2",
+            Some("Amex"),
+        ),
+    ];
+
+    // Invoke
+    let result = find_probable_duplicates(&expenses, 3, 0.75);
+
+    // Analysis
+    assert!(
+        result.is_empty(),
+        "Same-source rows are handled by exact dedup"
+    );
+}
+
+#[test]
+fn test_find_probable_duplicates_different_amount() {
+    // Setup
+    let expenses = vec![
+        as_stored(
+            setup_expense("Blue Bottle", 6.5, 6, 4, &[]),
+            "amex",
+            Some("Amex"),
+        ),
+        as_stored(
+            setup_expense("Blue Bottle", 6.75, 6, 4, &[]),
+            "manual",
+            None,
+        ),
+    ];
+
+    // Invoke
+    let result = find_probable_duplicates(&expenses, 3, 0.75);
+
+    // Analysis
+    assert!(result.is_empty());
+}
+
+#[test]
+fn test_find_probable_duplicates_clusters_transitively() {
+    // Setup
+    let expenses = vec![
+        as_stored(
+            setup_expense("Whole Foods Market", 42.1, 6, 10, &[]),
+            "amex",
+            Some("Amex"),
+        ),
+        as_stored(
+            setup_expense("WHOLE FOODS MKT", 42.1, 6, 10, &[]),
+            "capital",
+            Some("CapitalOne"),
+        ),
+        as_stored(
+            setup_expense("whole foods market", 42.1, 6, 11, &[]),
+            "manual",
+            None,
+        ),
+    ];
+
+    // Invoke
+    let result = find_probable_duplicates(&expenses, 3, 0.75);
+
+    // Analysis
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].expense_ids, vec!["amex", "capital", "manual"]);
+}
+
+#[test]
+fn test_find_probable_duplicates_without_sources() {
+    // Setup
+    let expenses = vec![
+        as_stored(
+            setup_expense("Whole Foods Market", 54.2, 6, 8, &[]),
+            "legacy",
+            None,
+        ),
+        as_stored(
+            setup_expense("whole foods", 54.2, 6, 9, &[]),
+            "manual",
+            None,
+        ),
+    ];
+
+    // Invoke
+    let result = find_probable_duplicates(&expenses, 3, 0.75);
+
+    // Analysis
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].expense_ids, vec!["legacy", "manual"]);
+}
@@ -0,0 +1,207 @@
+use serde_json::json;
+use tauri_app_lib::model::backup::{BackupReason, RetentionPolicy};
+use tauri_app_lib::store::app_store::ExpenseStore;
+use tauri_app_lib::store::backend::StorageBackend;
+use tauri_app_lib::store::backup::BackupManager;
+use tauri_app_lib::store::command_context::run_as_command;
+use tauri_app_lib::store::encryption::{is_sealed, KdfParams, StoreLockedError};
+use tauri_app_lib::store::json_file_backend::JsonFileBackend;
+use tauri_app_lib::store::sqlite_store::SqliteStore;
+use tempfile::tempdir;
+
+mod common;
+use common::setup_expense;
+
+static PASSPHRASE: &str = "correct horse battery";
+
+#[test]
+fn test_seal_and_open_roundtrip() {
+    // Setup
+    let key = KdfParams::generate_with(1024, 1, 1)
+        .derive_key(PASSPHRASE)
+        .unwrap();
+    let other_key = KdfParams::generate_with(1024, 1, 1)
+        .derive_key(PASSPHRASE)
+        .unwrap();
+
+    // Invoke
+    let sealed = key.seal(b"store content").unwrap();
+
+    // Analysis
+    assert!(is_sealed(&sealed));
+    assert_eq!(key.open(&sealed).unwrap(), b"store content");
+    assert!(other_key.open(&sealed).is_err());
+
+    let mut tampered = sealed.clone();
+    let last = tampered.len() - 3;
+    tampered[last] = if tampered[last] == b'0' { b'1' } else { b'0' };
+    assert!(key.open(&tampered).is_err());
+}
+
+#[test]
+fn test_enable_encryption_seals_store_file() {
+    // Setup
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("store.json");
+    let store = ExpenseStore::new(JsonFileBackend::open(&path).unwrap());
+    store
+        .add_expense(setup_expense("Secret groceries", 82.1, 3, 5, &[]), false)
+        .unwrap();
+
+    // Invoke
+    store
+        .with_encryption(|encryption| encryption.enable_encryption(PASSPHRASE))
+        .unwrap();
+
+    // Analysis
+    let content = std::fs::read(&path).unwrap();
+    assert!(is_sealed(&content));
+    assert!(!String::from_utf8_lossy(&content).contains("Secret groceries"));
+    assert_eq!(store.get_all_expenses().unwrap().len(), 1);
+}
+
+#[test]
+fn test_reopened_store_is_locked_until_unlocked() {
+    // Setup
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("store.json");
+    {
+        let store = ExpenseStore::new(JsonFileBackend::open(&path).unwrap());
+        store
+            .add_expense(setup_expense("Secret groceries", 82.1, 3, 5, &[]), false)
+            .unwrap();
+        store
+            .with_encryption(|encryption| encryption.enable_encryption(PASSPHRASE))
+            .unwrap();
+    }
+    let store = ExpenseStore::new(JsonFileBackend::open(&path).unwrap());
+
+    // Invoke
+    let locked_read = store.get_all_expenses();
+    let wrong_unlock = store.with_encryption(|encryption| encryption.unlock("wrong passphrase"));
+    store
+        .with_encryption(|encryption| encryption.unlock(PASSPHRASE))
+        .unwrap();
+
+    // Analysis
+    assert!(locked_read
+        .unwrap_err()
+        .downcast_ref::<StoreLockedError>()
+        .is_some());
+    assert!(wrong_unlock.is_err());
+    assert!(!store.is_locked());
+    assert_eq!(store.get_all_expenses().unwrap().len(), 1);
+}
+
+#[test]
+fn test_lock_forgets_data() {
+    // Setup
+    let dir = tempdir().unwrap();
+    let store = ExpenseStore::new(JsonFileBackend::open(dir.path().join("store.json")).unwrap());
+    store
+        .add_expense(setup_expense("Secret groceries", 82.1, 3, 5, &[]), false)
+        .unwrap();
+    store
+        .with_encryption(|encryption| encryption.enable_encryption(PASSPHRASE))
+        .unwrap();
+    let mut edited = store.get_all_expenses().unwrap().remove(0);
+    edited.add_tag("Food");
+    run_as_command("update_expense", || {
+        store
+            .update_expense(edited.get_id().to_string(), edited.clone())
+            .unwrap()
+    });
+
+    // Invoke
+    store
+        .with_encryption(|encryption| encryption.lock())
+        .unwrap();
+
+    // Analysis
+    assert!(store.is_locked());
+    assert!(store.get_all_expenses().is_err());
+    let state = store.undo_state().unwrap();
+    assert!(state.undo_command.is_none());
+    assert!(state.redo_command.is_none());
+    assert!(store.set_json_value("import_history", json!([])).is_err());
+}
+
+#[test]
+fn test_change_passphrase_and_disable() {
+    // Setup
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("store.json");
+    let store = ExpenseStore::new(JsonFileBackend::open(&path).unwrap());
+    store
+        .add_expense(setup_expense("Secret groceries", 82.1, 3, 5, &[]), false)
+        .unwrap();
+    store
+        .with_encryption(|encryption| encryption.enable_encryption(PASSPHRASE))
+        .unwrap();
+
+    // Invoke
+    let wrong_change = store.with_encryption(|encryption| {
+        encryption.change_passphrase("wrong passphrase", "another passphrase")
+    });
+    store
+        .with_encryption(|encryption| {
+            encryption.change_passphrase(PASSPHRASE, "another passphrase")
+        })
+        .unwrap();
+    store
+        .with_encryption(|encryption| encryption.disable_encryption("another passphrase"))
+        .unwrap();
+
+    // Analysis
+    assert!(wrong_change.is_err());
+    assert!(!is_sealed(&std::fs::read(&path).unwrap()));
+    let reopened = JsonFileBackend::open(&path).unwrap();
+    assert_eq!(reopened.load_expenses().unwrap().unwrap().len(), 1);
+}
+
+#[test]
+fn test_backups_are_resealed_with_the_store() {
+    // Setup
+    let dir = tempdir().unwrap();
+    let store = ExpenseStore::new(JsonFileBackend::open(dir.path().join("store.json")).unwrap());
+    store
+        .add_expense(setup_expense("Secret groceries", 82.1, 3, 5, &[]), false)
+        .unwrap();
+    let vault = store.backend().encryption().unwrap().vault();
+    let backups = BackupManager::new(dir.path().join("backups"), RetentionPolicy::default())
+        .with_vault(vault);
+    let info = backups
+        .create_snapshot(&store.get_all_store_data().unwrap(), BackupReason::Startup)
+        .unwrap()
+        .unwrap();
+
+    // Invoke
+    backups
+        .reseal_all(|| store.with_encryption(|encryption| encryption.enable_encryption(PASSPHRASE)))
+        .unwrap();
+
+    // Analysis
+    let content = std::fs::read(backups.dir().join(&info.id)).unwrap();
+    assert!(is_sealed(&content));
+    assert_eq!(backups.inspect(&info.id).unwrap().expense_count, 1);
+}
+
+#[test]
+fn test_encryption_rejected_by_sqlite_and_short_passphrase() {
+    // Setup
+    let sqlite_store = ExpenseStore::new(SqliteStore::open_in_memory().unwrap());
+    let dir = tempdir().unwrap();
+    let json_store =
+        ExpenseStore::new(JsonFileBackend::open(dir.path().join("store.json")).unwrap());
+
+    // Invoke
+    let sqlite_result =
+        sqlite_store.with_encryption(|encryption| encryption.enable_encryption(PASSPHRASE));
+    let short_result =
+        json_store.with_encryption(|encryption| encryption.enable_encryption("short"));
+
+    // Analysis
+    assert!(sqlite_result.is_err());
+    assert!(short_result.is_err());
+    assert!(!json_store.is_locked());
+}
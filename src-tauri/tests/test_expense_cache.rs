@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use tauri_app_lib::store::expense_cache::ExpenseCache;
+
+mod common;
+use common::{as_stored, setup_date, setup_expense};
+
+#[test]
+fn test_between_returns_range_in_date_order() {
+    // Setup
+    let cache = ExpenseCache::from_stored(Some(HashMap::from([
+        (
+            "c".to_string(),
+            as_stored(setup_expense("Expense c", 10.0, 3, 20, &[]), "c", None),
+        ),
+        (
+            "a".to_string(),
+            as_stored(setup_expense("Expense a", 10.0, 3, 5, &[]), "a", None),
+        ),
+        (
+            "b".to_string(),
+            as_stored(setup_expense("Expense b", 10.0, 3, 12, &[]), "b", None),
+        ),
+    ])));
+
+    // Invoke
+    let result = cache.between(setup_date(3, 5), setup_date(3, 12));
+
+    // Analysis
+    let ids: Vec<&str> = result.iter().map(|e| e.get_id()).collect();
+    assert_eq!(ids, vec!["a", "b"]);
+}
+
+#[test]
+fn test_upsert_moves_tag_index() {
+    // Setup
+    let mut cache = ExpenseCache::from_stored(None);
+    cache.upsert(as_stored(
+        setup_expense("Expense a", 10.0, 3, 1, &["Food"]),
+        "a",
+        None,
+    ));
+
+    // Invoke
+    cache.upsert(as_stored(
+        setup_expense("Expense a", 10.0, 3, 2, &["Travel"]),
+        "a",
+        None,
+    ));
+
+    // Analysis
+    assert!(cache.with_tag("Food").is_empty());
+    assert_eq!(cache.with_tag("Travel").len(), 1);
+    assert!(cache.between(setup_date(3, 1), setup_date(3, 1)).is_empty());
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn test_remove_clears_indexes() {
+    // Setup
+    let mut cache = ExpenseCache::from_stored(None);
+    cache.upsert(as_stored(
+        setup_expense("Expense a", 10.0, 3, 1, &["Food"]),
+        "a",
+        None,
+    ));
+    cache.upsert(as_stored(
+        setup_expense("Expense b", 10.0, 3, 1, &["Food"]),
+        "b",
+        None,
+    ));
+
+    // Invoke
+    let removed = cache.remove("a");
+
+    // Analysis
+    assert!(removed.is_some());
+    assert!(!cache.contains("a"));
+    assert_eq!(cache.tag_counts().get("Food"), Some(&1));
+    assert_eq!(cache.between(setup_date(3, 1), setup_date(3, 1)).len(), 1);
+}
+
+#[test]
+fn test_upsert_reindexes_description() {
+    // Setup
+    let mut cache = ExpenseCache::from_stored(None);
+    cache.upsert(as_stored(
+        setup_expense("Expense a", 10.0, 3, 1, &[]),
+        "a",
+        None,
+    ));
+    let mut renamed = as_stored(setup_expense("Expense a", 10.0, 3, 1, &[]), "a", None);
+    renamed.set_description("Farmers market");
+
+    // Invoke
+    cache.upsert(renamed);
+
+    // Analysis
+    assert!(cache.search_description("expense", 10).is_empty());
+    assert_eq!(cache.search_description("market", 10).len(), 1);
+}
@@ -0,0 +1,163 @@
+use blake3::Hasher;
+use tauri_app_lib::store::app_store::{generate_fingerprint, occurrence_indices, ExpenseStore};
+use tauri_app_lib::store::memory_backend::MemoryBackend;
+
+mod common;
+use common::setup_expense;
+
+#[test]
+fn test_occurrence_indices_same_day_duplicates() {
+    // Setup
+    let expenses = vec![
+        setup_expense("MTA Subway", 2.75, 1, 3, &[]),
+        setup_expense("Coffee", 4.5, 1, 3, &[]),
+        setup_expense("MTA Subway", 2.75, 1, 3, &[]),
+        setup_expense("MTA Subway", 2.75, 1, 4, &[]),
+        setup_expense("MTA Subway", 2.75, 1, 3, &[]),
+    ];
+
+    // Invoke
+    let result = occurrence_indices(&expenses);
+
+    // Analysis
+    assert_eq!(result, vec![0, 0, 1, 0, 2]);
+}
+
+#[test]
+fn test_hash_first_occurrence_matches_legacy_id() {
+    // Setup
+    let expense = setup_expense("MTA Subway", 2.75, 1, 3, &[]);
+    let legacy_input = format!(
+        "{}:{}:{}",
+        expense.get_description(),
+        expense.get_date(),
+        expense.get_amount()
+    );
+    let expected = Hasher::new()
+        .update(legacy_input.as_bytes())
+        .finalize()
+        .to_hex()
+        .to_string();
+
+    // Invoke
+    let result = generate_fingerprint(&expense, 0);
+
+    // Analysis
+    assert!(result.is_ok(), "Expected hashing to succeed");
+    assert_eq!(result.unwrap(), expected);
+}
+
+#[test]
+fn test_hash_differs_per_occurrence() {
+    // Setup
+    let expense = setup_expense("MTA Subway", 2.75, 1, 3, &[]);
+
+    // Invoke
+    let first = generate_fingerprint(&expense, 0).unwrap();
+    let second = generate_fingerprint(&expense, 1).unwrap();
+    let second_again = generate_fingerprint(&expense, 1).unwrap();
+
+    // Analysis
+    assert_ne!(first, second);
+    assert_eq!(
+        second, second_again,
+        "Re-importing must produce the same id"
+    );
+}
+
+#[test]
+fn test_edited_expense_keeps_id_and_fingerprint() {
+    // Setup
+    let store = ExpenseStore::new(MemoryBackend::new());
+    let row = setup_expense("MTA Subway", 2.75, 1, 3, &[]);
+    store
+        .add_expense_as_batch(vec![row.clone()], false)
+        .unwrap();
+    let stored = store.get_all_expenses().unwrap().remove(0);
+
+    let mut edited = stored.clone();
+    edited.set_description("Subway to work");
+    edited.set_amount(3.0);
+
+    // Invoke
+    store
+        .update_expense(stored.get_id().to_string(), edited)
+        .unwrap();
+    let reimported = store
+        .add_expense_as_batch(vec![row.clone()], false)
+        .unwrap();
+
+    // Analysis
+    let expenses = store.get_all_expenses().unwrap();
+    assert_eq!(expenses.len(), 1);
+    assert_eq!(expenses[0].get_id(), stored.get_id());
+    assert_eq!(expenses[0].get_description(), "Subway to work");
+    assert_eq!(
+        expenses[0].get_fingerprint(),
+        generate_fingerprint(&row, 0).unwrap()
+    );
+    assert_eq!(reimported.duplicate_count, 1);
+}
+
+#[test]
+fn test_ids_are_opaque() {
+    // Setup
+    let store = ExpenseStore::new(MemoryBackend::new());
+    let row = setup_expense("MTA Subway", 2.75, 1, 3, &[]);
+
+    // Invoke
+    store
+        .add_expense_as_batch(vec![row.clone()], false)
+        .unwrap();
+
+    // Analysis
+    let stored = store.get_all_expenses().unwrap().remove(0);
+    assert_ne!(stored.get_id(), stored.get_fingerprint());
+    assert_ne!(stored.get_id(), generate_fingerprint(&row, 0).unwrap());
+}
+
+#[test]
+fn test_identical_manual_entries_do_not_collide() {
+    // Setup
+    let store = ExpenseStore::new(MemoryBackend::new());
+    let entry = setup_expense("Farmers market", 12.0, 1, 5, &[]);
+
+    // Invoke
+    let first = store.add_expense(entry.clone(), true).unwrap();
+    let second = store.add_expense(entry, true).unwrap();
+
+    // Analysis
+    assert!(first && second);
+    let expenses = store.get_all_expenses().unwrap();
+    assert_eq!(expenses.len(), 2);
+    assert_ne!(expenses[0].get_id(), expenses[1].get_id());
+    assert!(expenses.iter().all(|e| e.get_fingerprint().is_empty()));
+}
+
+#[test]
+fn test_update_expense_ignores_id_of_body() {
+    // Setup
+    let store = ExpenseStore::new(MemoryBackend::new());
+    store
+        .add_expense_as_batch(vec![setup_expense("Bakery", 7.25, 1, 4, &[])], false)
+        .unwrap();
+    let stored = store.get_all_expenses().unwrap().remove(0);
+
+    let mut edited = stored.clone();
+    edited.set_id("something-else");
+    edited.add_tag("Food");
+
+    // Invoke
+    let updated = store
+        .update_expense(stored.get_id().to_string(), edited)
+        .unwrap();
+
+    // Analysis
+    assert!(updated);
+    let expense = store
+        .get_expense(&stored.get_id().to_string())
+        .unwrap()
+        .unwrap();
+    assert_eq!(expense.get_id(), stored.get_id());
+    assert_eq!(expense.get_tags(), &vec!["Food".to_string()]);
+}
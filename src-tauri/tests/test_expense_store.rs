@@ -0,0 +1,225 @@
+use chrono::NaiveDate;
+use std::io::Write;
+use tauri_app_lib::definition::csv_definition::{
+    CsvColumnDataType, CsvColumnInfo, CsvColumnRole, CsvDefinition, STANDARD,
+};
+use tauri_app_lib::service::csv_file_service::parse_csv_file_with_definition;
+use tauri_app_lib::store::app_store::ExpenseStore;
+use tauri_app_lib::store::json_file_backend::JsonFileBackend;
+use tauri_app_lib::store::memory_backend::MemoryBackend;
+use tempfile::Builder;
+
+mod common;
+use common::setup_expense;
+
+#[test]
+fn test_add_expense_as_batch_skips_existing() {
+    // Setup
+    let store = ExpenseStore::new(MemoryBackend::new());
+    let batch = vec![
+        setup_expense("Coffee", 4.5, 3, 1, &[]),
+        setup_expense("Coffee", 4.5, 3, 1, &[]),
+        setup_expense("Rent", 1500.0, 3, 1, &[]),
+    ];
+    store.add_expense_as_batch(batch.clone(), false).unwrap();
+
+    // Invoke
+    let result = store.add_expense_as_batch(batch, false).unwrap();
+
+    // Analysis
+    assert_eq!(result.added_count, 0);
+    assert_eq!(result.duplicate_count, 3);
+    assert_eq!(store.get_all_expenses().unwrap().len(), 3);
+}
+
+#[test]
+fn test_update_expense_keeps_source() {
+    // Setup
+    let store = ExpenseStore::new(MemoryBackend::new());
+    let mut imported = setup_expense("Groceries", 82.1, 3, 4, &[]);
+    imported.set_source("Amex");
+    store.add_expense_as_batch(vec![imported], false).unwrap();
+    let stored = store.get_all_expenses().unwrap().remove(0);
+
+    let mut edited = stored.clone();
+    edited.add_tag("Food");
+    edited.set_source("Edited");
+
+    // Invoke
+    let result = store
+        .update_expense(stored.get_id().to_string(), edited)
+        .unwrap();
+
+    // Analysis
+    assert!(result);
+    let updated = store
+        .get_expense(&stored.get_id().to_string())
+        .unwrap()
+        .unwrap();
+    assert_eq!(updated.get_tags(), &vec!["Food"]);
+    assert_eq!(updated.get_source(), Some("Amex"));
+}
+
+#[test]
+fn test_remove_expense_unlinks_transfer() {
+    // Setup
+    let store = ExpenseStore::new(MemoryBackend::new());
+    store
+        .add_expense_as_batch(
+            vec![
+                setup_expense("Card payment", 250.0, 3, 10, &[]),
+                setup_expense("Payment received", -250.0, 3, 11, &[]),
+            ],
+            false,
+        )
+        .unwrap();
+    let expenses = store.get_all_expenses().unwrap();
+    let outflow = expenses
+        .iter()
+        .find(|e| e.get_amount().is_positive())
+        .unwrap();
+    let inflow = expenses
+        .iter()
+        .find(|e| e.get_amount().is_negative())
+        .unwrap();
+    store
+        .link_transfer(outflow.get_id(), inflow.get_id())
+        .unwrap();
+
+    // Invoke
+    let result = store.remove_expense(&outflow.get_id().to_string()).unwrap();
+
+    // Analysis
+    assert!(result);
+    let remaining = store
+        .get_expense(&inflow.get_id().to_string())
+        .unwrap()
+        .unwrap();
+    assert!(!remaining.is_transfer());
+    assert!(store.get_transfers().unwrap().is_empty());
+}
+
+#[test]
+fn test_merge_expenses_unions_tags() {
+    // Setup
+    let store = ExpenseStore::new(MemoryBackend::new());
+    let mut manual = setup_expense("Dinner at Mario's", 60.0, 3, 7, &[]);
+    manual.add_tag("Dining");
+    let mut imported = setup_expense("MARIOS TRATTORIA", 60.0, 3, 8, &[]);
+    imported.add_tag("Restaurants");
+    store
+        .add_expense_as_batch(vec![manual, imported], false)
+        .unwrap();
+    let expenses = store.get_all_expenses().unwrap();
+    let keep_id = expenses[0].get_id().to_string();
+    let merge_id = expenses[1].get_id().to_string();
+
+    // Invoke
+    let kept = store
+        .merge_expenses(&keep_id, std::slice::from_ref(&merge_id))
+        .unwrap();
+
+    // Analysis
+    assert_eq!(kept.get_tags().len(), 2);
+    assert!(!store.exists(&merge_id).unwrap());
+    assert_eq!(store.get_all_expenses().unwrap().len(), 1);
+}
+
+#[test]
+fn test_json_file_backend_persists_between_opens() {
+    // Setup
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("store.json");
+    let store = ExpenseStore::new(JsonFileBackend::open(&path).unwrap());
+
+    // Invoke
+    store
+        .add_expense(setup_expense("Bus pass", 30.0, 3, 2, &[]), false)
+        .unwrap();
+    store
+        .set_json_value("forecast_config", serde_json::json!({ "months": 6 }))
+        .unwrap();
+    let reopened = ExpenseStore::new(JsonFileBackend::open(&path).unwrap());
+
+    // Analysis
+    assert_eq!(reopened.get_all_expenses().unwrap().len(), 1);
+    assert_eq!(
+        reopened.get_json_value("forecast_config").unwrap(),
+        Some(serde_json::json!({ "months": 6 }))
+    );
+}
+
+#[test]
+fn test_parse_csv_file_into_memory_store() {
+    // Setup
+    let csv_definition = CsvDefinition::new(
+        "Test",
+        true,
+        vec![
+            (
+                CsvColumnRole::Date,
+                CsvColumnInfo::required_content(0, CsvColumnDataType::DateObject("%Y-%m-%d")),
+            ),
+            (
+                CsvColumnRole::Description,
+                CsvColumnInfo::required_content(1, CsvColumnDataType::String),
+            ),
+            (
+                CsvColumnRole::Amount,
+                CsvColumnInfo::required_content(2, CsvColumnDataType::Float(&STANDARD)),
+            ),
+        ],
+    );
+    let mut file = Builder::new().suffix(".csv").tempfile().unwrap();
+    writeln!(file, "Date,Description,Amount").unwrap();
+    writeln!(file, "2025-03-01,Subway,2.90").unwrap();
+    writeln!(file, "2025-03-01,Subway,2.90").unwrap();
+    writeln!(file, "2025-03-03,Bakery,7.25").unwrap();
+    let store = ExpenseStore::new(MemoryBackend::new());
+
+    // Invoke
+    let (added, duplicates, min_date, max_date) = parse_csv_file_with_definition(
+        &store,
+        file.path().to_str().unwrap().to_string(),
+        &csv_definition,
+        "Test",
+    )
+    .unwrap();
+
+    // Analysis
+    assert_eq!(added, 3);
+    assert_eq!(duplicates, 0);
+    assert_eq!(min_date, NaiveDate::from_ymd_opt(2025, 3, 1));
+    assert_eq!(max_date, NaiveDate::from_ymd_opt(2025, 3, 3));
+    assert!(store
+        .get_all_expenses()
+        .unwrap()
+        .iter()
+        .all(|e| e.get_source() == Some("Test")));
+}
+
+#[test]
+fn test_set_expenses_value_invalidates_cache() {
+    // Setup
+    let store = ExpenseStore::new(MemoryBackend::new());
+    store
+        .add_expense(setup_expense("Old entry", 5.0, 3, 1, &[]), false)
+        .unwrap();
+    assert_eq!(store.get_all_expenses().unwrap().len(), 1);
+
+    let mut replacement = setup_expense("Imported entry", 9.0, 3, 2, &[]);
+    replacement.set_id("imported");
+
+    // Invoke
+    store
+        .set_json_value(
+            "expenses",
+            serde_json::json!({ "imported": serde_json::to_value(&replacement).unwrap() }),
+        )
+        .unwrap();
+
+    // Analysis
+    let expenses = store.get_all_expenses().unwrap();
+    assert_eq!(expenses.len(), 1);
+    assert_eq!(expenses[0].get_id(), "imported");
+}
@@ -0,0 +1,167 @@
+use tauri_app_lib::definition::csv_definition::CsvDefinitionKey;
+use tauri_app_lib::model::expense::Expense;
+use tauri_app_lib::model::export::{ExportColumn, ExportFormat, DEFAULT_EXPORT_COLUMNS};
+use tauri_app_lib::model::money::Money;
+use tauri_app_lib::model::query::ExpenseFilter;
+use tauri_app_lib::service::csv_file_service::parse_csv_file_with_selected_definition;
+use tauri_app_lib::service::export_service::{export_to_path, write_expenses};
+use tauri_app_lib::store::app_store::ExpenseStore;
+use tauri_app_lib::store::memory_backend::MemoryBackend;
+use tempfile::tempdir;
+
+mod common;
+use common::setup_expense;
+
+/// Helper function to set up a store holding a few expenses, returned oldest first
+fn setup_store() -> (ExpenseStore<MemoryBackend>, Vec<Expense>) {
+    let store = ExpenseStore::new(MemoryBackend::new());
+    store
+        .add_expense_as_batch(
+            vec![
+                setup_expense("Bookstore, downtown", 25.0, 7, 2, &["Books"]),
+                setup_expense("Salary", -3000.0, 7, 1, &["Income"]),
+                setup_expense("Bakery & Cafe", 4.5, 7, 3, &[]),
+            ],
+            false,
+        )
+        .unwrap();
+    let expenses = store
+        .get_matching_expenses(&ExpenseFilter::default())
+        .unwrap();
+    (store, expenses)
+}
+
+fn render(expenses: &[Expense], format: ExportFormat, columns: &[ExportColumn]) -> String {
+    let mut output = Vec::new();
+    write_expenses(&mut output, expenses, format, columns).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn test_matching_expenses_are_filtered_oldest_first() {
+    // Setup
+    let (store, _) = setup_store();
+    let filter = ExpenseFilter {
+        min_amount: Some(Money::ZERO),
+        ..ExpenseFilter::default()
+    };
+
+    // Invoke
+    let expenses = store.get_matching_expenses(&filter).unwrap();
+
+    // Analysis
+    let descriptions: Vec<&str> = expenses.iter().map(|e| e.get_description()).collect();
+    assert_eq!(descriptions, vec!["Bookstore, downtown", "Bakery & Cafe"]);
+}
+
+#[test]
+fn test_backup_csv_round_trips() {
+    // Setup
+    let (_, expenses) = setup_store();
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("export.csv");
+    let target = ExpenseStore::new(MemoryBackend::new());
+
+    // Invoke
+    export_to_path(&path, &expenses, ExportFormat::ExpenseTrackerBackup, &[]).unwrap();
+    let (added, duplicates, _, _) = parse_csv_file_with_selected_definition(
+        &target,
+        path.to_string_lossy().to_string(),
+        CsvDefinitionKey::ExpenseTrackerBackup,
+    )
+    .unwrap();
+
+    // Analysis
+    assert_eq!((added, duplicates), (3, 0));
+    let imported = target
+        .get_matching_expenses(&ExpenseFilter::default())
+        .unwrap();
+    for (original, imported) in expenses.iter().zip(imported.iter()) {
+        assert_eq!(original.get_description(), imported.get_description());
+        assert_eq!(original.get_amount(), imported.get_amount());
+        assert_eq!(original.get_date(), imported.get_date());
+        assert_eq!(original.get_tags(), imported.get_tags());
+    }
+}
+
+#[test]
+fn test_csv_with_chosen_columns() {
+    // Setup
+    let (_, expenses) = setup_store();
+
+    // Invoke
+    let output = render(
+        &expenses[1..2],
+        ExportFormat::Csv,
+        &[ExportColumn::Description, ExportColumn::Amount],
+    );
+    let default_output = render(&expenses[..1], ExportFormat::Csv, DEFAULT_EXPORT_COLUMNS);
+
+    // Analysis
+    assert_eq!(output, "Description,Amount\n\"Bookstore, downtown\",25\n");
+    assert_eq!(
+        default_output,
+        "Date,Description,Amount,Tags\n2025-07-01,Salary,-3000,Income\n"
+    );
+}
+
+#[test]
+fn test_csv_without_columns_fails() {
+    // Setup
+    let (_, expenses) = setup_store();
+    let mut output = Vec::new();
+
+    // Invoke
+    let result = write_expenses(&mut output, &expenses, ExportFormat::Csv, &[]);
+
+    // Analysis
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_json_lists_stored_expenses() {
+    // Setup
+    let (_, expenses) = setup_store();
+
+    // Invoke
+    let output = render(&expenses, ExportFormat::Json, &[]);
+
+    // Analysis
+    let parsed: Vec<Expense> = serde_json::from_str(&output).unwrap();
+    assert_eq!(parsed, expenses);
+}
+
+#[test]
+fn test_ofx_transactions() {
+    // Setup
+    let (_, expenses) = setup_store();
+
+    // Invoke
+    let output = render(&expenses, ExportFormat::Ofx, &[]);
+
+    // Analysis
+    assert!(output.starts_with("OFXHEADER:100\n"));
+    assert_eq!(output.matches("<STMTTRN>").count(), 3);
+    assert!(output.contains("<DTSTART>20250701000000<DTEND>20250703000000"));
+    assert!(output.contains("<TRNTYPE>CREDIT\n<DTPOSTED>20250701000000\n<TRNAMT>3000.00"));
+    assert!(output.contains("<TRNAMT>-25.00"));
+    assert!(output.contains(&format!("<FITID>{}", expenses[0].get_id())));
+    assert!(output.contains("<NAME>Bakery &amp; Cafe"));
+}
+
+#[test]
+fn test_qif_transactions() {
+    // Setup
+    let (_, expenses) = setup_store();
+
+    // Invoke
+    let output = render(&expenses[1..], ExportFormat::Qif, &[]);
+
+    // Analysis
+    assert_eq!(
+        output,
+        "!Type:Bank\n\
+         D07/02/2025\nT-25.00\nPBookstore, downtown\nLBooks\n^\n\
+         D07/03/2025\nT-4.50\nPBakery & Cafe\n^\n"
+    );
+}
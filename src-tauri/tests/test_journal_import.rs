@@ -0,0 +1,280 @@
+use tauri_app_lib::model::expense::Expense;
+use tauri_app_lib::model::ledger::{JournalFormat, LedgerConfig};
+use tauri_app_lib::model::money::Money;
+use tauri_app_lib::service::journal_parser::{parse_journal, tag_for_account, JOURNAL_SOURCE};
+use tauri_app_lib::service::ledger_service::write_journal;
+
+mod common;
+use common::setup_date;
+
+#[test]
+fn test_beancount_transactions() {
+    // Setup
+    let journal = r#"
+option "operating_currency" "USD"
+
+2025-01-01 open Expenses:Food
+2025-01-01 open Assets:Checking
+
+2025-08-02 * "Trader Joe's" "Weekly groceries" #weekly ^receipt-1
+  source: "NavyFederal"
+  Expenses:Food          60.25 USD
+  Assets:Checking
+
+2025-08-03 txn "Salary"
+  Income:Salary  -3,000.00 USD
+  Assets:Checking  3000.00 USD
+"#;
+
+    // Invoke
+    let parsed = parse_journal(journal, &LedgerConfig::default());
+
+    // Analysis
+    assert_eq!(parsed.transactions, 2);
+    assert!(parsed.warnings.is_empty(), "{:?}", parsed.warnings);
+    assert_eq!(parsed.expenses.len(), 2);
+
+    let groceries = &parsed.expenses[0];
+    assert_eq!(
+        groceries.get_description(),
+        "Trader Joe's - Weekly groceries"
+    );
+    assert_eq!(groceries.get_amount(), Money::from(60.25));
+    assert_eq!(*groceries.get_date(), setup_date(8, 2));
+    assert_eq!(
+        groceries.get_tags(),
+        &vec!["Food".to_string(), "weekly".to_string()]
+    );
+    assert_eq!(groceries.get_source(), Some("NavyFederal"));
+
+    let salary = &parsed.expenses[1];
+    assert_eq!(salary.get_amount(), Money::from(-3000.0));
+    assert_eq!(salary.get_tags(), &vec!["Salary".to_string()]);
+    assert_eq!(salary.get_source(), Some(JOURNAL_SOURCE));
+}
+
+#[test]
+fn test_ledger_transactions() {
+    // Setup
+    let journal = "\
+; Monthly bills
+2025/08/04=2025/08/06 * (1042) Electric Co | August bill ; :bills:
+    Expenses:Utilities:Electric   $84.10
+    Liabilities:Credit Card        -$84.10
+
+2025/08/05 Coffee Shop
+    Expenses:Food  4.50  ; :coffee:
+    Assets:Cash
+";
+
+    // Invoke
+    let parsed = parse_journal(journal, &LedgerConfig::default());
+
+    // Analysis
+    assert!(parsed.warnings.is_empty(), "{:?}", parsed.warnings);
+    assert_eq!(parsed.expenses.len(), 2);
+
+    let electric = &parsed.expenses[0];
+    assert_eq!(electric.get_description(), "Electric Co - August bill");
+    assert_eq!(electric.get_amount(), Money::from(84.1));
+    assert_eq!(*electric.get_date(), setup_date(8, 4));
+    assert_eq!(
+        electric.get_tags(),
+        &vec!["Utilities".to_string(), "bills".to_string()]
+    );
+
+    let coffee = &parsed.expenses[1];
+    assert_eq!(coffee.get_amount(), Money::from(4.5));
+    assert_eq!(
+        coffee.get_tags(),
+        &vec!["Food".to_string(), "coffee".to_string()]
+    );
+}
+
+#[test]
+fn test_unsupported_directives_are_warnings() {
+    // Setup
+    let journal = r#"
+include "other.beancount"
+2025-08-01 balance Assets:Checking  100.00 USD
+2025-08-01 price EUR 1.10 USD
+
+2025-08-02 * "Bakery"
+  Expenses:Food  3.00 USD
+  Assets:Cash   -3.00 USD
+"#;
+
+    // Invoke
+    let parsed = parse_journal(journal, &LedgerConfig::default());
+
+    // Analysis
+    assert_eq!(parsed.expenses.len(), 1);
+    let lines: Vec<usize> = parsed.warnings.iter().map(|warning| warning.line).collect();
+    assert_eq!(lines, vec![2, 3, 4]);
+    assert!(parsed.warnings[0].message.contains("`include`"));
+    assert!(parsed.warnings[1].message.contains("`balance`"));
+}
+
+#[test]
+fn test_foreign_currency_needs_a_price() {
+    // Setup
+    let journal = r#"
+2025-08-02 * "Falafel"
+  Expenses:Food  35.00 ILS @ 0.28 USD
+  Assets:Cash
+
+2025-08-03 * "Hummus"
+  Expenses:Food  20.00 ILS
+  Assets:Cash   -20.00 ILS
+"#;
+
+    // Invoke
+    let parsed = parse_journal(journal, &LedgerConfig::default());
+
+    // Analysis
+    assert_eq!(parsed.expenses.len(), 1);
+    assert_eq!(parsed.expenses[0].get_amount(), Money::from(9.8));
+    assert_eq!(parsed.warnings.len(), 1);
+    assert_eq!(parsed.warnings[0].line, 7);
+    assert!(parsed.warnings[0].message.contains("ILS"));
+}
+
+#[test]
+fn test_prices_are_applied_exactly() {
+    // Setup
+    let journal = r#"
+2025-08-02 * "Pastries"
+  Expenses:Food  3 EUR @ 1.10 USD
+  Expenses:Gifts  10 EUR @ 0.333 USD
+  Expenses:Fees  -2 EUR @@ 5.00 USD
+  Assets:Cash
+"#;
+
+    // Invoke
+    let parsed = parse_journal(journal, &LedgerConfig::default());
+
+    // Analysis
+    let amounts: Vec<Money> = parsed
+        .expenses
+        .iter()
+        .map(|expense| expense.get_amount())
+        .collect();
+    assert_eq!(
+        amounts,
+        vec![Money::from(3.3), Money::from(3.33), Money::from(-5.0)]
+    );
+    assert!(parsed.warnings.is_empty());
+}
+
+#[test]
+fn test_out_of_range_amount_is_a_warning() {
+    // Setup
+    let journal = r#"
+2025-08-02 * "Too much"
+  Expenses:Food  100000000000000000000.00 USD
+  Assets:Cash
+
+2025-08-03 * "Fine"
+  Expenses:Food  1.00 USD
+  Assets:Cash
+"#;
+
+    // Invoke
+    let parsed = parse_journal(journal, &LedgerConfig::default());
+
+    // Analysis
+    assert_eq!(parsed.expenses.len(), 1);
+    assert_eq!(parsed.expenses[0].get_description(), "Fine");
+    assert_eq!(parsed.warnings.len(), 1);
+    assert_eq!(parsed.warnings[0].line, 3);
+    assert!(parsed.warnings[0].message.contains("too large"));
+}
+
+#[test]
+fn test_unbalanced_and_invalid_transactions_are_skipped() {
+    // Setup
+    let journal = r#"
+2025-08-02 * "Unbalanced"
+  Expenses:Food  10.00 USD
+  Assets:Cash   -9.00 USD
+
+2025-08-03 * "Bad amount"
+  Expenses:Food  ten dollars  USD
+  Assets:Cash
+
+2025-08-04 * "Fine"
+  Expenses:Food  1.00 USD
+  Assets:Cash
+"#;
+
+    // Invoke
+    let parsed = parse_journal(journal, &LedgerConfig::default());
+
+    // Analysis
+    assert_eq!(parsed.transactions, 3);
+    assert_eq!(parsed.expenses.len(), 1);
+    assert_eq!(parsed.expenses[0].get_description(), "Fine");
+    let lines: Vec<usize> = parsed.warnings.iter().map(|warning| warning.line).collect();
+    assert_eq!(lines, vec![2, 7]);
+}
+
+#[test]
+fn test_tag_for_account_uses_config() {
+    // Setup
+    let mut config = LedgerConfig::default();
+    config.tag_accounts.insert(
+        "Rent/Mortgage".to_string(),
+        "Expenses:Housing:Rent".to_string(),
+    );
+
+    // Invoke
+    let tags = [
+        tag_for_account("Expenses:Housing:Rent", &config),
+        tag_for_account("Expenses:Food:Groceries", &config),
+        tag_for_account("Expenses:Uncategorized", &config),
+        tag_for_account("Expenses", &config),
+    ];
+
+    // Analysis
+    assert_eq!(
+        tags,
+        [
+            Some("Rent/Mortgage".to_string()),
+            Some("Food".to_string()),
+            None,
+            None
+        ]
+    );
+}
+
+#[test]
+fn test_exported_journal_reads_back() {
+    // Setup
+    let mut config = LedgerConfig::default();
+    config
+        .source_accounts
+        .insert("NavyFederal".to_string(), "Assets:Checking".to_string());
+    let mut groceries = Expense::new("Grocer \"big\"".to_string(), 60.0, setup_date(8, 1));
+    groceries.add_tag("Food");
+    groceries.set_source("NavyFederal");
+    let mut salary = Expense::new("Salary".to_string(), -3000.0, setup_date(8, 2));
+    salary.add_tag("Income");
+    salary.set_source("NavyFederal");
+    let expenses = vec![groceries, salary];
+
+    for format in [
+        JournalFormat::Beancount,
+        JournalFormat::Ledger,
+        JournalFormat::Hledger,
+    ] {
+        let mut output = Vec::new();
+        write_journal(&mut output, &expenses, format, &config).unwrap();
+
+        // Invoke
+        let parsed = parse_journal(&String::from_utf8(output).unwrap(), &config);
+
+        // Analysis
+        assert!(parsed.warnings.is_empty(), "{:?}", parsed.warnings);
+        assert_eq!(parsed.expenses, expenses, "{:?}", format);
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::BTreeMap;
+use tauri_app_lib::model::expense::Expense;
+use tauri_app_lib::model::ledger::{JournalFormat, LedgerConfig};
+use tauri_app_lib::service::ledger_service::{
+    account_component, category_account, source_account, validate_ledger_config, write_journal,
+};
+
+mod common;
+use common::{as_stored, setup_expense};
+
+fn render(expenses: &[Expense], format: JournalFormat, config: &LedgerConfig) -> String {
+    let mut output = Vec::new();
+    write_journal(&mut output, expenses, format, config).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn test_account_component_sanitizes_names() {
+    // Setup
+    let names = ["Rent/Mortgage", "Misc.", "one off", "קניות", "NavyFederal"];
+
+    // Invoke
+    let components: Vec<String> = names.iter().map(|name| account_component(name)).collect();
+
+    // Analysis
+    assert_eq!(
+        components,
+        vec!["Rent-Mortgage", "Misc", "One-Off", "Other", "NavyFederal"]
+    );
+}
+
+#[test]
+fn test_accounts_from_tables_and_defaults() {
+    // Setup
+    let mut config = LedgerConfig::default();
+    config
+        .tag_accounts
+        .insert("Food".to_string(), "Expenses:Food:Groceries".to_string());
+    config.source_accounts.insert(
+        "CapitalOne".to_string(),
+        "Liabilities:CapitalOne".to_string(),
+    );
+    let groceries = as_stored(
+        setup_expense("Grocer", 60.0, 8, 1, &["Food"]),
+        "id-1",
+        Some("CapitalOne"),
+    );
+    let salary = as_stored(
+        setup_expense("Salary", -3000.0, 8, 2, &["Income"]),
+        "id-2",
+        Some("NavyFederal"),
+    );
+    let manual = as_stored(setup_expense("Cash", 10.0, 8, 3, &[]), "id-3", None);
+
+    // Invoke
+    let accounts = [
+        (
+            category_account(&groceries, &config),
+            source_account(&groceries, &config),
+        ),
+        (
+            category_account(&salary, &config),
+            source_account(&salary, &config),
+        ),
+        (
+            category_account(&manual, &config),
+            source_account(&manual, &config),
+        ),
+    ];
+
+    // Analysis
+    assert_eq!(
+        accounts[0],
+        (
+            "Expenses:Food:Groceries".to_string(),
+            "Liabilities:CapitalOne".to_string()
+        )
+    );
+    assert_eq!(
+        accounts[1],
+        (
+            "Income:Income".to_string(),
+            "Assets:NavyFederal".to_string()
+        )
+    );
+    assert_eq!(
+        accounts[2],
+        (
+            "Expenses:Uncategorized".to_string(),
+            "Assets:Cash".to_string()
+        )
+    );
+}
+
+#[test]
+fn test_beancount_journal_is_balanced() {
+    // Setup
+    let config = LedgerConfig::default();
+    let expenses = vec![
+        as_stored(
+            setup_expense("Book \"store\"", 25.0, 8, 2, &["Books"]),
+            "id-2",
+            Some("NavyFederal"),
+        ),
+        // Converted from shekels, more decimals than cents
+        as_stored(
+            setup_expense("Falafel", 12.0 / 3.5, 8, 3, &[]),
+            "id-3",
+            None,
+        ),
+    ];
+
+    // Invoke
+    let output = render(&expenses, JournalFormat::Beancount, &config);
+
+    // Analysis
+    assert_eq!(
+        output,
+        "option \"operating_currency\" \"USD\"\n\
+         \n\
+         1970-01-01 open Assets:Cash\n\
+         1970-01-01 open Assets:NavyFederal\n\
+         1970-01-01 open Expenses:Books\n\
+         1970-01-01 open Expenses:Uncategorized\n\
+         \n\
+         2025-08-02 * \"Book \\\"store\\\"\"\n  \
+         id: \"id-2\"\n  \
+         Expenses:Books      25.00 USD\n  \
+         Assets:NavyFederal  -25.00 USD\n\
+         \n\
+         2025-08-03 * \"Falafel\"\n  \
+         id: \"id-3\"\n  \
+         Expenses:Uncategorized  3.43 USD\n  \
+         Assets:Cash             -3.43 USD\n"
+    );
+}
+
+#[test]
+fn test_ledger_and_hledger_dates_and_metadata() {
+    // Setup
+    let config = LedgerConfig::default();
+    let expenses = vec![as_stored(
+        setup_expense("Bakery", 4.5, 8, 5, &["Food"]),
+        "id-5",
+        None,
+    )];
+
+    // Invoke
+    let ledger = render(&expenses, JournalFormat::Ledger, &config);
+    let hledger = render(&expenses, JournalFormat::Hledger, &config);
+
+    // Analysis
+    assert!(ledger.starts_with("\n2025/08/05 * Bakery\n    ; id: id-5\n"));
+    assert!(hledger.starts_with("\n2025-08-05 * Bakery\n    ; id: id-5\n"));
+    assert!(ledger.contains("    Expenses:Food  4.50 USD\n"));
+    assert!(ledger.contains("    Assets:Cash    -4.50 USD\n"));
+}
+
+#[test]
+fn test_transfer_legs_use_clearing_account() {
+    // Setup
+    let config = LedgerConfig::default();
+    let mut payment = as_stored(
+        setup_expense("Card payment", 500.0, 8, 6, &[]),
+        "id-6",
+        Some("NavyFederal"),
+    );
+    payment.set_transfer_id(Some("pair".to_string()));
+
+    // Invoke
+    let account = category_account(&payment, &config);
+
+    // Analysis
+    assert_eq!(account, "Assets:Transfers");
+}
+
+#[test]
+fn test_reexport_is_identical() {
+    // Setup
+    let config = LedgerConfig::default();
+    let expenses = vec![
+        as_stored(setup_expense("Bakery", 4.5, 8, 5, &["Food"]), "id-5", None),
+        as_stored(
+            setup_expense("Grocer", 60.0, 8, 7, &["Food"]),
+            "id-7",
+            Some("Max"),
+        ),
+    ];
+
+    // Invoke
+    let first = render(&expenses, JournalFormat::Beancount, &config);
+    let second = render(&expenses, JournalFormat::Beancount, &config);
+
+    // Analysis
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_invalid_config_is_rejected() {
+    // Setup
+    let invalid_account = LedgerConfig {
+        tag_accounts: BTreeMap::from([("Food".to_string(), "food:groceries".to_string())]),
+        ..LedgerConfig::default()
+    };
+    let invalid_currency = LedgerConfig {
+        currency: "usd".to_string(),
+        ..LedgerConfig::default()
+    };
+
+    // Invoke
+    let results = [
+        validate_ledger_config(&invalid_account),
+        validate_ledger_config(&invalid_currency),
+        validate_ledger_config(&LedgerConfig::default()),
+    ];
+
+    // Analysis
+    assert!(results[0].is_err());
+    assert!(results[1].is_err());
+    assert!(results[2].is_ok());
+}
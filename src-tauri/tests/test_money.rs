@@ -0,0 +1,170 @@
+use tauri_app_lib::definition::csv_definition::SHEKEL_TO_DOLLAR_DIVISION;
+use tauri_app_lib::model::money::{Currency, Money};
+
+#[test]
+fn test_parse_exact_decimals() {
+    // Setup
+    let inputs = ["0.1", "-1234.5", "+7", ".99", "19.999", "0.125"];
+
+    // Invoke
+    let parsed: Vec<i64> = inputs
+        .iter()
+        .map(|text| Money::parse(text, Currency::STORE).unwrap().minor_units())
+        .collect();
+
+    // Analysis
+    assert_eq!(parsed, vec![10, -123450, 700, 99, 2000, 13]);
+}
+
+#[test]
+fn test_parse_rejects_invalid_and_overflowing_amounts() {
+    // Invoke
+    let text = Money::parse("twelve", Currency::STORE);
+    let empty = Money::parse("", Currency::STORE);
+    let huge = Money::parse("1e300", Currency::STORE);
+    let digits = Money::parse("99999999999999999999", Currency::STORE);
+
+    // Analysis
+    assert!(text.is_err());
+    assert!(empty.is_err());
+    assert!(huge.is_err());
+    assert!(digits.is_err());
+}
+
+#[test]
+fn test_parse_scientific_notation() {
+    // Invoke
+    let result = Money::parse("1.5e3", Currency::STORE).unwrap();
+
+    // Analysis
+    assert_eq!(result, Money::from(1500.0));
+}
+
+#[test]
+fn test_display_matches_float_format() {
+    // Setup
+    let amounts = [12.5, 100.0, -0.05, 0.0, 84.1, -3000.0];
+
+    // Invoke
+    let displayed: Vec<String> = amounts
+        .iter()
+        .map(|amount| Money::from(*amount).to_string())
+        .collect();
+
+    // Analysis
+    let expected: Vec<String> = amounts.iter().map(|amount| amount.to_string()).collect();
+    assert_eq!(displayed, expected);
+}
+
+#[test]
+fn test_fixed_string_has_two_decimals() {
+    // Invoke
+    let whole = Money::from(100.0).to_fixed_string();
+    let negative = Money::from(-0.05).to_fixed_string();
+    let half = Money::from(12.5).to_fixed_string();
+
+    // Analysis
+    assert_eq!(whole, "100.00");
+    assert_eq!(negative, "-0.05");
+    assert_eq!(half, "12.50");
+}
+
+#[test]
+fn test_sum_does_not_drift() {
+    // Setup
+    let amounts = [Money::from(0.1); 10];
+
+    // Invoke
+    let total = amounts.iter().sum::<Result<Money, _>>().unwrap();
+    let empty = Vec::<Money>::new().into_iter().sum::<Result<Money, _>>();
+
+    // Analysis
+    assert_eq!(total, Money::from(1.0));
+    assert_eq!(total.to_string(), "1");
+    assert_eq!(empty, Ok(Money::ZERO));
+}
+
+#[test]
+fn test_mixed_currencies_are_not_combined() {
+    // Setup
+    let dollars = Money::from_minor(1000, Currency::Usd);
+    let shekels = Money::from_minor(1000, Currency::Ils);
+
+    // Invoke
+    let added = dollars.checked_add(shekels);
+    let subtracted = shekels.checked_sub(dollars);
+    let total = [dollars, shekels].iter().sum::<Result<Money, _>>();
+    let same = shekels.checked_add(shekels).unwrap();
+
+    // Analysis
+    let error = added.unwrap_err();
+    assert_eq!((error.left, error.right), (Currency::Usd, Currency::Ils));
+    assert!(error.to_string().contains("USD and ILS"));
+    assert!(subtracted.is_err());
+    assert!(total.is_err());
+    assert_eq!(same, Money::from_minor(2000, Currency::Ils));
+}
+
+#[test]
+fn test_divide_rounds_to_nearest_cent() {
+    // Invoke
+    let third = Money::from(10.0).divide(3);
+    let negative_half = Money::from(-0.05).divide(2);
+    let nothing = Money::from(10.0).divide(0);
+
+    // Analysis
+    assert_eq!(third, Money::from(3.33));
+    assert_eq!(negative_half, Money::from(-0.03));
+    assert_eq!(nothing, Money::ZERO);
+}
+
+#[test]
+fn test_convert_shekels_rounds_once() {
+    // Setup
+    let shekels = Money::parse("100", Currency::Ils).unwrap();
+    let dollar_price = Money::from_major(SHEKEL_TO_DOLLAR_DIVISION, Currency::Ils).unwrap();
+
+    // Invoke
+    let dollars = shekels.convert(Currency::STORE, dollar_price);
+    let free = shekels.convert(Currency::STORE, Money::from_minor(0, Currency::Ils));
+    let wrong_price = shekels.convert(Currency::STORE, Money::from(3.5));
+
+    // Analysis
+    assert_eq!(dollars, Some(Money::from(28.57)));
+    assert_eq!(dollars.unwrap().currency(), Currency::STORE);
+    assert_eq!(free, None);
+    assert_eq!(wrong_price, None);
+}
+
+#[test]
+fn test_parse_product_rounds_once() {
+    // Invoke
+    let three = Money::parse_product("0.333", "10", Currency::STORE).unwrap();
+    let fraction = Money::parse_product("0.33", "-0.125", Currency::Ils).unwrap();
+    let invalid = Money::parse_product("0.33", "1e3", Currency::STORE);
+    let huge = Money::parse_product("99999999999", "99999999999", Currency::STORE);
+
+    // Analysis
+    assert_eq!(three, Money::from(3.33));
+    assert_eq!(fraction, Money::from_minor(-4, Currency::Ils));
+    assert!(invalid.is_err());
+    assert!(huge.is_err());
+}
+
+#[test]
+fn test_serde_round_trip_as_number() {
+    // Setup
+    let amount = Money::from(-1234.56);
+
+    // Invoke
+    let json = serde_json::to_string(&amount).unwrap();
+    let read: Money = serde_json::from_str(&json).unwrap();
+    let drifted: Money = serde_json::from_str("0.30000000000000004").unwrap();
+    let shekels = serde_json::to_string(&Money::from_minor(100, Currency::Ils));
+
+    // Analysis
+    assert_eq!(json, "-1234.56");
+    assert_eq!(read, amount);
+    assert_eq!(drifted, Money::from(0.3));
+    assert!(shekels.is_err());
+}
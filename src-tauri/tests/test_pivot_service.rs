@@ -0,0 +1,146 @@
+use tauri_app_lib::model::expense::Expense;
+use tauri_app_lib::model::pivot::{PivotDimension, PivotMeasure};
+use tauri_app_lib::service::pivot_service::{amount_band_labels, pivot, pivot_to_csv};
+
+mod common;
+use common::{as_stored, setup_expense};
+
+/// Helper function to set up a small history for test
+fn setup_expenses() -> Vec<Expense> {
+    vec![
+        as_stored(
+            setup_expense("Test", 20.0, 1, 6, &["Food"]),
+            "1",
+            Some("Amex"),
+        ),
+        as_stored(
+            setup_expense("Test", 30.0, 1, 7, &["Food"]),
+            "2",
+            Some("Amex"),
+        ),
+        as_stored(
+            setup_expense("Test", 900.0, 1, 8, &["Rent"]),
+            "3",
+            Some("NavyFederal"),
+        ),
+        as_stored(
+            setup_expense("Test", 25.0, 2, 3, &["Food"]),
+            "4",
+            Some("CapitalOne"),
+        ),
+    ]
+}
+
+#[test]
+fn test_pivot_tag_by_month_sum() {
+    // Setup
+    let expenses = setup_expenses();
+
+    // Invoke
+    let result = pivot(
+        &expenses,
+        PivotDimension::Tag,
+        PivotDimension::Month,
+        PivotMeasure::Sum,
+        &[10.0, 100.0],
+    )
+    .unwrap();
+
+    // Analysis
+    assert_eq!(result.row_labels, vec!["Food", "Rent"]);
+    assert_eq!(result.column_labels, vec!["2025-01", "2025-02"]);
+    assert_eq!(
+        result.cells,
+        vec![vec![Some(50.0), Some(25.0)], vec![Some(900.0), None]]
+    );
+    assert_eq!(result.row_totals, vec![75.0, 900.0]);
+    assert_eq!(result.column_totals, vec![950.0, 25.0]);
+    assert_eq!(result.grand_total, 975.0);
+}
+
+#[test]
+fn test_pivot_weekday_by_source_avg() {
+    // Setup
+    let expenses = setup_expenses();
+
+    // Invoke
+    let result = pivot(
+        &expenses,
+        PivotDimension::Weekday,
+        PivotDimension::Source,
+        PivotMeasure::Avg,
+        &[10.0, 100.0],
+    )
+    .unwrap();
+
+    // Analysis
+    assert_eq!(result.row_labels, vec!["Mon", "Tue", "Wed"]);
+    assert_eq!(
+        result.column_labels,
+        vec!["Amex", "CapitalOne", "NavyFederal"]
+    );
+    assert_eq!(result.cells[0], vec![Some(20.0), Some(25.0), None]);
+    assert_eq!(result.row_totals[0], 22.5);
+}
+
+#[test]
+fn test_pivot_amount_band_by_year_count() {
+    // Setup
+    let expenses = setup_expenses();
+
+    // Invoke
+    let result = pivot(
+        &expenses,
+        PivotDimension::AmountBand,
+        PivotDimension::Year,
+        PivotMeasure::Count,
+        &[25.0, 100.0],
+    )
+    .unwrap();
+
+    // Analysis
+    assert_eq!(result.row_labels, vec!["<25", "25-100", "100+"]);
+    assert_eq!(result.row_totals, vec![1.0, 2.0, 1.0]);
+    assert_eq!(amount_band_labels(&[25.0, 100.0]), result.row_labels);
+}
+
+#[test]
+fn test_pivot_rejects_unsorted_band_edges() {
+    // Setup
+    let expenses = setup_expenses();
+
+    // Invoke
+    let result = pivot(
+        &expenses,
+        PivotDimension::AmountBand,
+        PivotDimension::Year,
+        PivotMeasure::Count,
+        &[100.0, 25.0],
+    );
+
+    // Analysis
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_pivot_to_csv_includes_totals() {
+    // Setup
+    let expenses = setup_expenses();
+    let table = pivot(
+        &expenses,
+        PivotDimension::Tag,
+        PivotDimension::Month,
+        PivotMeasure::Sum,
+        &[10.0],
+    )
+    .unwrap();
+
+    // Invoke
+    let result = pivot_to_csv(&table).unwrap();
+
+    // Analysis
+    assert_eq!(
+        result,
+        ",2025-01,2025-02,Total\nFood,50,25,75\nRent,900,,900\nTotal,950,25,975\n"
+    );
+}
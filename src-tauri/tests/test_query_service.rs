@@ -0,0 +1,126 @@
+use chrono::NaiveDate;
+use tauri_app_lib::model::expense::Expense;
+use tauri_app_lib::model::money::Money;
+use tauri_app_lib::model::query::{AmountSign, ExpenseFilter, ExpenseQuery, SortField, SortOrder};
+use tauri_app_lib::service::query_service::run_query;
+
+mod common;
+use common::{as_stored, setup_expense};
+
+/// Helper function to set up a small history for test
+fn setup_expenses() -> Vec<Expense> {
+    vec![
+        as_stored(
+            setup_expense("Blue Bottle Coffee", 6.5, 3, 1, &["Food", "Coffee"]),
+            "a",
+            None,
+        ),
+        as_stored(
+            setup_expense("Supermarket", 82.0, 3, 3, &["Food"]),
+            "b",
+            None,
+        ),
+        as_stored(
+            setup_expense("Birthday present", 45.0, 3, 5, &["Gifts"]),
+            "c",
+            None,
+        ),
+        as_stored(
+            setup_expense("Salary", -3200.0, 3, 10, &["Income"]),
+            "d",
+            None,
+        ),
+        as_stored(
+            setup_expense("Blue Bottle Coffee", 7.0, 3, 12, &["Food", "Coffee"]),
+            "e",
+            None,
+        ),
+    ]
+}
+
+#[test]
+fn test_run_query_filters_date_and_tags() {
+    // Setup
+    let expenses = setup_expenses();
+    let query = ExpenseQuery {
+        filter: ExpenseFilter {
+            start_date: NaiveDate::from_ymd_opt(2025, 3, 1),
+            end_date: NaiveDate::from_ymd_opt(2025, 3, 10),
+            tags_any: vec!["Food".to_string(), "Gifts".to_string()],
+            tags_none: vec!["Coffee".to_string()],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    // Invoke
+    let result = run_query(&expenses, &query).unwrap();
+
+    // Analysis
+    let ids: Vec<&str> = result.expenses.iter().map(|e| e.get_id()).collect();
+    assert_eq!(ids, vec!["c", "b"]);
+    assert_eq!(result.total_count, 2);
+    assert_eq!(result.total_amount, Money::from(127.0));
+}
+
+#[test]
+fn test_run_query_text_and_sign() {
+    // Setup
+    let expenses = setup_expenses();
+    let query = ExpenseQuery {
+        filter: ExpenseFilter {
+            text: Some("bottle".to_string()),
+            sign: Some(AmountSign::Positive),
+            min_amount: Some(Money::from(7.0)),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    // Invoke
+    let result = run_query(&expenses, &query).unwrap();
+
+    // Analysis
+    assert_eq!(result.total_count, 1);
+    assert_eq!(result.expenses[0].get_id(), "e");
+}
+
+#[test]
+fn test_run_query_sorts_and_pages() {
+    // Setup
+    let expenses = setup_expenses();
+    let query = ExpenseQuery {
+        sort_by: SortField::Amount,
+        sort_order: SortOrder::Asc,
+        page: 1,
+        page_size: Some(2),
+        ..Default::default()
+    };
+
+    // Invoke
+    let result = run_query(&expenses, &query).unwrap();
+
+    // Analysis
+    let ids: Vec<&str> = result.expenses.iter().map(|e| e.get_id()).collect();
+    assert_eq!(ids, vec!["e", "c"]);
+    assert_eq!(result.total_count, 5);
+}
+
+#[test]
+fn test_run_query_invalid_regex() {
+    // Setup
+    let expenses = setup_expenses();
+    let query = ExpenseQuery {
+        filter: ExpenseFilter {
+            regex: Some("(unclosed".to_string()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    // Invoke
+    let result = run_query(&expenses, &query);
+
+    // Analysis
+    assert!(result.is_err());
+}
@@ -0,0 +1,261 @@
+use chrono::NaiveDate;
+use serde_json::{json, Map, Value};
+use tauri_app_lib::definition::csv_definition::SHEKEL_TO_DOLLAR_DIVISION;
+use tauri_app_lib::model::expense::Expense;
+use tauri_app_lib::model::money::{Currency, Money};
+use tauri_app_lib::store::app_store::ExpenseStore;
+use tauri_app_lib::store::backend::StorageBackend;
+use tauri_app_lib::store::memory_backend::MemoryBackend;
+use tauri_app_lib::store::schema::{
+    migrate, schema_version, SCHEMA_VERSION, SCHEMA_VERSION_SUB_KEY,
+};
+
+/// Helper function to set up an expense as stored by older versions
+fn setup_expense_value(id: &str, description: &str) -> Value {
+    json!({
+        "id": id,
+        "description": description,
+        "amount": 12.5,
+        "tags": [],
+        "date": "2025-05-02T00:00:00"
+    })
+}
+
+fn setup_data(value: Value) -> Map<String, Value> {
+    value.as_object().unwrap().clone()
+}
+
+#[test]
+fn test_migrate_unversioned_data() {
+    // Setup
+    let mut data = setup_data(json!({
+        "expenses": { "a": setup_expense_value("a", "Bookstore") },
+        "forecast_config": { "months": 3 }
+    }));
+
+    // Invoke
+    let report = migrate(&mut data, false).unwrap();
+
+    // Analysis
+    assert_eq!(report.from_version, 0);
+    assert_eq!(report.to_version, SCHEMA_VERSION);
+    assert_eq!(report.steps.len(), SCHEMA_VERSION as usize);
+    assert_eq!(schema_version(&data).unwrap(), SCHEMA_VERSION);
+    assert_eq!(data["forecast_config"], json!({ "months": 3 }));
+    assert!(report.keys.contains(&SCHEMA_VERSION_SUB_KEY.to_string()));
+}
+
+#[test]
+fn test_migrate_expense_list_to_map() {
+    // Setup
+    let mut data = setup_data(json!({
+        "expenses": [setup_expense_value("a", "Bookstore"), setup_expense_value("b", "Bakery")]
+    }));
+
+    // Invoke
+    let report = migrate(&mut data, false).unwrap();
+
+    // Analysis
+    assert_eq!(report.steps[0].changes.len(), 1);
+    let expenses = data["expenses"].as_object().unwrap();
+    assert_eq!(expenses.len(), 2);
+    assert_eq!(expenses["b"]["description"], "Bakery");
+}
+
+#[test]
+fn test_migrate_rekeys_mismatched_expense() {
+    // Setup
+    let mut data = setup_data(json!({
+        "expenses": { "old-key": setup_expense_value("a", "Bookstore") }
+    }));
+
+    // Invoke
+    let report = migrate(&mut data, false).unwrap();
+
+    // Analysis
+    assert_eq!(
+        report.steps[0].changes,
+        vec!["Moved expense stored under old-key to its id a".to_string()]
+    );
+    assert!(data["expenses"].get("a").is_some());
+    assert!(data["expenses"].get("old-key").is_none());
+}
+
+#[test]
+fn test_dry_run_leaves_data_untouched() {
+    // Setup
+    let mut data = setup_data(json!({
+        "expenses": [setup_expense_value("a", "Bookstore")]
+    }));
+    let original = data.clone();
+
+    // Invoke
+    let report = migrate(&mut data, true).unwrap();
+
+    // Analysis
+    assert!(report.dry_run);
+    assert_eq!(report.steps.len(), SCHEMA_VERSION as usize);
+    assert!(report.keys.contains(&"expenses".to_string()));
+    assert_eq!(data, original);
+}
+
+#[test]
+fn test_current_data_needs_no_migration() {
+    // Setup
+    let mut data = setup_data(json!({ "expenses": {} }));
+    migrate(&mut data, false).unwrap();
+
+    // Invoke
+    let report = migrate(&mut data, false).unwrap();
+
+    // Analysis
+    assert!(report.steps.is_empty());
+    assert!(report.keys.is_empty());
+}
+
+#[test]
+fn test_newer_schema_is_rejected() {
+    // Setup
+    let mut data = setup_data(json!({ "schema_version": SCHEMA_VERSION + 1 }));
+
+    // Invoke
+    let result = migrate(&mut data, false);
+
+    // Analysis
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_store_migration_commits_and_loads_expenses() {
+    // Setup
+    let backend = MemoryBackend::new();
+    backend
+        .write_data(&json!({
+            "expenses": [setup_expense_value("a", "Bookstore")]
+        }))
+        .unwrap();
+    let store = ExpenseStore::new(backend);
+
+    // Invoke
+    let dry_run = store.migrate_schema(true).unwrap();
+    let before = store.get_all_expenses().unwrap();
+    let report = store.migrate_schema(false).unwrap();
+
+    // Analysis
+    assert_eq!(dry_run.keys, report.keys);
+    assert!(before.is_empty());
+    let expenses = store.get_all_expenses().unwrap();
+    assert_eq!(expenses.len(), 1);
+    assert_eq!(expenses[0].get_description(), "Bookstore");
+    assert_eq!(
+        store.get_json_value(SCHEMA_VERSION_SUB_KEY).unwrap(),
+        Some(json!(SCHEMA_VERSION))
+    );
+}
+
+#[test]
+fn test_migrate_keeps_hash_ids_as_fingerprints() {
+    // Setup
+    let mut fingerprinted = setup_expense_value("b", "Bakery");
+    fingerprinted["fingerprint"] = json!("original");
+    let mut data = setup_data(json!({
+        "schema_version": 1,
+        "expenses": { "a": setup_expense_value("a", "Bookstore"), "b": fingerprinted }
+    }));
+
+    // Invoke
+    let report = migrate(&mut data, false).unwrap();
+
+    // Analysis
+    assert_eq!(report.steps.len(), 3);
+    assert_eq!(report.steps[0].version, 2);
+    assert_eq!(report.steps[0].changes.len(), 1);
+    assert_eq!(data["expenses"]["a"]["id"], "a");
+    assert_eq!(data["expenses"]["a"]["fingerprint"], "a");
+    assert_eq!(data["expenses"]["b"]["fingerprint"], "original");
+}
+
+#[test]
+fn test_migrate_rounds_amounts_to_cents() {
+    // Setup
+    let mut drifted = setup_expense_value("b", "Bakery");
+    drifted["amount"] = json!(0.30000000000000004);
+    let mut data = setup_data(json!({
+        "schema_version": 2,
+        "expenses": { "a": setup_expense_value("a", "Bookstore"), "b": drifted }
+    }));
+
+    // Invoke
+    let report = migrate(&mut data, false).unwrap();
+
+    // Analysis
+    assert_eq!(report.steps.len(), 2);
+    assert_eq!(report.steps[0].version, 3);
+    assert_eq!(report.steps[0].changes.len(), 1);
+    assert_eq!(data["expenses"]["a"]["amount"], json!(12.5));
+    assert_eq!(data["expenses"]["b"]["amount"], json!(0.3));
+}
+
+#[test]
+fn test_migrate_rejects_missing_amount() {
+    // Setup
+    let mut broken = setup_expense_value("a", "Bookstore");
+    broken.as_object_mut().unwrap().remove("amount");
+    let mut data = setup_data(json!({
+        "schema_version": 2,
+        "expenses": { "a": broken }
+    }));
+
+    // Invoke
+    let result = migrate(&mut data, false);
+
+    // Analysis
+    assert!(result.is_err());
+    assert_eq!(schema_version(&data).unwrap(), 2);
+}
+
+#[test]
+fn test_migrated_shekel_rows_are_found_again_on_import() {
+    // Setup
+    let amount = 100.0 / SHEKEL_TO_DOLLAR_DIVISION;
+    let identity = format!("Falafel:2025-05-02 00:00:00:{}", amount);
+    let mut expenses = Map::new();
+    for (id, input) in [("a", identity.clone()), ("b", format!("{}#1", identity))] {
+        let mut expense = setup_expense_value(id, "Falafel");
+        expense["amount"] = json!(amount);
+        expense["fingerprint"] = json!(blake3::hash(input.as_bytes()).to_hex().to_string());
+        expenses.insert(id.to_string(), expense);
+    }
+    let store = ExpenseStore::new(MemoryBackend::with_data(json!({
+        "schema_version": 2,
+        "expenses": expenses
+    })));
+    store.migrate_schema(false).unwrap();
+
+    // Invoke
+    let dollar_price = Money::from_major(SHEKEL_TO_DOLLAR_DIVISION, Currency::Ils).unwrap();
+    let converted = Money::from_minor(10000, Currency::Ils)
+        .convert(Currency::STORE, dollar_price)
+        .unwrap();
+    let date = NaiveDate::from_ymd_opt(2025, 5, 2)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    let reimported = vec![
+        Expense::new("Falafel".to_string(), converted, date),
+        Expense::new("Falafel".to_string(), converted, date),
+    ];
+    let result = store.add_expense_as_batch(reimported, false).unwrap();
+
+    // Analysis
+    assert_eq!(result.added_count, 0);
+    assert_eq!(result.duplicate_count, 2);
+    assert_eq!(
+        store
+            .get_expense(&"a".to_string())
+            .unwrap()
+            .unwrap()
+            .get_amount(),
+        Money::from(28.57)
+    );
+}
@@ -0,0 +1,191 @@
+use chrono::NaiveDate;
+use tauri_app_lib::model::money::Money;
+use tauri_app_lib::service::search_parser::{parse_search_query, Comparison, SearchTerm};
+
+mod common;
+use common::setup_expense;
+
+#[test]
+fn test_parse_search_query_full_example() {
+    // Setup
+    let input = "tag:Food -tag:Gifts amount>50 date:2025-01..2025-03 \"blue bottle\"";
+
+    // Invoke
+    let result = parse_search_query(input).unwrap();
+
+    // Analysis
+    assert_eq!(
+        result.terms,
+        vec![
+            SearchTerm::Tag("Food".to_string()),
+            SearchTerm::Not(Box::new(SearchTerm::Tag("Gifts".to_string()))),
+            SearchTerm::Amount(Comparison::Gt, Money::from(50.0)),
+            SearchTerm::Date(
+                NaiveDate::from_ymd_opt(2025, 1, 1),
+                NaiveDate::from_ymd_opt(2025, 3, 31)
+            ),
+            SearchTerm::Text("blue bottle".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_search_query_amount_forms() {
+    // Setup
+    let input = "amount>=10 amount<=-5 amount:12.5 amount:10..50";
+
+    // Invoke
+    let result = parse_search_query(input).unwrap();
+
+    // Analysis
+    assert_eq!(
+        result.terms,
+        vec![
+            SearchTerm::Amount(Comparison::Ge, Money::from(10.0)),
+            SearchTerm::Amount(Comparison::Le, Money::from(-5.0)),
+            SearchTerm::Amount(Comparison::Eq, Money::from(12.5)),
+            SearchTerm::AmountRange(Money::from(10.0), Money::from(50.0)),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_search_query_open_and_single_dates() {
+    // Setup
+    let input = "date:2024 date:..2024-02 date:2025-03-15..";
+
+    // Invoke
+    let result = parse_search_query(input).unwrap();
+
+    // Analysis
+    assert_eq!(
+        result.terms,
+        vec![
+            SearchTerm::Date(
+                NaiveDate::from_ymd_opt(2024, 1, 1),
+                NaiveDate::from_ymd_opt(2024, 12, 31)
+            ),
+            SearchTerm::Date(None, NaiveDate::from_ymd_opt(2024, 2, 29)),
+            SearchTerm::Date(NaiveDate::from_ymd_opt(2025, 3, 15), None),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_search_query_invalid_date_position() {
+    // Setup
+    let input = "tag:Food date:2025-01..2025-02-29";
+
+    // Invoke
+    let result = parse_search_query(input);
+
+    // Analysis
+    let error = result.unwrap_err();
+    assert_eq!(
+        error.position, 23,
+        "The error points at the invalid end date"
+    );
+}
+
+#[test]
+fn test_parse_search_query_hebrew_text() {
+    // Setup
+    let input = "סופר \"רמי לוי\" tag:אוכל";
+
+    // Invoke
+    let result = parse_search_query(input).unwrap();
+
+    // Analysis
+    assert_eq!(
+        result.terms,
+        vec![
+            SearchTerm::Text("סופר".to_string()),
+            SearchTerm::Text("רמי לוי".to_string()),
+            SearchTerm::Tag("אוכל".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_search_query_unknown_field_position() {
+    // Setup
+    let input = "tag:Food colour:red";
+
+    // Invoke
+    let result = parse_search_query(input);
+
+    // Analysis
+    let error = result.unwrap_err();
+    assert_eq!(error.position, 9);
+    assert!(error.message.contains("colour"));
+}
+
+#[test]
+fn test_parse_search_query_unterminated_quote_position() {
+    // Setup
+    let input = "אוכל \"blue bottle";
+
+    // Invoke
+    let result = parse_search_query(input);
+
+    // Analysis
+    assert_eq!(
+        result.unwrap_err().position,
+        5,
+        "Positions count characters"
+    );
+}
+
+#[test]
+fn test_parse_search_query_invalid_values() {
+    // Invoke
+    let invalid_amount = parse_search_query("amount>abc").unwrap_err();
+    let empty_tag = parse_search_query("tag: Food").unwrap_err();
+    let comparison_on_tag = parse_search_query("tag>5").unwrap_err();
+    let lone_dash = parse_search_query("Food - Gifts").unwrap_err();
+
+    // Analysis
+    assert_eq!(invalid_amount.position, 7);
+    assert_eq!(empty_tag.position, 4);
+    assert_eq!(comparison_on_tag.position, 0);
+    assert_eq!(lone_dash.position, 5);
+}
+
+#[test]
+fn test_parse_search_query_non_finite_amounts() {
+    // Invoke
+    let errors: Vec<usize> = [
+        "amount>nan",
+        "amount:inf",
+        "amount<=-infinity",
+        "amount:1e400",
+        "amount:99999999999999999999",
+        "amount:1..NaN",
+    ]
+    .iter()
+    .map(|input| parse_search_query(input).unwrap_err().position)
+    .collect();
+
+    // Analysis
+    assert_eq!(errors, vec![7, 7, 8, 7, 7, 10]);
+}
+
+#[test]
+fn test_search_query_matches_expense() {
+    // Setup
+    let query =
+        parse_search_query("tag:food -tag:Gifts amount>50 date:2025-01..2025-03 \"blue\"").unwrap();
+    let matching = setup_expense("Blue Bottle catering", 120.0, 2, 14, &["Food"]);
+    let gift = setup_expense("Blue Bottle gift card", 120.0, 2, 14, &["Food", "Gifts"]);
+    let small = setup_expense("Blue Bottle", 6.5, 2, 14, &["Food"]);
+
+    // Invoke
+    let results = [
+        query.matches(&matching),
+        query.matches(&gift),
+        query.matches(&small),
+    ];
+
+    // Analysis
+    assert_eq!(results, [true, false, false]);
+}
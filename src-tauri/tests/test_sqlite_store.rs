@@ -0,0 +1,227 @@
+use serde_json::json;
+use std::path::Path;
+use tauri_app_lib::store::app_store::ExpenseStore;
+use tauri_app_lib::store::backend::StorageBackend;
+use tauri_app_lib::store::settings::{BackendKind, StoreSettings, STORE_SETTINGS_FILE};
+use tauri_app_lib::store::sqlite_store::SqliteStore;
+use tempfile::tempdir;
+
+mod common;
+use common::{as_stored, setup_expense};
+
+#[test]
+fn test_open_applies_all_migrations() {
+    // Setup
+    let db = SqliteStore::open_in_memory().unwrap();
+
+    // Invoke
+    let version = db.schema_version().unwrap();
+
+    // Analysis
+    assert_eq!(version, 2);
+}
+
+#[test]
+fn test_audit_log_is_not_replaced_with_the_data() {
+    // Setup
+    let store = ExpenseStore::new(SqliteStore::open_in_memory().unwrap());
+    store
+        .add_expense_as_batch(
+            vec![as_stored(
+                setup_expense("Expense first", 12.5, 3, 10, &[]),
+                "first",
+                None,
+            )],
+            false,
+        )
+        .unwrap();
+    let snapshot = store.get_all_store_data().unwrap();
+    let mut edited = store.get_all_expenses().unwrap().remove(0);
+    let id = edited.get_id().to_string();
+    edited.add_tag("Food");
+    store.update_expense(id.clone(), edited).unwrap();
+
+    // Invoke
+    store.replace_all_data(snapshot).unwrap();
+
+    // Analysis
+    let ids: Vec<u64> = store
+        .get_expense_history(&id)
+        .unwrap()
+        .iter()
+        .map(|entry| entry.id)
+        .collect();
+    // The restore is logged after the entries already there
+    assert_eq!(ids, vec![3, 2, 1]);
+}
+
+#[test]
+fn test_apply_changes_upserts_and_removes() {
+    // Setup
+    let db = SqliteStore::open_in_memory().unwrap();
+    let first = as_stored(
+        setup_expense("Expense first", 12.5, 3, 10, &["Food"]),
+        "first",
+        None,
+    );
+    let second = as_stored(
+        setup_expense("Expense second", 40.0, 3, 10, &[]),
+        "second",
+        None,
+    );
+    db.apply_changes(&[&first, &second], &[], &[]).unwrap();
+
+    let mut updated = first.clone();
+    updated.add_tag("Dining");
+
+    // Invoke
+    db.apply_changes(&[&updated], &["second".to_string()], &[])
+        .unwrap();
+    let result = db.load_expenses().unwrap().unwrap();
+
+    // Analysis
+    assert_eq!(result.len(), 1);
+    assert_eq!(result["first"].get_tags(), &vec!["Food", "Dining"]);
+}
+
+#[test]
+fn test_set_and_get_value() {
+    // Setup
+    let db = SqliteStore::open_in_memory().unwrap();
+
+    // Invoke
+    db.set_value("forecast_config", json!({ "months": 6 }))
+        .unwrap();
+    let result = db.get_value("forecast_config").unwrap();
+    let missing = db.get_value("grants").unwrap();
+
+    // Analysis
+    assert_eq!(result, Some(json!({ "months": 6 })));
+    assert_eq!(missing, None);
+}
+
+fn select_backend(dir: &Path, backend: BackendKind) {
+    let path = dir.join(STORE_SETTINGS_FILE);
+    let mut settings = StoreSettings::load(&path).unwrap();
+    settings.backend = backend;
+    settings.save(&path).unwrap();
+}
+
+#[test]
+fn test_switching_backends_copies_data_both_ways() {
+    // Setup
+    let dir = tempdir().unwrap();
+    let json_store = ExpenseStore::open_selected_backend(dir.path()).unwrap();
+    json_store
+        .add_expense_as_batch(
+            vec![as_stored(
+                setup_expense("Expense first", 12.5, 3, 10, &[]),
+                "first",
+                None,
+            )],
+            false,
+        )
+        .unwrap();
+    drop(json_store);
+
+    // Invoke
+    select_backend(dir.path(), BackendKind::Sqlite);
+    let sqlite_store = ExpenseStore::open_selected_backend(dir.path()).unwrap();
+    let copied_to_sqlite = sqlite_store.get_all_expenses().unwrap().len();
+    sqlite_store
+        .add_expense_as_batch(
+            vec![as_stored(
+                setup_expense("Expense second", 40.0, 3, 10, &[]),
+                "second",
+                None,
+            )],
+            false,
+        )
+        .unwrap();
+    drop(sqlite_store);
+
+    select_backend(dir.path(), BackendKind::Json);
+    let json_store = ExpenseStore::open_selected_backend(dir.path()).unwrap();
+    let copied_to_json = json_store.get_all_expenses().unwrap().len();
+    json_store
+        .add_expense_as_batch(
+            vec![as_stored(
+                setup_expense("Expense third", 7.0, 3, 10, &[]),
+                "third",
+                None,
+            )],
+            false,
+        )
+        .unwrap();
+    drop(json_store);
+
+    select_backend(dir.path(), BackendKind::Sqlite);
+    let sqlite_store = ExpenseStore::open_selected_backend(dir.path()).unwrap();
+
+    // Analysis
+    assert_eq!(copied_to_sqlite, 1);
+    assert_eq!(copied_to_json, 2);
+    assert_eq!(sqlite_store.backend().name(), "sqlite");
+    assert_eq!(sqlite_store.get_all_expenses().unwrap().len(), 3);
+    let audit_log = sqlite_store.backend().audit_log().unwrap();
+    assert_eq!(audit_log.read_entries().unwrap().len(), 3);
+    let settings = StoreSettings::load(&dir.path().join(STORE_SETTINGS_FILE)).unwrap();
+    assert_eq!(settings.active_backend, BackendKind::Sqlite);
+}
+
+#[test]
+fn test_reopening_same_backend_does_not_copy() {
+    // Setup
+    let dir = tempdir().unwrap();
+    select_backend(dir.path(), BackendKind::Sqlite);
+    let sqlite_store = ExpenseStore::open_selected_backend(dir.path()).unwrap();
+    sqlite_store
+        .add_expense_as_batch(
+            vec![as_stored(
+                setup_expense("Expense first", 12.5, 3, 10, &[]),
+                "first",
+                None,
+            )],
+            false,
+        )
+        .unwrap();
+    drop(sqlite_store);
+
+    // Invoke
+    let reopened = ExpenseStore::open_selected_backend(dir.path()).unwrap();
+
+    // Analysis
+    assert_eq!(reopened.get_all_expenses().unwrap().len(), 1);
+}
+
+#[test]
+fn test_encrypted_json_store_is_not_copied_to_sqlite() {
+    // Setup
+    let dir = tempdir().unwrap();
+    let json_store = ExpenseStore::open_selected_backend(dir.path()).unwrap();
+    json_store
+        .add_expense_as_batch(
+            vec![as_stored(
+                setup_expense("Expense first", 12.5, 3, 10, &[]),
+                "first",
+                None,
+            )],
+            false,
+        )
+        .unwrap();
+    json_store
+        .with_encryption(|encryption| encryption.enable_encryption("correct horse battery"))
+        .unwrap();
+    drop(json_store);
+
+    // Invoke
+    select_backend(dir.path(), BackendKind::Sqlite);
+    let reopened = ExpenseStore::open_selected_backend(dir.path()).unwrap();
+
+    // Analysis
+    assert_eq!(reopened.backend().name(), "json-file");
+    assert!(reopened.is_locked());
+    let settings = StoreSettings::load(&dir.path().join(STORE_SETTINGS_FILE)).unwrap();
+    assert_eq!(settings.backend, BackendKind::Json);
+    assert_eq!(settings.active_backend, BackendKind::Json);
+}
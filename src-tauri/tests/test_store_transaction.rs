@@ -0,0 +1,304 @@
+use serde_json::json;
+use std::sync::Arc;
+use std::thread;
+use tauri_app_lib::model::expense::Expense;
+use tauri_app_lib::store::app_store::ExpenseStore;
+use tauri_app_lib::store::backend::StorageBackend;
+use tauri_app_lib::store::json_file_backend::{write_atomically, JsonFileBackend};
+use tauri_app_lib::store::memory_backend::MemoryBackend;
+use tauri_app_lib::store::transaction::ConflictError;
+use tempfile::tempdir;
+
+mod common;
+use common::{setup_date, setup_expense};
+
+#[test]
+fn test_transaction_error_rolls_back() {
+    // Setup
+    let store = ExpenseStore::new(MemoryBackend::new());
+    store
+        .add_expense_as_batch(vec![setup_expense("Coffee", 4.5, 4, 1, &[])], false)
+        .unwrap();
+    let stored = store.get_all_expenses().unwrap().remove(0);
+
+    // Invoke
+    let result: Result<(), _> = store.transaction(|tx| {
+        tx.remove(stored.get_id());
+        tx.set_value("forecast_config", json!({ "months": 6 }))?;
+        Err("Step failed".into())
+    });
+
+    // Analysis
+    assert!(result.is_err());
+    assert!(store.exists(&stored.get_id().to_string()).unwrap());
+    assert_eq!(store.get_json_value("forecast_config").unwrap(), None);
+    assert_eq!(store.backend().load_expenses().unwrap().unwrap().len(), 1);
+}
+
+#[test]
+fn test_transaction_stages_changes_over_the_cache() {
+    // Setup
+    let store = ExpenseStore::new(MemoryBackend::new());
+    store
+        .add_expense_as_batch(
+            vec![
+                setup_expense("Rent", 1500.0, 4, 1, &["Housing"]),
+                setup_expense("Coffee", 4.5, 4, 2, &["Food"]),
+            ],
+            false,
+        )
+        .unwrap();
+    let rent = store.get_expenses_with_tag("Housing").unwrap().remove(0);
+    let coffee = store.get_expenses_with_tag("Food").unwrap().remove(0);
+
+    // Invoke
+    let seen = store
+        .transaction(|tx| {
+            tx.remove(rent.get_id());
+            tx.update(coffee.get_id(), |expense| expense.add_tag("Treats"));
+            tx.upsert(setup_expense("Bakery", 3.0, 4, 3, &["Food"]));
+            Ok(tx.expenses().count())
+        })
+        .unwrap();
+
+    // Analysis
+    assert_eq!(seen, 2);
+    assert!(store.get_expenses_with_tag("Housing").unwrap().is_empty());
+    assert_eq!(store.get_expenses_with_tag("Treats").unwrap().len(), 1);
+    assert_eq!(store.get_expenses_with_tag("Food").unwrap().len(), 2);
+    let dates: Vec<String> = store
+        .get_expenses_between(setup_date(4, 1), setup_date(4, 30))
+        .unwrap()
+        .iter()
+        .map(|expense| expense.get_description().to_string())
+        .collect();
+    assert_eq!(dates, vec!["Coffee", "Bakery"]);
+    assert_eq!(store.backend().load_expenses().unwrap().unwrap().len(), 2);
+}
+
+#[test]
+fn test_update_bulk_expenses_is_all_or_nothing() {
+    // Setup
+    let store = ExpenseStore::new(MemoryBackend::new());
+    store
+        .add_expense_as_batch(vec![setup_expense("Rent", 1500.0, 4, 1, &[])], false)
+        .unwrap();
+    let mut edited = store.get_all_expenses().unwrap().remove(0);
+    edited.add_tag("Housing");
+
+    // Invoke
+    let result = store.update_bulk_expenses(
+        vec![edited.get_id().to_string(), "missing".to_string()],
+        vec![edited.clone(), edited.clone()],
+    );
+
+    // Analysis
+    assert!(result.is_err());
+    let stored = store
+        .get_expense(&edited.get_id().to_string())
+        .unwrap()
+        .unwrap();
+    assert!(stored.get_tags().is_empty());
+}
+
+#[test]
+fn test_import_values_commits_once() {
+    // Setup
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("store.json");
+    let store = ExpenseStore::new(JsonFileBackend::open(&path).unwrap());
+    store
+        .add_expense_as_batch(vec![setup_expense("Old", 10.0, 4, 1, &[])], false)
+        .unwrap();
+
+    let mut imported = setup_expense("Imported", 20.0, 4, 2, &[]);
+    imported.set_id("imported-id");
+    let values = json!({
+        "expenses": { "imported-id": imported },
+        "forecast_config": { "months": 3 },
+    });
+
+    // Invoke
+    let mut keys = store
+        .import_values(values.as_object().unwrap().clone())
+        .unwrap();
+
+    // Analysis
+    keys.sort();
+    assert_eq!(keys, vec!["expenses", "forecast_config"]);
+    let expenses = store.get_all_expenses().unwrap();
+    assert_eq!(expenses.len(), 1);
+    assert_eq!(expenses[0].get_description(), "Imported");
+
+    let reopened = JsonFileBackend::open(&path).unwrap();
+    assert_eq!(
+        reopened.get_value("forecast_config").unwrap(),
+        Some(json!({ "months": 3 }))
+    );
+    assert_eq!(reopened.load_expenses().unwrap().unwrap().len(), 1);
+}
+
+#[test]
+fn test_import_values_invalid_key_leaves_store_untouched() {
+    // Setup
+    let store = ExpenseStore::new(MemoryBackend::new());
+    store
+        .add_expense_as_batch(vec![setup_expense("Old", 10.0, 4, 1, &[])], false)
+        .unwrap();
+    let values = json!({
+        "forecast_config": { "months": 3 },
+        "expenses": "not a map",
+    });
+
+    // Invoke
+    let result = store.import_values(values.as_object().unwrap().clone());
+
+    // Analysis
+    assert!(result.is_err());
+    assert_eq!(store.get_all_expenses().unwrap().len(), 1);
+    assert_eq!(store.get_json_value("forecast_config").unwrap(), None);
+}
+
+#[test]
+fn test_write_atomically_replaces_file() {
+    // Setup
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("store.json");
+    std::fs::write(&path, "old content").unwrap();
+
+    // Invoke
+    write_atomically(&path, b"new content").unwrap();
+
+    // Analysis
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "new content");
+    let entries = std::fs::read_dir(dir.path()).unwrap().count();
+    assert_eq!(entries, 1, "no temporary file may be left behind");
+}
+
+#[test]
+fn test_update_expense_bumps_revision() {
+    // Setup
+    let store = ExpenseStore::new(MemoryBackend::new());
+    store
+        .add_expense_as_batch(vec![setup_expense("Gym", 40.0, 4, 3, &[])], false)
+        .unwrap();
+    let mut edited = store.get_all_expenses().unwrap().remove(0);
+    edited.add_tag("Health");
+
+    // Invoke
+    store
+        .update_expense(edited.get_id().to_string(), edited.clone())
+        .unwrap();
+
+    // Analysis
+    let stored = store
+        .get_expense(&edited.get_id().to_string())
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.get_revision(), edited.get_revision() + 1);
+}
+
+#[test]
+fn test_update_expense_rejects_stale_revision() {
+    // Setup
+    let store = ExpenseStore::new(MemoryBackend::new());
+    store
+        .add_expense_as_batch(vec![setup_expense("Gym", 40.0, 4, 3, &[])], false)
+        .unwrap();
+    let read_by_first = store.get_all_expenses().unwrap().remove(0);
+    let mut read_by_second = read_by_first.clone();
+
+    let mut first_edit = read_by_first.clone();
+    first_edit.add_tag("Health");
+    store
+        .update_expense(first_edit.get_id().to_string(), first_edit)
+        .unwrap();
+
+    // Invoke
+    read_by_second.add_tag("Leisure");
+    let result = store.update_expense(read_by_second.get_id().to_string(), read_by_second);
+
+    // Analysis
+    let err = result.unwrap_err();
+    let conflict = err.downcast_ref::<ConflictError>().unwrap();
+    assert_eq!(conflict.ids, vec![read_by_first.get_id().to_string()]);
+    let stored = store
+        .get_expense(&read_by_first.get_id().to_string())
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.get_tags(), &vec!["Health"]);
+}
+
+#[test]
+fn test_update_bulk_expenses_rejects_stale_revision() {
+    // Setup
+    let store = ExpenseStore::new(MemoryBackend::new());
+    store
+        .add_expense_as_batch(
+            vec![
+                setup_expense("Gym", 40.0, 4, 3, &[]),
+                setup_expense("Pool", 12.0, 4, 4, &[]),
+            ],
+            false,
+        )
+        .unwrap();
+    let snapshot = store.get_all_expenses().unwrap();
+    let mut fresh = snapshot[0].clone();
+    fresh.add_tag("Health");
+    store
+        .update_expense(fresh.get_id().to_string(), fresh)
+        .unwrap();
+
+    let hashes: Vec<String> = snapshot.iter().map(|e| e.get_id().to_string()).collect();
+    let edits: Vec<Expense> = snapshot
+        .iter()
+        .map(|e| {
+            let mut e = e.clone();
+            e.add_tag("Sport");
+            e
+        })
+        .collect();
+
+    // Invoke
+    let result = store.update_bulk_expenses(hashes, edits);
+
+    // Analysis
+    let err = result.unwrap_err();
+    assert!(err.downcast_ref::<ConflictError>().is_some());
+    let untouched = store
+        .get_expense(&snapshot[1].get_id().to_string())
+        .unwrap()
+        .unwrap();
+    assert!(untouched.get_tags().is_empty());
+}
+
+#[test]
+fn test_concurrent_transactions_keep_every_write() {
+    // Setup
+    let store = Arc::new(ExpenseStore::new(MemoryBackend::new()));
+
+    // Invoke
+    let handles: Vec<_> = (0..8)
+        .map(|worker| {
+            let store = Arc::clone(&store);
+            thread::spawn(move || {
+                for day in 1..=10 {
+                    let description = format!("Worker {}", worker);
+                    store
+                        .add_expense_as_batch(
+                            vec![setup_expense(&description, 1.0, 4, day, &[])],
+                            false,
+                        )
+                        .unwrap();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    // Analysis
+    assert_eq!(store.get_all_expenses().unwrap().len(), 80);
+    assert_eq!(store.backend().load_expenses().unwrap().unwrap().len(), 80);
+}
@@ -0,0 +1,259 @@
+use tauri_app_lib::model::ledger::LedgerConfig;
+use tauri_app_lib::model::money::Money;
+use tauri_app_lib::service::ledger_service::{parse_ledger_config, LEDGER_CONFIG_SUB_KEY};
+use tauri_app_lib::service::tag_service::replace_tags;
+use tauri_app_lib::store::app_store::ExpenseStore;
+use tauri_app_lib::store::command_context::run_as_command;
+use tauri_app_lib::store::memory_backend::MemoryBackend;
+
+mod common;
+use common::setup_expense;
+
+fn setup_store() -> ExpenseStore<MemoryBackend> {
+    let store = ExpenseStore::new(MemoryBackend::new());
+    store
+        .add_expense_as_batch(
+            vec![
+                setup_expense("Pizza place", 30.5, 4, 1, &["Resturant", "Dining"]),
+                setup_expense("Bistro", 40.0, 4, 2, &["Restaurant"]),
+                setup_expense("Cafe", 4.5, 4, 3, &["Resturant", "Restaurant"]),
+                setup_expense("Salary", -3000.0, 4, 4, &["Income"]),
+            ],
+            false,
+        )
+        .unwrap();
+    store
+}
+
+fn tags_of(store: &ExpenseStore<MemoryBackend>, description: &str) -> Vec<String> {
+    store
+        .get_all_expenses()
+        .unwrap()
+        .into_iter()
+        .find(|expense| expense.get_description() == description)
+        .unwrap()
+        .get_tags()
+        .clone()
+}
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+#[test]
+fn test_list_tags_with_counts_and_totals() {
+    // Setup
+    let store = setup_store();
+
+    // Invoke
+    let tags = store.list_tags().unwrap();
+
+    // Analysis
+    let summary: Vec<(&str, usize, Money)> = tags
+        .iter()
+        .map(|tag| (tag.tag.as_str(), tag.count, tag.total))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("Dining", 1, Money::from(30.5)),
+            ("Income", 1, Money::from(-3000.0)),
+            ("Restaurant", 2, Money::from(44.5)),
+            ("Resturant", 2, Money::from(35.0)),
+        ]
+    );
+}
+
+#[test]
+fn test_rename_tag_everywhere() {
+    // Setup
+    let store = setup_store();
+
+    // Invoke
+    let changed = store.rename_tag("Resturant", " Restaurant ").unwrap();
+
+    // Analysis
+    assert_eq!(changed, 2);
+    assert_eq!(
+        tags_of(&store, "Pizza place"),
+        strings(&["Restaurant", "Dining"])
+    );
+    // Already had the new name, it is kept once
+    assert_eq!(tags_of(&store, "Cafe"), strings(&["Restaurant"]));
+    let tags = store.list_tags().unwrap();
+    assert!(tags.iter().all(|tag| tag.tag != "Resturant"));
+    assert_eq!(store.get_expenses_with_tag("Restaurant").unwrap().len(), 3);
+}
+
+#[test]
+fn test_merge_tags_into_one() {
+    // Setup
+    let store = setup_store();
+
+    // Invoke
+    let changed = store
+        .merge_tags(&strings(&["Resturant", "Dining", "Restaurant"]), "Food")
+        .unwrap();
+
+    // Analysis
+    assert_eq!(changed, 3);
+    assert_eq!(tags_of(&store, "Pizza place"), strings(&["Food"]));
+    assert_eq!(tags_of(&store, "Bistro"), strings(&["Food"]));
+    assert_eq!(tags_of(&store, "Salary"), strings(&["Income"]));
+}
+
+#[test]
+fn test_delete_tag_with_and_without_replacement() {
+    // Setup
+    let store = setup_store();
+
+    // Invoke
+    let deleted = store.delete_tag("Dining", None).unwrap();
+    let replaced = store.delete_tag("Resturant", Some("Eating out")).unwrap();
+
+    // Analysis
+    assert_eq!(deleted, 1);
+    assert_eq!(replaced, 2);
+    assert_eq!(tags_of(&store, "Pizza place"), strings(&["Eating out"]));
+    assert_eq!(
+        tags_of(&store, "Cafe"),
+        strings(&["Eating out", "Restaurant"])
+    );
+}
+
+#[test]
+fn test_ledger_tag_accounts_follow_renamed_tags() {
+    // Setup
+    let store = setup_store();
+    let mut config = LedgerConfig::default();
+    for (tag, account) in [
+        ("Resturant", "Expenses:Food:Resturant"),
+        ("Restaurant", "Expenses:Food:Restaurant"),
+        ("Dining", "Expenses:Food:Dining"),
+        ("Income", "Income:Salary"),
+    ] {
+        config
+            .tag_accounts
+            .insert(tag.to_string(), account.to_string());
+    }
+    store
+        .set_json_value(
+            LEDGER_CONFIG_SUB_KEY,
+            serde_json::to_value(&config).unwrap(),
+        )
+        .unwrap();
+
+    // Invoke
+    store.rename_tag("Dining", "Eating out").unwrap();
+    store
+        .merge_tags(&strings(&["Resturant", "Restaurant"]), "Eating out")
+        .unwrap();
+    store.delete_tag("Income", None).unwrap();
+
+    // Analysis
+    let config = parse_ledger_config(store.get_json_value(LEDGER_CONFIG_SUB_KEY).unwrap()).unwrap();
+    assert_eq!(
+        config.tag_accounts.into_iter().collect::<Vec<_>>(),
+        vec![("Eating out".to_string(), "Expenses:Food:Dining".to_string())]
+    );
+}
+
+#[test]
+fn test_unknown_tag_changes_nothing() {
+    // Setup
+    let store = setup_store();
+    let before = store.get_all_expenses().unwrap();
+
+    // Invoke
+    let changed = store.rename_tag("Groceries", "Food").unwrap();
+
+    // Analysis
+    assert_eq!(changed, 0);
+    let mut after = store.get_all_expenses().unwrap();
+    let mut before = before;
+    before.sort_by(|a, b| a.get_id().cmp(b.get_id()));
+    after.sort_by(|a, b| a.get_id().cmp(b.get_id()));
+    assert_eq!(before, after);
+}
+
+#[test]
+fn test_invalid_target_is_rejected() {
+    // Setup
+    let store = setup_store();
+
+    // Invoke
+    let empty = store.rename_tag("Resturant", "  ");
+    let itself = store.merge_tags(&strings(&["Restaurant"]), "Restaurant");
+
+    // Analysis
+    assert!(empty.is_err());
+    assert!(itself.is_err());
+    assert_eq!(
+        tags_of(&store, "Cafe"),
+        strings(&["Resturant", "Restaurant"])
+    );
+}
+
+#[test]
+fn test_source_names_are_normalized() {
+    // Setup
+    let store = setup_store();
+
+    // Invoke
+    let empty_source = store.merge_tags(&strings(&["Resturant", " "]), "Food");
+    let merged = store
+        .merge_tags(
+            &strings(&[" Resturant", "Resturant ", "Restaurant  "]),
+            " Restaurant",
+        )
+        .unwrap();
+    let itself = store.merge_tags(&strings(&[" Restaurant "]), "Restaurant");
+    let deleted = store.delete_tag(" Dining\t", None).unwrap();
+
+    // Analysis
+    assert!(empty_source.is_err());
+    assert!(itself.is_err());
+    assert_eq!(merged, 2);
+    assert_eq!(deleted, 1);
+    assert_eq!(tags_of(&store, "Pizza place"), strings(&["Restaurant"]));
+    assert_eq!(tags_of(&store, "Cafe"), strings(&["Restaurant"]));
+}
+
+#[test]
+fn test_rename_tag_is_undone_at_once() {
+    // Setup
+    let store = setup_store();
+    run_as_command("rename_tag", || {
+        store.rename_tag("Resturant", "Restaurant").unwrap();
+    });
+
+    // Invoke
+    let undone = store.undo().unwrap().unwrap();
+
+    // Analysis
+    assert_eq!(undone.command, "rename_tag");
+    assert_eq!(
+        tags_of(&store, "Pizza place"),
+        strings(&["Resturant", "Dining"])
+    );
+    assert_eq!(
+        tags_of(&store, "Cafe"),
+        strings(&["Resturant", "Restaurant"])
+    );
+}
+
+#[test]
+fn test_replace_tags_keeps_order() {
+    // Setup
+    let tags = strings(&["Travel", "Hotel", "Work"]);
+
+    // Invoke
+    let merged = replace_tags(&tags, &strings(&["Hotel", "Work"]), Some("Lodging"));
+    let removed = replace_tags(&tags, &strings(&["Travel"]), None);
+    let untouched = replace_tags(&tags, &strings(&["Food"]), Some("Dining"));
+
+    // Analysis
+    assert_eq!(merged, Some(strings(&["Travel", "Lodging"])));
+    assert_eq!(removed, Some(strings(&["Hotel", "Work"])));
+    assert_eq!(untouched, None);
+}
@@ -0,0 +1,82 @@
+use tauri_app_lib::store::text_index::{tokenize_text, TextIndex};
+
+/// Helper function to set up an index for test
+///
+/// Returns:
+/// `TextIndex` An index over a few English and Hebrew descriptions
+fn setup_index() -> TextIndex {
+    let mut index = TextIndex::default();
+    index.add("coffee", "Blue Bottle Coffee");
+    index.add("coffee_2", "Blue Bottle Coffee Oakland");
+    index.add("bottle_shop", "Bottle shop");
+    index.add("rami_levy", "רמי לוי שיווק השקמה");
+    index.add("super", "סופר-פארם ברמת גן");
+    index
+}
+
+#[test]
+fn test_tokenize_text_normalizes_hebrew() {
+    // Invoke
+    let result = tokenize_text("שָׁלוֹם, צה״ל & Mario's CAFÉ");
+
+    // Analysis
+    assert_eq!(result, vec!["שלומ", "צהל", "marios", "café"]);
+}
+
+#[test]
+fn test_search_ranks_exact_match_first() {
+    // Setup
+    let index = setup_index();
+
+    // Invoke
+    let result = index.search("blue bottle coffee", 10);
+
+    // Analysis
+    let ids: Vec<&str> = result.iter().map(|(id, _)| id.as_str()).collect();
+    assert_eq!(
+        ids,
+        vec!["coffee", "coffee_2"],
+        "Shorter descriptions rank higher"
+    );
+}
+
+#[test]
+fn test_search_prefix_and_fuzzy() {
+    // Setup
+    let index = setup_index();
+
+    // Invoke
+    let prefix = index.search("bot", 10);
+    let fuzzy = index.search("cofee", 10);
+
+    // Analysis
+    assert_eq!(prefix.len(), 3);
+    assert_eq!(fuzzy.len(), 2);
+}
+
+#[test]
+fn test_search_hebrew_final_letters_and_prefix_letters() {
+    // Setup
+    let index = setup_index();
+
+    // Invoke
+    let final_letter = index.search("השקמ", 10);
+    let without_prefix = index.search("רמת", 10);
+
+    // Analysis
+    assert_eq!(final_letter[0].0, "rami_levy");
+    assert_eq!(without_prefix[0].0, "super", "ברמת is also indexed as רמת");
+}
+
+#[test]
+fn test_remove_updates_index() {
+    // Setup
+    let mut index = setup_index();
+
+    // Invoke
+    index.remove("bottle_shop", "Bottle shop");
+
+    // Analysis
+    assert!(index.search("shop", 10).is_empty());
+    assert_eq!(index.search("bottle", 10).len(), 2);
+}
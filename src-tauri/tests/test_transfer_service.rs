@@ -0,0 +1,216 @@
+use std::collections::HashSet;
+use tauri_app_lib::model::transfer::{transfer_pair_id, TransferStatus};
+use tauri_app_lib::service::transfer_service::find_transfer_candidates;
+
+mod common;
+use common::{as_stored, setup_expense};
+
+#[test]
+fn test_find_transfer_candidates_pairs_card_payment() {
+    // Setup
+    let expenses = vec![
+        as_stored(
+            setup_expense("Expense checking", 250.0, 3, 10, &[]),
+            "checking",
+            Some("NavyFederal"),
+        ),
+        as_stored(
+            setup_expense("Expense card", -250.0, 3, 12, &[]),
+            "card",
+            Some("CapitalOne"),
+        ),
+        as_stored(
+            setup_expense("Expense groceries", 250.0, 3, 11, &[]),
+            "groceries",
+            Some("CapitalOne"),
+        ),
+    ];
+
+    // Invoke
+    let result = find_transfer_candidates(&expenses, 5, &HashSet::new());
+
+    // Analysis
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].outflow_id, "checking");
+    assert_eq!(result[0].inflow_id, "card");
+    assert_eq!(result[0].status, TransferStatus::Suggested);
+}
+
+#[test]
+fn test_find_transfer_candidates_same_source_ignored() {
+    // Setup
+    let expenses = vec![
+        as_stored(
+            setup_expense("Expense purchase", 40.0, 3, 10, &[]),
+            "purchase",
+            Some("Amex"),
+        ),
+        as_stored(
+            setup_expense("Expense refund", -40.0, 3, 11, &[]),
+            "refund",
+            Some("Amex"),
+        ),
+    ];
+
+    // Invoke
+    let result = find_transfer_candidates(&expenses, 5, &HashSet::new());
+
+    // Analysis
+    assert!(
+        result.is_empty(),
+        "Refunds on the same account are not transfers"
+    );
+}
+
+#[test]
+fn test_find_transfer_candidates_outside_window() {
+    // Setup
+    let expenses = vec![
+        as_stored(
+            setup_expense("Expense checking", 100.0, 3, 1, &[]),
+            "checking",
+            Some("NavyFederal"),
+        ),
+        as_stored(
+            setup_expense("Expense card", -100.0, 3, 20, &[]),
+            "card",
+            Some("CapitalOne"),
+        ),
+    ];
+
+    // Invoke
+    let result = find_transfer_candidates(&expenses, 5, &HashSet::new());
+
+    // Analysis
+    assert!(result.is_empty());
+}
+
+#[test]
+fn test_find_transfer_candidates_prefers_closest_date() {
+    // Setup
+    let expenses = vec![
+        as_stored(
+            setup_expense("Expense checking", 75.5, 3, 10, &[]),
+            "checking",
+            Some("NavyFederal"),
+        ),
+        as_stored(
+            setup_expense("Expense card_far", -75.5, 3, 14, &[]),
+            "card_far",
+            Some("CapitalOne"),
+        ),
+        as_stored(
+            setup_expense("Expense card_near", -75.5, 3, 11, &[]),
+            "card_near",
+            Some("Amex"),
+        ),
+    ];
+
+    // Invoke
+    let result = find_transfer_candidates(&expenses, 5, &HashSet::new());
+
+    // Analysis
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].inflow_id, "card_near");
+}
+
+#[test]
+fn test_find_transfer_candidates_skips_rejected_pair() {
+    // Setup
+    let expenses = vec![
+        as_stored(
+            setup_expense("Expense checking", 75.5, 3, 10, &[]),
+            "checking",
+            Some("NavyFederal"),
+        ),
+        as_stored(
+            setup_expense("Expense card_far", -75.5, 3, 14, &[]),
+            "card_far",
+            Some("CapitalOne"),
+        ),
+        as_stored(
+            setup_expense("Expense card_near", -75.5, 3, 11, &[]),
+            "card_near",
+            Some("Amex"),
+        ),
+    ];
+    let rejected: HashSet<String> = [transfer_pair_id("checking", "card_near")]
+        .into_iter()
+        .collect();
+
+    // Invoke
+    let result = find_transfer_candidates(&expenses, 5, &rejected);
+
+    // Analysis
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].inflow_id, "card_far");
+}
+
+#[test]
+fn test_find_transfer_candidates_skips_linked_expenses() {
+    // Setup
+    let mut linked = as_stored(
+        setup_expense("Expense checking", 60.0, 3, 10, &[]),
+        "checking",
+        Some("NavyFederal"),
+    );
+    linked.set_transfer_id(Some("existing".to_string()));
+    let expenses = vec![
+        linked,
+        as_stored(
+            setup_expense("Expense card", -60.0, 3, 10, &[]),
+            "card",
+            Some("CapitalOne"),
+        ),
+    ];
+
+    // Invoke
+    let result = find_transfer_candidates(&expenses, 5, &HashSet::new());
+
+    // Analysis
+    assert!(result.is_empty());
+}
+
+#[test]
+fn test_find_transfer_candidates_pairs_expenses_without_source() {
+    // Setup
+    let expenses = vec![
+        as_stored(
+            setup_expense("Card payment", 300.0, 3, 10, &[]),
+            "legacy_outflow",
+            None,
+        ),
+        as_stored(
+            setup_expense("Payment received", -300.0, 3, 10, &[]),
+            "legacy_inflow",
+            None,
+        ),
+        as_stored(
+            setup_expense("Expense checking", 80.0, 3, 11, &[]),
+            "checking",
+            Some("NavyFederal"),
+        ),
+        as_stored(
+            setup_expense("Expense card", -80.0, 3, 11, &[]),
+            "card",
+            Some("CapitalOne"),
+        ),
+        as_stored(
+            setup_expense("Expense refund", -80.0, 3, 11, &[]),
+            "refund",
+            Some("NavyFederal"),
+        ),
+    ];
+
+    // Invoke
+    let result = find_transfer_candidates(&expenses, 5, &HashSet::new());
+
+    // Analysis
+    let ids: Vec<(&str, &str)> = result
+        .iter()
+        .map(|pair| (pair.outflow_id.as_str(), pair.inflow_id.as_str()))
+        .collect();
+    assert_eq!(ids.len(), 2);
+    assert!(ids.contains(&("legacy_outflow", "legacy_inflow")));
+    assert!(ids.contains(&("checking", "card")));
+}
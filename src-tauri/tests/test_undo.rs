@@ -0,0 +1,223 @@
+use serde_json::json;
+use tauri_app_lib::model::expense::Expense;
+use tauri_app_lib::store::app_store::ExpenseStore;
+use tauri_app_lib::store::command_context::run_as_command;
+use tauri_app_lib::store::memory_backend::MemoryBackend;
+use tauri_app_lib::store::transaction::ConflictError;
+
+mod common;
+use common::setup_expense;
+
+/// Helper function to set up a store holding the given expenses, returned as stored
+fn setup_store(expenses: Vec<Expense>) -> (ExpenseStore<MemoryBackend>, Vec<Expense>) {
+    let store = ExpenseStore::new(MemoryBackend::new());
+    store.add_expense_as_batch(expenses, false).unwrap();
+    let mut stored = store.get_all_expenses().unwrap();
+    stored.sort_by(|a, b| a.get_date().cmp(b.get_date()));
+    (store, stored)
+}
+
+fn tag_expense(store: &ExpenseStore<MemoryBackend>, expense: &Expense, tag: &str) {
+    let mut edited = store
+        .get_expense(&expense.get_id().to_string())
+        .unwrap()
+        .unwrap();
+    edited.add_tag(tag);
+    run_as_command("update_expense", || {
+        store
+            .update_expense(expense.get_id().to_string(), edited)
+            .unwrap();
+    });
+}
+
+#[test]
+fn test_undo_and_redo_update() {
+    // Setup
+    let (store, expenses) = setup_store(vec![setup_expense("Bookstore", 25.0, 5, 2, &[])]);
+    let id = expenses[0].get_id().to_string();
+    tag_expense(&store, &expenses[0], "Books");
+
+    // Invoke
+    let undone = store.undo().unwrap().unwrap();
+    let after_undo = store.get_expense(&id).unwrap().unwrap();
+    let redone = store.redo().unwrap().unwrap();
+    let after_redo = store.get_expense(&id).unwrap().unwrap();
+
+    // Analysis
+    assert_eq!(undone.command, "update_expense");
+    assert_eq!(undone.keys, vec!["expenses".to_string()]);
+    assert!(after_undo.get_tags().is_empty());
+    assert_eq!(redone.command, "update_expense");
+    assert_eq!(after_redo.get_tags(), &vec!["Books".to_string()]);
+    assert!(store.redo().unwrap().is_none());
+}
+
+#[test]
+fn test_nothing_to_undo() {
+    // Setup
+    let (store, _) = setup_store(vec![setup_expense("Bookstore", 25.0, 5, 2, &[])]);
+
+    // Invoke
+    let undone = store.undo().unwrap();
+
+    // Analysis
+    // Adding from a CSV import is not an undoable command
+    assert!(undone.is_none());
+    assert!(store.undo_state().unwrap().undo_command.is_none());
+}
+
+#[test]
+fn test_restoring_data_clears_history() {
+    // Setup
+    let (store, expenses) = setup_store(vec![
+        setup_expense("Bookstore", 25.0, 5, 2, &[]),
+        setup_expense("Cafe", 4.5, 5, 3, &[]),
+    ]);
+    let snapshot = store.get_all_store_data().unwrap();
+    tag_expense(&store, &expenses[0], "Books");
+    tag_expense(&store, &expenses[1], "Coffee");
+    store.undo().unwrap();
+
+    // Invoke
+    store.replace_all_data(snapshot).unwrap();
+
+    // Analysis
+    let state = store.undo_state().unwrap();
+    assert!(state.undo_command.is_none());
+    assert!(state.redo_command.is_none());
+    assert!(store.undo().unwrap().is_none());
+}
+
+#[test]
+fn test_undo_groups_one_invocation() {
+    // Setup
+    let (store, expenses) = setup_store(vec![
+        setup_expense("Bookstore", 25.0, 5, 2, &[]),
+        setup_expense("Bakery", 4.5, 5, 3, &[]),
+    ]);
+
+    // Invoke
+    run_as_command("remove_expense", || {
+        store
+            .remove_expense(&expenses[0].get_id().to_string())
+            .unwrap();
+        store
+            .remove_expense(&expenses[1].get_id().to_string())
+            .unwrap();
+    });
+    store.undo().unwrap().unwrap();
+
+    // Analysis
+    assert_eq!(store.get_all_expenses().unwrap().len(), 2);
+    assert!(store.undo().unwrap().is_none());
+}
+
+#[test]
+fn test_undo_steps_in_reverse_order() {
+    // Setup
+    let (store, expenses) = setup_store(vec![setup_expense("Bookstore", 25.0, 5, 2, &[])]);
+    let id = expenses[0].get_id().to_string();
+    tag_expense(&store, &expenses[0], "Books");
+    tag_expense(&store, &expenses[0], "Gifts");
+
+    // Invoke
+    store.undo().unwrap().unwrap();
+    let after_first = store.get_expense(&id).unwrap().unwrap();
+    store.undo().unwrap().unwrap();
+    let after_second = store.get_expense(&id).unwrap().unwrap();
+
+    // Analysis
+    assert_eq!(after_first.get_tags(), &vec!["Books".to_string()]);
+    assert!(after_second.get_tags().is_empty());
+    assert_eq!(
+        store.undo_state().unwrap().redo_command.as_deref(),
+        Some("update_expense")
+    );
+}
+
+#[test]
+fn test_undo_conflicts_when_changed_since() {
+    // Setup
+    let (store, expenses) = setup_store(vec![setup_expense("Bookstore", 25.0, 5, 2, &[])]);
+    let id = expenses[0].get_id().to_string();
+    tag_expense(&store, &expenses[0], "Books");
+    // Not undoable itself, but changes the expense after the tagging
+    let mut edited = store.get_expense(&id).unwrap().unwrap();
+    edited.set_description("Bookstore downtown");
+    store.update_expense(id.clone(), edited).unwrap();
+
+    // Invoke
+    let err = store.undo().unwrap_err();
+
+    // Analysis
+    let conflict = err.downcast_ref::<ConflictError>().unwrap();
+    assert_eq!(conflict.ids, vec![id.clone()]);
+    let current = store.get_expense(&id).unwrap().unwrap();
+    assert_eq!(current.get_description(), "Bookstore downtown");
+    // The step stays available
+    assert!(store.undo_state().unwrap().undo_command.is_some());
+}
+
+#[test]
+fn test_new_change_clears_redo() {
+    // Setup
+    let (store, expenses) = setup_store(vec![setup_expense("Bookstore", 25.0, 5, 2, &[])]);
+    tag_expense(&store, &expenses[0], "Books");
+    store.undo().unwrap().unwrap();
+
+    // Invoke
+    tag_expense(&store, &expenses[0], "Gifts");
+
+    // Analysis
+    assert!(store.redo().unwrap().is_none());
+}
+
+#[test]
+fn test_undo_remove_restores_transfer() {
+    // Setup
+    let (store, expenses) = setup_store(vec![
+        setup_expense("Transfer to savings", -100.0, 5, 2, &[]),
+        setup_expense("Transfer from checking", 100.0, 5, 2, &[]),
+    ]);
+    let first = expenses[0].get_id().to_string();
+    let second = expenses[1].get_id().to_string();
+    store.link_transfer(&first, &second).unwrap();
+
+    // Invoke
+    run_as_command("remove_expense", || {
+        store.remove_expense(&first).unwrap();
+    });
+    let undone = store.undo().unwrap().unwrap();
+
+    // Analysis
+    assert!(undone.keys.contains(&"transfers".to_string()));
+    assert_eq!(store.get_transfers().unwrap().len(), 1);
+    let restored = store.get_expense(&first).unwrap().unwrap();
+    let partner = store.get_expense(&second).unwrap().unwrap();
+    assert!(restored.get_transfer_id().is_some());
+    assert_eq!(restored.get_transfer_id(), partner.get_transfer_id());
+}
+
+#[test]
+fn test_undo_set_json_value() {
+    // Setup
+    let store = ExpenseStore::new(MemoryBackend::new());
+    store
+        .set_json_value("forecast_config", json!({"months": 3}))
+        .unwrap();
+    run_as_command("store_set_json_value", || {
+        store
+            .set_json_value("forecast_config", json!({"months": 6}))
+            .unwrap();
+    });
+
+    // Invoke
+    let undone = store.undo().unwrap().unwrap();
+
+    // Analysis
+    assert_eq!(undone.keys, vec!["forecast_config".to_string()]);
+    assert_eq!(
+        store.get_json_value("forecast_config").unwrap(),
+        Some(json!({"months": 3}))
+    );
+}
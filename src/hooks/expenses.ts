@@ -13,7 +13,8 @@ export const useExpenses = () => {
       value?.filter((e) => {
         const isIncome = e.tags.includes(NonExpenseTags.Income);
         const isSavings = e.tags.includes(NonExpenseTags.Savings);
-        return !isIncome && !isSavings;
+        const isTransfer = !!e.transfer_id;
+        return !isIncome && !isSavings && !isTransfer;
       }) ?? [],
     [value]
   );
@@ -47,7 +48,9 @@ export const useIncome = () => {
 
   const expenses = useMemo(
     () =>
-      value?.filter((e) => e.tags.includes(NonExpenseTags.Income)) ?? [],
+      value?.filter(
+        (e) => e.tags.includes(NonExpenseTags.Income) && !e.transfer_id
+      ) ?? [],
     [value]
   );
   return expenses;
@@ -71,9 +74,13 @@ export const useFilteredIncome = () => {
 export const useSavings = () => {
   const { value } = useExpensesStore();
 
-  const savings = useMemo(() => {
-    return value?.filter((e) => e.tags.includes(NonExpenseTags.Savings)) ?? [];
-  }, [value]);
+  const savings = useMemo(
+    () =>
+      value?.filter(
+        (e) => e.tags.includes(NonExpenseTags.Savings) && !e.transfer_id
+      ) ?? [],
+    [value]
+  );
   return savings;
 };
 
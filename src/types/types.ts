@@ -16,7 +16,40 @@ export enum API {
   PreviewParseCSV = "preview_csv_parse",
   ExportAllData = "export_all_data",
   ImportAllData = "import_all_data",
+  MigrateStore = "migrate_store",
+  CheckImportMigrations = "check_import_migrations",
+  ExportExpenses = "export_expenses",
+  GetLedgerConfig = "get_ledger_config",
+  SetLedgerConfig = "set_ledger_config",
+  ExportJournal = "export_journal",
+  ImportJournal = "import_journal",
   ReadTextFile = "read_text_file",
+  QueryExpenses = "query_expenses",
+  ParseSearchQuery = "parse_search_query",
+  SearchDescriptions = "search_descriptions",
+  ListTags = "list_tags",
+  RenameTag = "rename_tag",
+  MergeTags = "merge_tags",
+  DeleteTag = "delete_tag",
+  AggregateExpenses = "aggregate_expenses",
+  PivotExpenses = "pivot_expenses",
+  PivotExpensesCsv = "pivot_expenses_csv",
+  ListBackups = "list_backups",
+  InspectBackup = "inspect_backup",
+  RestoreBackup = "restore_backup",
+  GetBackupRetention = "get_backup_retention",
+  SetBackupRetention = "set_backup_retention",
+  GetEncryptionStatus = "get_encryption_status",
+  UnlockStore = "unlock_store",
+  LockStore = "lock_store",
+  EnableEncryption = "enable_encryption",
+  ChangePassphrase = "change_passphrase",
+  DisableEncryption = "disable_encryption",
+  GetExpenseHistory = "get_expense_history",
+  RevertExpense = "revert_expense",
+  Undo = "undo",
+  Redo = "redo",
+  GetUndoState = "get_undo_state",
 }
 
 export enum KnownStoreKeys {
@@ -32,6 +65,7 @@ export enum KnownStoreKeys {
   SsdiPayPeriods = "ssdi_pay_periods",
   SsdiConfig = "ssdi_config",
   ImportHistory = "import_history",
+  LedgerConfig = "ledger_config",
 }
 
 export type ForecastConfigData = {
@@ -60,10 +94,60 @@ export type ForecastConfigData = {
 
 export type Expense = {
   id: string;
+  fingerprint?: string;
   amount: number;
   tags: Tag[];
   date: string;
   description: string;
+  source?: string;
+  transfer_id?: string;
+  revision?: number;
+};
+
+export type TagSummary = {
+  tag: string;
+  count: number;
+  total: number;
+};
+
+export type ExpenseFilter = {
+  startDate?: string;
+  endDate?: string;
+  tagsAny?: Tag[];
+  tagsAll?: Tag[];
+  tagsNone?: Tag[];
+  minAmount?: number;
+  maxAmount?: number;
+  text?: string;
+  regex?: string;
+  sign?: "positive" | "negative";
+  search?: string;
+};
+
+export type DescriptionMatch = {
+  expense: Expense;
+  score: number;
+};
+
+export type SearchParseError = {
+  position: number;
+  message: string;
+};
+
+export type ExpenseQuery = {
+  filter?: ExpenseFilter;
+  sortBy?: "date" | "amount" | "description";
+  sortOrder?: "asc" | "desc";
+  page?: number;
+  pageSize?: number;
+};
+
+export type ExpensePage = {
+  expenses: Expense[];
+  totalCount: number;
+  totalAmount: number;
+  page: number;
+  pageSize: number;
 };
 
 export type StoreExpenseMap = {
@@ -76,6 +160,38 @@ export enum Mode {
   YEARLY = "YEARLY",
 }
 
+export type PivotDimension =
+  | "tag"
+  | "month"
+  | "year"
+  | "weekday"
+  | "source"
+  | "amountBand";
+
+export type PivotMeasure = "sum" | "count" | "avg" | "min" | "max";
+
+export type PivotTable = {
+  rows: PivotDimension;
+  columns: PivotDimension;
+  measure: PivotMeasure;
+  rowLabels: string[];
+  columnLabels: string[];
+  cells: (number | null)[][];
+  rowTotals: number[];
+  columnTotals: number[];
+  grandTotal: number;
+};
+
+export type AggregateBucket = {
+  key: string;
+  start: string;
+  tag?: string;
+  total: number;
+  count: number;
+  average: number;
+  median: number;
+};
+
 export enum ExpenseTag {
   Food = "Food",
   Utilities = "Utilities",
@@ -215,3 +331,143 @@ export type SsdiConfig = {
 };
 
 export type ImportHistory = string[];
+
+export type BackupReason =
+  | "startup"
+  | "import"
+  | "importAll"
+  | "restore"
+  | "unlock";
+
+export type BackupInfo = {
+  id: string;
+  createdAt: string;
+  reason: BackupReason;
+  sizeBytes: number;
+};
+
+export type BackupDetails = {
+  info: BackupInfo;
+  version: number;
+  keys: { key: string; entries: number }[];
+  expenseCount: number;
+  firstExpenseDate?: string;
+  lastExpenseDate?: string;
+};
+
+export type RetentionPolicy = {
+  daily: number;
+  weekly: number;
+  monthly: number;
+};
+
+export type EncryptionStatus = {
+  supported: boolean;
+  enabled: boolean;
+  locked: boolean;
+};
+
+/** Status of every command but the encryption ones while the store is locked */
+export const STATUS_LOCKED = 423;
+
+export type AuditAction = "create" | "update" | "delete";
+
+export type AuditEntry = {
+  id: number;
+  expenseId: string;
+  timestamp: string;
+  command: string;
+  bulk: boolean;
+  action: AuditAction;
+  before: Expense | null;
+  after: Expense | null;
+};
+
+export type UndoResult = {
+  command: string;
+  keys: string[];
+};
+
+export type UndoState = {
+  undoCommand: string | null;
+  redoCommand: string | null;
+};
+
+export type MigrationStepReport = {
+  version: number;
+  description: string;
+  changes: string[];
+};
+
+export type MigrationReport = {
+  fromVersion: number;
+  toVersion: number;
+  dryRun: boolean;
+  steps: MigrationStepReport[];
+  keys: string[];
+};
+
+export type ImportMode = "replace" | "merge" | "onlyMissing";
+
+export type KeyImportReport = {
+  key: string;
+  status: "imported" | "unchanged" | "skipped";
+  added: number;
+  updated: number;
+  removed: number;
+  unchanged: number;
+  skipped: number;
+  message: string | null;
+};
+
+export type ImportReport = {
+  mode: ImportMode;
+  keys: KeyImportReport[];
+};
+
+export type ExportFormat = "expenseTrackerBackup" | "csv" | "json" | "ofx" | "qif";
+
+export type ExportColumn =
+  | "id"
+  | "date"
+  | "description"
+  | "amount"
+  | "tags"
+  | "source"
+  | "transferId";
+
+export type ExportSummary = {
+  path: string;
+  format: ExportFormat;
+  count: number;
+};
+
+export type JournalFormat = "ledger" | "hledger" | "beancount";
+
+export type LedgerConfig = {
+  currency: string;
+  tagAccounts: Record<string, string>;
+  sourceAccounts: Record<string, string>;
+  defaultExpenseAccount: string;
+  defaultIncomeAccount: string;
+  defaultSourceAccount: string;
+  transferAccount: string;
+};
+
+export type JournalSummary = {
+  path: string;
+  format: JournalFormat;
+  count: number;
+};
+
+export type JournalWarning = {
+  line: number;
+  message: string;
+};
+
+export type JournalImportSummary = {
+  transactions: number;
+  addedCount: number;
+  duplicateCount: number;
+  warnings: JournalWarning[];
+};
@@ -1,4 +1,4 @@
-import { Expense, API, Response } from "@/types/types";
+import { Expense, API, ImportMode, Response } from "@/types/types";
 import { save, open } from "@tauri-apps/plugin-dialog";
 import { invoke } from "@tauri-apps/api/core";
 
@@ -62,7 +62,9 @@ export async function exportAllData(): Promise<string | null> {
   return path;
 }
 
-export async function importAllData(): Promise<string[]> {
+export async function importAllData(
+  mode: ImportMode = "replace"
+): Promise<string[]> {
   const path = await open({
     multiple: false,
     filters: [{ name: "JSON", extensions: ["json"] }],
@@ -82,7 +84,7 @@ export async function importAllData(): Promise<string[]> {
 
   const response: Response<{ imported_keys: string[] }> = await invoke(
     API.ImportAllData,
-    { data: parsed }
+    { data: parsed, mode }
   );
 
   if (response.status !== 200) {